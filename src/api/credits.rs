@@ -0,0 +1,79 @@
+use crate::model::error::GlassBoxError;
+use crate::model::person::Credit;
+use reqwest::Client;
+use serde::Deserialize;
+
+// --- TVMAZE JSON STRUCTURES ---
+#[derive(Deserialize, Debug)]
+struct TvMazePerson {
+    id: u64,
+    name: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct TvMazeCharacter {
+    name: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct TvMazeCastItem {
+    person: TvMazePerson,
+    character: Option<TvMazeCharacter>,
+}
+
+#[derive(Deserialize, Debug)]
+struct TvMazeCrewItem {
+    #[serde(rename = "type")]
+    kind: String, // "Creator", "Executive Producer", ...
+    person: TvMazePerson,
+}
+
+// Only the top of the cast list is billed; the rest is mostly guest noise
+const MAX_CAST: usize = 10;
+
+// Crew roles worth treating as a creative fingerprint
+const CREW_ROLES: &[&str] = &["Creator", "Developer", "Showrunner", "Director", "Writer"];
+
+// --- PUBLIC FUNCTION ---
+// Fetches /shows/:id/cast and /shows/:id/crew and flattens them into credits
+pub async fn fetch_credits(show_id: &str) -> Result<Vec<Credit>, GlassBoxError> {
+    let client = Client::new();
+
+    let cast_url = format!("https://api.tvmaze.com/shows/{}/cast", show_id);
+    let resp = client.get(&cast_url).send().await?;
+    if !resp.status().is_success() {
+        return Err(GlassBoxError::HttpStatus(resp.status().as_u16()));
+    }
+    let cast: Vec<TvMazeCastItem> = resp.json().await?;
+
+    let crew_url = format!("https://api.tvmaze.com/shows/{}/crew", show_id);
+    let resp = client.get(&crew_url).send().await?;
+    if !resp.status().is_success() {
+        return Err(GlassBoxError::HttpStatus(resp.status().as_u16()));
+    }
+    let crew: Vec<TvMazeCrewItem> = resp.json().await?;
+
+    let mut credits: Vec<Credit> = cast
+        .into_iter()
+        .take(MAX_CAST)
+        .map(|c| Credit {
+            person_id: c.person.id.to_string(),
+            name: c.person.name,
+            role: "Cast".to_string(),
+            character: c.character.map(|ch| ch.name),
+        })
+        .collect();
+
+    credits.extend(
+        crew.into_iter()
+            .filter(|c| CREW_ROLES.iter().any(|r| c.kind.eq_ignore_ascii_case(r)))
+            .map(|c| Credit {
+                person_id: c.person.id.to_string(),
+                name: c.person.name,
+                role: c.kind,
+                character: None,
+            }),
+    );
+
+    Ok(credits)
+}
@@ -0,0 +1,45 @@
+use crate::model::episodes::Episode;
+use crate::model::error::GlassBoxError;
+use chrono::NaiveDate;
+use reqwest::Client;
+use serde::Deserialize;
+
+// --- TVMAZE JSON STRUCTURES ---
+#[derive(Deserialize, Debug)]
+struct TvMazeEpisode {
+    id: u64,
+    name: String,
+    season: u32,
+    number: Option<u32>,     // null for specials
+    airdate: Option<String>, // "2013-06-24", or "" when not announced
+    runtime: Option<u32>,
+}
+
+// --- PUBLIC FUNCTION ---
+// /shows/:id/episodes lists regular episodes in airing order (specials left out)
+pub async fn fetch_episodes(show_id: &str) -> Result<Vec<Episode>, GlassBoxError> {
+    let url = format!("https://api.tvmaze.com/shows/{}/episodes", show_id);
+
+    let client = Client::new();
+    let resp = client.get(&url).send().await?;
+
+    if !resp.status().is_success() {
+        return Err(GlassBoxError::HttpStatus(resp.status().as_u16()));
+    }
+
+    let episodes: Vec<TvMazeEpisode> = resp.json().await?;
+
+    Ok(episodes
+        .into_iter()
+        .filter_map(|e| {
+            Some(Episode {
+                episode_id: e.id.to_string(),
+                season: e.season,
+                number: e.number?,
+                name: e.name,
+                airdate: e.airdate.and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
+                runtime: e.runtime,
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,59 @@
+use crate::api::search::{show_to_video, TvMazeSearchItem, TvMazeShow};
+use crate::model::error::GlassBoxError;
+use crate::model::video::Video;
+use reqwest::Client;
+
+// A show plus the premiere date, which Video doesn't keep but title+year matching needs
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShowCandidate {
+    pub video: Video,
+    pub premiered: Option<String>, // "2013-06-24"
+}
+
+impl ShowCandidate {
+    pub fn year(&self) -> Option<i32> {
+        self.premiered.as_deref()?.get(..4)?.parse().ok()
+    }
+}
+
+fn to_candidate(show: TvMazeShow) -> ShowCandidate {
+    let premiered = show.premiered.clone();
+    ShowCandidate {
+        video: show_to_video(show),
+        premiered,
+    }
+}
+
+// --- PUBLIC FUNCTIONS ---
+// Exact match by IMDb ID ("tt0944947"); Ok(None) when TVMaze doesn't know it
+pub async fn lookup_imdb(imdb_id: &str) -> Result<Option<ShowCandidate>, GlassBoxError> {
+    let url = format!("https://api.tvmaze.com/lookup/shows?imdb={}", imdb_id);
+
+    let client = Client::new();
+    let resp = client.get(&url).send().await?;
+
+    if resp.status().as_u16() == 404 {
+        return Ok(None);
+    }
+    if !resp.status().is_success() {
+        return Err(GlassBoxError::HttpStatus(resp.status().as_u16()));
+    }
+
+    let show: TvMazeShow = resp.json().await?;
+    Ok(Some(to_candidate(show)))
+}
+
+// Same endpoint as search_videos, but keeps the premiere year for disambiguation
+pub async fn search_candidates(title: &str) -> Result<Vec<ShowCandidate>, GlassBoxError> {
+    let url = format!("https://api.tvmaze.com/search/shows?q={}", title);
+
+    let client = Client::new();
+    let resp = client.get(&url).send().await?;
+
+    if !resp.status().is_success() {
+        return Err(GlassBoxError::HttpStatus(resp.status().as_u16()));
+    }
+
+    let results: Vec<TvMazeSearchItem> = resp.json().await?;
+    Ok(results.into_iter().map(|item| to_candidate(item.show)).collect())
+}
@@ -1,2 +1,6 @@
+pub mod credits;
+pub mod episodes;
+pub mod lookup;
+pub mod trending;
 pub mod youtube;
 pub mod search;
\ No newline at end of file
@@ -1,3 +1,5 @@
+use crate::model::error::GlassBoxError;
+use crate::model::graph::link_batch;
 use crate::model::video::Video;
 use serde::Deserialize;
 use reqwest::Client;
@@ -5,12 +7,12 @@ use chrono::Utc;
 
 // --- TVMAZE JSON STRUCTURES ---
 #[derive(Deserialize, Debug)]
-struct TvMazeSearchItem {
-    show: TvMazeShow,
+pub(crate) struct TvMazeSearchItem {
+    pub(crate) show: TvMazeShow,
 }
 
 #[derive(Deserialize, Debug)]
-struct TvMazeShow {
+pub(crate) struct TvMazeShow {
     id: u64,
     name: String,
     summary: Option<String>, // HTML string
@@ -18,6 +20,10 @@ struct TvMazeShow {
     network: Option<TvMazeNetwork>,
     rating: Option<TvMazeRating>,
     genres: Vec<String>,
+    // TVMaze's own 0-100 popularity indicator
+    #[serde(default)]
+    pub(crate) weight: Option<u32>,
+    pub(crate) premiered: Option<String>, // "2013-06-24"
 }
 
 #[derive(Deserialize, Debug)]
@@ -37,39 +43,57 @@ struct TvMazeRating {
 
 // --- PUBLIC FUNCTION ---
 // No #[server] needed! This works directly in the browser.
-pub async fn search_videos(query: &str) -> Result<Vec<Video>, String> {
+pub async fn search_videos(query: &str) -> Result<Vec<Video>, GlassBoxError> {
     let url = format!("https://api.tvmaze.com/search/shows?q={}", query);
     
     let client = Client::new();
-    let resp = client.get(&url).send().await.map_err(|e| e.to_string())?;
+    let resp = client.get(&url).send().await?;
 
     if !resp.status().is_success() {
-        return Err(format!("API Error: {}", resp.status()));
+        return Err(GlassBoxError::HttpStatus(resp.status().as_u16()));
     }
 
-    let results: Vec<TvMazeSearchItem> = resp.json().await.map_err(|e| e.to_string())?;
+    let results: Vec<TvMazeSearchItem> = resp.json().await?;
 
     // Convert to GlassBox "Video" Model
-    let videos = results.into_iter().map(|item| {
-        let s = item.show;
-        
-        // Clean up the summary (Remove <p> tags)
-        let raw_desc = s.summary.unwrap_or("No description".to_string());
-        let clean_desc = raw_desc.replace("<p>", "").replace("</p>", "").replace("<b>", "").replace("</b>", "");
-
-        Video {
-            id: None,
-            video_id: s.id.to_string(),
-            title: s.name,
-            description: clean_desc,
-            thumbnail_url: s.image.map(|i| i.medium).unwrap_or("https://via.placeholder.com/210x295?text=No+Image".to_string()),
-            channel_name: s.network.map(|n| n.name).unwrap_or("Web Series".to_string()),
-            rating: s.rating.and_then(|r| r.average).unwrap_or(0.0),
-            genres: s.genres, // CRITICAL FOR YOUR AI
-            related_ids: vec![], // We will fill this later
-            saved_at: Utc::now(),
-        }
-    }).collect();
+    let mut videos: Vec<Video> = results.into_iter().map(|item| show_to_video(item.show)).collect();
+
+    // Shows returned together that share a network and a genre are related
+    link_batch(&mut videos);
 
     Ok(videos)
-}
\ No newline at end of file
+}
+
+// Single show by TVMaze ID (used to hydrate graph neighbours)
+pub async fn fetch_show(show_id: &str) -> Result<Video, GlassBoxError> {
+    let url = format!("https://api.tvmaze.com/shows/{}", show_id);
+
+    let client = Client::new();
+    let resp = client.get(&url).send().await?;
+
+    if !resp.status().is_success() {
+        return Err(GlassBoxError::HttpStatus(resp.status().as_u16()));
+    }
+
+    let show: TvMazeShow = resp.json().await?;
+    Ok(show_to_video(show))
+}
+
+pub(crate) fn show_to_video(s: TvMazeShow) -> Video {
+    // Clean up the summary (Remove <p> tags)
+    let raw_desc = s.summary.unwrap_or("No description".to_string());
+    let clean_desc = raw_desc.replace("<p>", "").replace("</p>", "").replace("<b>", "").replace("</b>", "");
+
+    Video {
+        id: None,
+        video_id: s.id.to_string(),
+        title: s.name,
+        description: clean_desc,
+        thumbnail_url: s.image.map(|i| i.medium).unwrap_or("https://via.placeholder.com/210x295?text=No+Image".to_string()),
+        channel_name: s.network.map(|n| n.name).unwrap_or("Web Series".to_string()),
+        rating: s.rating.and_then(|r| r.average).unwrap_or(0.0),
+        genres: s.genres, // CRITICAL FOR YOUR AI
+        related_ids: vec![], // Filled by link_batch / graph::enrich_related
+        saved_at: Utc::now(),
+    }
+}
@@ -0,0 +1,74 @@
+use crate::api::search::{show_to_video, TvMazeShow};
+use crate::model::error::GlassBoxError;
+use crate::model::video::Video;
+use reqwest::Client;
+use serde::Deserialize;
+
+// --- TVMAZE JSON STRUCTURES ---
+#[derive(Deserialize, Debug)]
+struct TvMazeScheduleItem {
+    show: TvMazeShow,
+}
+
+// Raw popularity signals for one show, scored in model::trending
+#[derive(Debug, Clone)]
+pub struct PopularShow {
+    pub video: Video,
+    pub weight: u32,               // TVMaze popularity 0-100
+    pub premiered: Option<String>, // "2013-06-24"
+    pub airing: bool,              // Has an episode on today's schedule
+}
+
+// The first pages of the show index hold the long-running, most-followed shows
+const INDEX_PAGES: u32 = 2;
+
+// --- PUBLIC FUNCTION ---
+// Today's /schedule plus the first pages of /shows?page=, merged by show ID
+pub async fn fetch_popular_shows() -> Result<Vec<PopularShow>, GlassBoxError> {
+    let client = Client::new();
+    let mut shows: Vec<PopularShow> = Vec::new();
+
+    let resp = client
+        .get("https://api.tvmaze.com/schedule")
+        .send()
+        .await?;
+    if !resp.status().is_success() {
+        return Err(GlassBoxError::HttpStatus(resp.status().as_u16()));
+    }
+    let schedule: Vec<TvMazeScheduleItem> = resp.json().await?;
+
+    for item in schedule {
+        push_unique(&mut shows, item.show, true);
+    }
+
+    for page in 0..INDEX_PAGES {
+        let url = format!("https://api.tvmaze.com/shows?page={}", page);
+        let resp = client.get(&url).send().await?;
+        if !resp.status().is_success() {
+            return Err(GlassBoxError::HttpStatus(resp.status().as_u16()));
+        }
+        let index: Vec<TvMazeShow> = resp.json().await?;
+
+        for show in index {
+            push_unique(&mut shows, show, false);
+        }
+    }
+
+    Ok(shows)
+}
+
+fn push_unique(shows: &mut Vec<PopularShow>, show: TvMazeShow, airing: bool) {
+    let weight = show.weight.unwrap_or(0);
+    let premiered = show.premiered.clone();
+    let video = show_to_video(show);
+
+    match shows.iter_mut().find(|s| s.video.video_id == video.video_id) {
+        Some(existing) => existing.airing |= airing,
+        None => shows.push(PopularShow {
+            video,
+            weight,
+            premiered,
+            airing,
+        }),
+    }
+}
@@ -0,0 +1,172 @@
+// <------- Offline evaluator: grid / random search over RecommenderConfig ------->
+// Runs natively on an exported archive (Settings -> Export), so tuning never
+// blocks the browser. The best config is written as JSON that Settings ->
+// "Import JSON" accepts as the user's default.
+//
+//   cargo run --bin evaluate -- my-archive.json --pool other-archive.json \
+//       --search random --metric ndcg --trials 50 --out best.json
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    if let Err(msg) = native::run(std::env::args().skip(1).collect()) {
+        eprintln!("ERROR: {}", msg);
+        eprintln!("{}", native::USAGE);
+        std::process::exit(1);
+    }
+}
+
+// The browser build only ships the app; this binary has nothing to do there
+#[cfg(target_arch = "wasm32")]
+fn main() {}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use glassbox::model::archive::load_archive;
+    use glassbox::model::config::RecommenderConfig;
+    use glassbox::model::tuning::{describe, evaluate, evaluate_popularity, grid_search, random_search, temporal_split, Metric, SearchSpace};
+    use glassbox::model::video::Video;
+    use std::path::PathBuf;
+
+    pub const USAGE: &str = "usage: evaluate <archive.json> [--pool <archive.json>]... [--config <config.json>] [--space <space.json>]
+                [--search grid|random] [--metric hit|mrr|ndcg] [--k N] [--holdout F]
+                [--trials N] [--seed N] [--out <best.json>]";
+
+    struct Options {
+        archive: PathBuf,
+        pool: Vec<PathBuf>,
+        config: Option<PathBuf>,
+        space: Option<PathBuf>,
+        random: bool,
+        metric: Metric,
+        k: usize,
+        holdout: f64,
+        trials: usize,
+        seed: u64,
+        out: Option<PathBuf>,
+    }
+
+    fn parse_args(args: Vec<String>) -> Result<Options, String> {
+        let mut archive = None;
+        let mut options = Options {
+            archive: PathBuf::new(),
+            pool: Vec::new(),
+            config: None,
+            space: None,
+            random: false,
+            metric: Metric::default(),
+            k: 10,
+            holdout: 0.2,
+            trials: 30,
+            seed: 42,
+            out: None,
+        };
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if !arg.starts_with("--") {
+                archive = Some(PathBuf::from(arg));
+                continue;
+            }
+            let value = args.next().ok_or(format!("{} needs a value", arg))?;
+            let number = |what: &str| format!("{} expects {}, got '{}'", arg, what, value);
+            match arg.as_str() {
+                "--pool" => options.pool.push(PathBuf::from(&value)),
+                "--config" => options.config = Some(PathBuf::from(&value)),
+                "--space" => options.space = Some(PathBuf::from(&value)),
+                "--out" => options.out = Some(PathBuf::from(&value)),
+                "--search" => {
+                    options.random = match value.as_str() {
+                        "grid" => false,
+                        "random" => true,
+                        _ => return Err(format!("--search is grid or random, got '{}'", value)),
+                    }
+                }
+                "--metric" => {
+                    options.metric = match value.as_str() {
+                        "hit" => Metric::HitRate,
+                        "mrr" => Metric::Mrr,
+                        "ndcg" => Metric::Ndcg,
+                        _ => return Err(format!("--metric is hit, mrr or ndcg, got '{}'", value)),
+                    }
+                }
+                "--k" => options.k = value.parse().map_err(|_| number("a whole number"))?,
+                "--holdout" => options.holdout = value.parse().map_err(|_| number("a fraction"))?,
+                "--trials" => options.trials = value.parse().map_err(|_| number("a whole number"))?,
+                "--seed" => options.seed = value.parse().map_err(|_| number("a whole number"))?,
+                _ => return Err(format!("unknown option {}", arg)),
+            }
+        }
+
+        options.archive = archive.ok_or("no archive given")?;
+        Ok(options)
+    }
+
+    fn read(path: &PathBuf) -> Result<String, String> {
+        std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn run(args: Vec<String>) -> Result<(), String> {
+        let options = parse_args(args)?;
+
+        let archive = load_archive(&options.archive).map_err(|e| format!("{:?}", e))?;
+        // The archive's own settings are the starting point unless a config is given
+        let base = match &options.config {
+            Some(path) => RecommenderConfig::from_json(&read(path)?).map_err(|e| e.to_string())?,
+            None => archive.settings.clone(),
+        };
+        let space: SearchSpace = match &options.space {
+            Some(path) => serde_json::from_str(&read(path)?).map_err(|e| format!("{}: {}", path.display(), e))?,
+            None => SearchSpace::default(),
+        };
+
+        let split = temporal_split(&archive.library, options.holdout)
+            .ok_or("the archive needs at least two titles in its library")?
+            .with_signals(archive.signals());
+
+        // Pool = held-out titles + every title from the other archives as distractors
+        let mut pool: Vec<Video> = split.test.clone();
+        for path in &options.pool {
+            let other = load_archive(path).map_err(|e| format!("{:?}", e))?;
+            for v in other.library {
+                if !pool.iter().any(|p| p.video_id == v.video_id) {
+                    pool.push(v);
+                }
+            }
+        }
+        if pool.len() == split.test.len() {
+            eprintln!("WARNING: No --pool archives, so nothing competes with the held-out titles and every config scores the same.");
+        }
+
+        println!(
+            "{} titles: {} train, {} held out, pool of {}",
+            archive.library.len(),
+            split.train.len(),
+            split.test.len(),
+            pool.len()
+        );
+
+        let results = if options.random {
+            random_search(&base, &space, &split, &pool, options.metric, options.k, options.trials, options.seed)
+        } else {
+            grid_search(&base, &space, &split, &pool, options.metric, options.k)
+        };
+        let current = evaluate(&base, &split, &pool, options.metric, options.k);
+        let baseline = evaluate_popularity(&split, &pool, options.metric, options.k);
+
+        println!("{}: current config {:.3}, popularity baseline {:.3}", options.metric.label(), current, baseline);
+        for (i, trial) in results.iter().take(5).enumerate() {
+            println!("#{} {:.3}  {}", i + 1, trial.score, describe(&trial.config));
+        }
+
+        let best = results.first().ok_or("the search space is empty")?;
+        let json = best.config.to_json().map_err(|e| e.to_string())?;
+        match &options.out {
+            Some(path) => {
+                std::fs::write(path, json).map_err(|e| format!("{}: {}", path.display(), e))?;
+                println!("Best config written to {}", path.display());
+            }
+            None => println!("{}", json),
+        }
+        Ok(())
+    }
+}
@@ -2,9 +2,14 @@ use crate::components::movie_modal::MovieModal;
 use crate::model::video::Video;
 use leptos::prelude::*;
 use leptos_meta::Stylesheet;
+use std::collections::HashMap;
 
 #[component]
-pub fn SearchResults(videos: ReadSignal<Vec<Video>>) -> impl IntoView {
+pub fn SearchResults(
+    videos: ReadSignal<Vec<Video>>,
+    // video_id -> the user's note and tags, shown under the description
+    #[prop(optional)] notes: Option<ReadSignal<HashMap<String, String>>>,
+) -> impl IntoView {
     // State to track which video is selected for modal
     let (selected_video, set_selected_video) = signal(Option::<Video>::None);
 
@@ -16,6 +21,7 @@ pub fn SearchResults(videos: ReadSignal<Vec<Video>>) -> impl IntoView {
                 key=|video| video.video_id.clone()
                 children=move |video| {
                     let video_clone = video.clone();
+                    let video_id = video.video_id.clone();
                     view! {
                         <div
                             class="search-result-card"
@@ -34,13 +40,25 @@ pub fn SearchResults(videos: ReadSignal<Vec<Video>>) -> impl IntoView {
                                         let v_for_save = video_clone.clone();
                                         let v_for_library = video_clone.clone(); // Clone for library save
                                         leptos::task::spawn_local(async move {
-                                            // 1. Save interaction
-                                            if let Ok(thing) = surrealdb::sql::thing(&uid_str) {
-                                                 let _ = crate::model::history::save_interaction(thing, v_for_save, "click".to_string()).await;
+                                            let Ok(thing) = surrealdb::sql::thing(&uid_str) else {
+                                                return;
+                                            };
+                                            // 1. Save interaction (clicking again this session only bumps its count)
+                                            let repeat = match crate::model::history::save_interaction(thing.clone(), v_for_save, "click".to_string()).await {
+                                                Ok(run) => run.count > 1,
+                                                Err(e) => {
+                                                    leptos::logging::error!("SEARCH_RESULTS: Failed to save interaction: {:?}", e);
+                                                    false
+                                                }
+                                            };
+                                            // The show is already cached and linked from the first click
+                                            if repeat {
+                                                return;
                                             }
 
-                                            // 2. Save to Library (Feed)
-                                            match crate::model::video::save_video(v_for_library).await {
+                                            // 2. Cache the show in this user's library. A click on its own only
+                                            // counts with click_weight; the modal's watch states say more.
+                                            match crate::model::video::save_video(thing.clone(), v_for_library).await {
                                                 Ok(v) => {
                                                     leptos::logging::log!("SEARCH_RESULTS: Video saved to library: {}", v.title);
                                                     // Trigger feed update
@@ -48,8 +66,20 @@ pub fn SearchResults(videos: ReadSignal<Vec<Video>>) -> impl IntoView {
                                                         leptos::logging::log!("SEARCH_RESULTS: Incrementing feed trigger...");
                                                         trigger.0.update(|c| *c += 1);
                                                     }
+
+                                                    // 3. Cache cast & crew for the people signal
+                                                    if let Err(e) = crate::model::person::credits_for(std::slice::from_ref(&v.video_id)).await {
+                                                        leptos::logging::error!("SEARCH_RESULTS: Failed to load credits: {:?}", e);
+                                                    }
+
+                                                    // 4. Link it into the related-shows graph
+                                                    let config = crate::model::config::load_config(thing.clone()).await.unwrap_or_default();
+                                                    let library = crate::model::video::get_library(thing.clone()).await.unwrap_or_default();
+                                                    if let Err(e) = crate::model::graph::enrich_related(&thing, &v.video_id, &library, config.session_gap_minutes).await {
+                                                        leptos::logging::error!("SEARCH_RESULTS: Failed to link related shows: {:?}", e);
+                                                    }
                                                 },
-                                                Err(e) => leptos::logging::error!("SEARCH_RESULTS: Failed to save video: {}", e)
+                                                Err(e) => leptos::logging::error!("SEARCH_RESULTS: Failed to save video: {:?}", e)
                                             }
                                         });
                                     }
@@ -80,6 +110,10 @@ pub fn SearchResults(videos: ReadSignal<Vec<Video>>) -> impl IntoView {
                                         video.description
                                     }}
                                 </div>
+
+                                {notes.and_then(|n| n.get().get(&video_id).cloned()).map(|line| view! {
+                                    <div class="result-note">{line}</div>
+                                })}
                             </div>
                         </div>
                     }
@@ -1,8 +1,32 @@
-use crate::api::search::search_videos;
-use crate::model::db::DB;
+use crate::api::search::{fetch_show, search_videos};
+use crate::components::vault_gate::VaultGate;
+use crate::model::config::{load_config, RecommenderConfig};
+use crate::model::episodes::{episodes_for, get_progress, next_episode, show_signal, Episode, ShowSignal};
+use crate::model::error::GlassBoxError;
+use crate::model::graph::graph_candidates;
+use crate::model::history::{get_searches, get_user_interactions, get_user_library};
+use crate::model::next_item::{last_interaction, TransitionModel};
+use crate::model::notes::{get_notes, tag_boost, TagProfile};
+use crate::model::person::{credits_for, PeopleProfile};
+use crate::model::queries::{query_boost, QueryProfile};
+use crate::model::ratings::{get_ratings, with_user_ratings};
+use crate::model::session::SessionState;
 use crate::model::svd::SVD;
+use crate::model::trending::{get_trending, is_trending_query};
 use crate::model::video::Video;
+use crate::model::watchlist::{get_watchlist, state_weights, with_watch_times, WatchEntry, WatchState};
+use chrono::Utc;
+use futures::future::join_all;
 use leptos::prelude::*;
+use std::collections::HashMap;
+use surrealdb::sql::Thing;
+
+// Only the head of the ranked list is worth the extra cast/crew lookups
+const PEOPLE_RERANK_DEPTH: usize = 12;
+const PEOPLE_PROFILE_DEPTH: usize = 10;
+const TRENDING_LIMIT: usize = 40;
+// TVMaze rate-limits bursts, so lookups go out a few at a time
+const FETCH_CONCURRENCY: usize = 6;
 
 #[component]
 pub fn Feed() -> impl IntoView {
@@ -11,7 +35,18 @@ pub fn Feed() -> impl IntoView {
     let loading = RwSignal::new(true);
     let genre_title = RwSignal::new("Your Library".to_string()); // Dynamic title
 
+    // Short-term row driven by the click sequence
+    let session_videos = RwSignal::new(Vec::<Video>::new());
+    let session_title = RwSignal::new(String::new());
+
+    // Shows with episodes ticked off, each with the next episode to watch
+    let continue_videos = RwSignal::new(Vec::<(Video, Episode)>::new());
+
+    // video_id -> "why is this here" line shown under the poster
+    let reasons = RwSignal::new(HashMap::<String, String>::new());
+
     let feed_trigger = use_context::<crate::model::feed_control::FeedTrigger>();
+    let session = use_context::<SessionState>();
 
     // spawn_local doesn't require Send — perfect for WASM
     Effect::new(move |_| {
@@ -27,83 +62,268 @@ pub fn Feed() -> impl IntoView {
 
         loading.set(true); // <--- Add this line!
 
+        let user = session.and_then(|s| s.user_thing());
+        // Without the key the library reads as empty: the view asks to unlock
+        // instead of falling back to Trending
+        if session.is_some_and(|s| s.is_locked()) {
+            loading.set(false);
+            return;
+        }
+
         leptos::task::spawn_local(async move {
             leptos::logging::log!("FEED: Starting recommendation engine...");
 
-            // Wait for DB to be ready (retry up to 15 times, ~3 seconds)
-            let mut db_ref = None;
-            for attempt in 1..=15 {
-                if let Some(db) = DB.get() {
-                    db_ref = Some(db);
-                    break;
-                }
-                leptos::logging::log!("FEED: DB not ready, attempt {}/15", attempt);
-                // Use gloo_timers for async delay
-                gloo_timers::future::TimeoutFuture::new(200).await;
-            }
+            // Waits for the vault to finish opening. The library is derived from
+            // this user's history, so forgotten entries drop out. Signed out,
+            // there is no library and the feed shows what's trending.
+            leptos::logging::log!("FEED: Fetching 'library'...");
+            let result = match user.clone() {
+                Some(thing) => get_user_library(thing).await,
+                None => Ok(Vec::new()),
+            };
+
+            match result {
+                Ok(library) => {
+                    if library.is_empty() {
+                        leptos::logging::log!(
+                            "FEED: Library is empty. Showing trending titles."
+                        );
+                        // Cold start: nothing to personalise yet
+                        match get_trending(TRENDING_LIMIT).await {
+                            Ok(trending) => {
+                                genre_title.set("Trending now".to_string());
+                                videos.set(trending);
+                            }
+                            Err(e) => {
+                                leptos::logging::error!("FEED: Trending Error: {:?}", e);
+                            }
+                        }
+                        loading.set(false);
+                        return;
+                    }
+
+                    // 0. Load the user's tunables (defaults if never saved)
+                    let config = match user.clone() {
+                        Some(thing) => load_config(thing).await.unwrap_or_else(|e| {
+                            leptos::logging::error!("FEED: Config Error: {:?}", e);
+                            RecommenderConfig::default()
+                        }),
+                        None => RecommenderConfig::default(),
+                    };
 
-            match db_ref {
-                Some(db) => {
-                    leptos::logging::log!("FEED: DB acquired, fetching 'library'...");
-                    let result: Result<Vec<Video>, _> = db.select("video").await;
-
-                    match result {
-                        Ok(library) => {
-                            if library.is_empty() {
-                                leptos::logging::log!(
-                                    "FEED: Library is empty. No recommendations."
-                                );
-                                loading.set(false);
-                                return;
+                    // 0b. Explicit ratings (imported or given here) replace TVMaze's average
+                    let library = match user.clone() {
+                        Some(thing) => match get_ratings(thing).await {
+                            Ok(ratings) => with_user_ratings(library, &ratings),
+                            Err(e) => {
+                                leptos::logging::error!("FEED: Ratings Error: {:?}", e);
+                                library
                             }
+                        },
+                        None => library,
+                    };
 
-                            // 1. Calculate User Vector
-                            let user_vec = SVD::user_vector(&library);
+                    // 0c. Watch states (or what episode progress implies): their own
+                    // weights, and a state change counts as recent
+                    continue_videos.set(Vec::new());
+                    let weights = match user.clone() {
+                        Some(thing) => match watch_signals(thing, &library, &config).await {
+                            Ok((entries, weights, continuing)) => {
+                                continue_videos.set(continuing);
+                                Some((weights, entries))
+                            }
+                            Err(e) => {
+                                leptos::logging::error!("FEED: Watchlist Error: {:?}", e);
+                                None
+                            }
+                        },
+                        None => None,
+                    };
 
-                            // 2. Get Top Genre
-                            let top_genre = SVD::get_top_genre(&user_vec);
-                            leptos::logging::log!("FEED: Top Genre determined: {}", top_genre);
-                            genre_title.set(format!("Recommended for you ({})", top_genre));
+                    // 1. Calculate User Vector (signed out there are no states: plain weights)
+                    let (library, user_vec, weights) = match weights {
+                        Some((weights, entries)) => {
+                            let library = with_watch_times(library, &entries);
+                            let user_vec = SVD::user_vector_weighted(&library, &config, |v| {
+                                weights.get(&v.video_id).copied().unwrap_or(config.click_weight)
+                            });
+                            (library, user_vec, weights)
+                        }
+                        None => {
+                            let user_vec = SVD::user_vector(&library, &config);
+                            (library, user_vec, HashMap::new())
+                        }
+                    };
 
-                            // 3. Fetch Candidates (Search API)
-                            leptos::logging::log!(
-                                "FEED: Fetching candidates for genre: {}",
-                                top_genre
-                            );
-                            match search_videos(&top_genre).await {
-                                Ok(mut candidates) => {
-                                    // 4. Filter out movies already in library
-                                    candidates.retain(|c| {
-                                        !library.iter().any(|l| l.video_id == c.video_id)
-                                    });
+                    // 1a. Personal tags as extra features, weighted like the shows behind them
+                    let tags = match user.clone() {
+                        Some(thing) => match get_notes(thing).await {
+                            Ok(notes) => TagProfile::from_notes(&notes, &library, |v| {
+                                weights.get(&v.video_id).copied().unwrap_or(config.click_weight)
+                            }),
+                            Err(e) => {
+                                leptos::logging::error!("FEED: Notes Error: {:?}", e);
+                                TagProfile::default()
+                            }
+                        },
+                        None => TagProfile::default(),
+                    };
+
+                    // 1b. What the user searches for, as a weak extra interest
+                    let searched = match user.clone() {
+                        Some(thing) => match get_searches(thing).await {
+                            Ok(searches) => QueryProfile::from_searches(&searches),
+                            Err(e) => {
+                                leptos::logging::error!("FEED: Searches Error: {:?}", e);
+                                QueryProfile::default()
+                            }
+                        },
+                        None => QueryProfile::default(),
+                    };
+
+                    // 1c. "Because you just looked at X" (Markov + long-term blend)
+                    session_videos.set(Vec::new());
+                    if let Some(thing) = user.clone() {
+                        let uid = thing.to_string();
+                        match get_user_interactions(thing).await {
+                            Ok(log) => {
+                                let model = TransitionModel::from_interactions(&log, config.session_gap_minutes);
+                                if let Some(last) = last_interaction(&log, &uid) {
+                                    // Followers outside the library are fetched, like graph candidates
+                                    let next = model.next_items(&last.video_id, 10);
+                                    let missing: Vec<&String> = next
+                                        .iter()
+                                        .map(|(id, _)| id)
+                                        .filter(|id| !library.iter().any(|l| &l.video_id == *id))
+                                        .collect();
+                                    let mut fetched: HashMap<String, Video> = HashMap::new();
+                                    for chunk in missing.chunks(FETCH_CONCURRENCY) {
+                                        let shows = join_all(chunk.iter().map(|id| fetch_show(id))).await;
+                                        for (id, show) in chunk.iter().zip(shows) {
+                                            match show {
+                                                Ok(v) => {
+                                                    fetched.insert((*id).clone(), v);
+                                                }
+                                                Err(e) => leptos::logging::error!("FEED: Failed to load next show {}: {:?}", id, e),
+                                            }
+                                        }
+                                    }
 
-                                    // 5. Rank Candidates (SVD)
-                                    candidates.sort_by(|a, b| {
-                                        let score_a = SVD::predict_match(&user_vec, a);
-                                        let score_b = SVD::predict_match(&user_vec, b);
-                                        score_b
-                                            .partial_cmp(&score_a)
-                                            .unwrap_or(std::cmp::Ordering::Equal)
+                                    let mut scored: Vec<(Video, f64)> = next
+                                        .iter()
+                                        .filter_map(|(id, prob)| {
+                                            let v = library
+                                                .iter()
+                                                .find(|l| &l.video_id == id)
+                                                .or_else(|| fetched.get(id))?;
+                                            let long_term = SVD::predict_match(&user_vec, v);
+                                            let score = config.session_blend * prob
+                                                + (1.0 - config.session_blend) * long_term;
+                                            Some((v.clone(), score))
+                                        })
+                                        .collect();
+                                    scored.sort_by(|a, b| {
+                                        b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal)
                                     });
 
-                                    leptos::logging::log!(
-                                        "FEED: Ranked {} candidates",
-                                        candidates.len()
-                                    );
-                                    videos.set(candidates);
+                                    session_title.set(format!("Because you just looked at {}", last.video_title));
+                                    session_videos.set(scored.into_iter().map(|(v, _)| v).collect());
                                 }
-                                Err(e) => {
-                                    leptos::logging::error!("FEED: API Error: {}", e);
+                            }
+                            Err(e) => {
+                                leptos::logging::error!("FEED: Interaction Error: {:?}", e);
+                            }
+                        }
+                    }
+
+                    // 2. Get Top Genres
+                    let top_genres = SVD::get_top_genres(&user_vec, &config);
+                    leptos::logging::log!("FEED: Top Genres determined: {:?}", top_genres);
+                    genre_title.set(format!("Recommended for you ({})", top_genres.join(", ")));
+
+                    // 3. Fetch Candidates (Search API)
+                    let mut fetched: Result<Vec<Video>, GlassBoxError> = Ok(Vec::new());
+                    for genre in &top_genres {
+                        leptos::logging::log!(
+                            "FEED: Fetching candidates for genre: {}",
+                            genre
+                        );
+                        // The fallback "Trending" means the popularity list, not a text search
+                        let found = if is_trending_query(genre) {
+                            get_trending(TRENDING_LIMIT).await
+                        } else {
+                            search_videos(genre).await
+                        };
+                        match found {
+                            Ok(found) => {
+                                if let Ok(all) = fetched.as_mut() {
+                                    for v in found {
+                                        if !all.iter().any(|c| c.video_id == v.video_id) {
+                                            all.push(v);
+                                        }
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                fetched = Err(e);
+                                break;
+                            }
+                        }
+                    }
+
+                    match fetched {
+                        Ok(mut candidates) => {
+                            // 3b. Walk the related-shows graph from the best library titles
+                            let mut why = graph_expand(&library, &mut candidates, &user_vec, &config).await;
+
+                            // 4. Filter out movies already in library
+                            candidates.retain(|c| {
+                                !library.iter().any(|l| l.video_id == c.video_id)
+                            });
+
+                            // 5. Rank Candidates (SVD + tag features + search terms)
+                            let base_score = |v: &Video| {
+                                SVD::predict_match(&user_vec, v) + tag_boost(&tags, v, &config) + query_boost(&searched, v, &config)
+                            };
+                            candidates.sort_by(|a, b| {
+                                let score_a = base_score(a);
+                                let score_b = base_score(b);
+                                score_b
+                                    .partial_cmp(&score_a)
+                                    .unwrap_or(std::cmp::Ordering::Equal)
+                            });
+                            if config.tag_weight > 0.0 {
+                                for v in &candidates {
+                                    if let Some(tag) = tags.closest_tag(v) {
+                                        why.entry(v.video_id.clone()).or_insert_with(|| format!("Like your #{} shows", tag));
+                                    }
+                                }
+                            }
+                            if config.query_weight > 0.0 {
+                                for v in &candidates {
+                                    if let Some(term) = searched.matched_term(v) {
+                                        why.entry(v.video_id.clone()).or_insert_with(|| format!("You searched for \"{}\"", term));
+                                    }
                                 }
                             }
+
+                            // 6. Re-rank the head by shared cast & crew
+                            why.extend(people_rerank(&library, &mut candidates, &base_score, &config).await);
+
+                            leptos::logging::log!(
+                                "FEED: Ranked {} candidates",
+                                candidates.len()
+                            );
+                            reasons.set(why);
+                            videos.set(candidates);
                         }
                         Err(e) => {
-                            leptos::logging::error!("FEED: Database Error: {}", e);
+                            leptos::logging::error!("FEED: API Error: {:?}", e);
                         }
                     }
                 }
-                None => {
-                    leptos::logging::error!("FEED: Database not available after 15 retries!");
+                Err(e) => {
+                    leptos::logging::error!("FEED: Database Error: {:?}", e);
                 }
             }
             loading.set(false);
@@ -112,39 +332,256 @@ pub fn Feed() -> impl IntoView {
 
     view! {
         <div class="feed-container" style="margin-top: 50px; padding: 20px;">
-            <h2 style="color: white; border-bottom: 1px solid #333; padding-bottom: 10px;">
-                {move || genre_title.get()}
-            </h2>
-
-            {move || {
-                let v = videos.get();
-                let is_loading = loading.get();
-
-                if is_loading {
-                    view! { <p style="color: #888;">"Analyzing your taste..."</p> }.into_any()
-                } else if v.is_empty() {
-                    view! {
-                        <div style="text-align: center; margin-top: 30px; color: #666;">
-                            <p>"Not enough data for recommendations."</p>
-                            <p>"Search and click movies to build your profile!"</p>
+            <VaultGate>
+                {move || {
+                    let v = continue_videos.get();
+                    if v.is_empty() {
+                        ().into_any()
+                    } else {
+                        view! {
+                            <h2 style="color: white; border-bottom: 1px solid #333; padding-bottom: 10px;">
+                                "Continue watching"
+                            </h2>
+                            {continue_grid(v)}
+                        }.into_any()
+                    }
+                }}
+
+                {move || {
+                    let v = session_videos.get();
+                    if v.is_empty() {
+                        ().into_any()
+                    } else {
+                        view! {
+                            <h2 style="color: white; border-bottom: 1px solid #333; padding-bottom: 10px;">
+                                {session_title.get()}
+                            </h2>
+                            {video_grid(v, HashMap::new())}
+                        }.into_any()
+                    }
+                }}
+
+                <h2 style="color: white; border-bottom: 1px solid #333; padding-bottom: 10px;">
+                    {move || genre_title.get()}
+                </h2>
+
+                {move || {
+                    let v = videos.get();
+                    let is_loading = loading.get();
+
+                    if is_loading {
+                        view! { <p style="color: #888;">"Analyzing your taste..."</p> }.into_any()
+                    } else if v.is_empty() {
+                        view! {
+                            <div style="text-align: center; margin-top: 30px; color: #666;">
+                                <p>"Not enough data for recommendations."</p>
+                                <p>"Search and click movies to build your profile!"</p>
+                            </div>
+                        }.into_any()
+                    } else {
+                        video_grid(v, reasons.get()).into_any()
+                    }
+                }}
+            </VaultGate>
+        </div>
+    }
+}
+
+// Explicit watch states, with episode progress filling in for shows that have
+// none, plus the "continue watching" row (most recent activity first)
+async fn watch_signals(
+    user: Thing,
+    library: &[Video],
+    config: &RecommenderConfig,
+) -> Result<(Vec<WatchEntry>, HashMap<String, f64>, Vec<(Video, Episode)>), GlassBoxError> {
+    let entries = get_watchlist(user.clone()).await?;
+    let mut weights = state_weights(&entries, config);
+    let mut continuing = Vec::new();
+    let now = Utc::now();
+
+    let shows = get_progress(user).await?;
+    let mut lists = Vec::with_capacity(shows.len());
+    for chunk in shows.chunks(FETCH_CONCURRENCY) {
+        lists.extend(join_all(chunk.iter().map(|p| episodes_for(&p.video_id))).await);
+    }
+
+    for (progress, episodes) in shows.iter().zip(lists) {
+        let episodes = match episodes {
+            Ok(episodes) => episodes,
+            Err(e) => {
+                leptos::logging::error!("FEED: Episodes Error for {}: {:?}", progress.video_title, e);
+                continue;
+            }
+        };
+        let signal = show_signal(progress, &episodes, config, now);
+        let explicit = entries.iter().find(|e| e.video_id == progress.video_id).map(|e| e.state);
+
+        if explicit.is_none() {
+            if let Some(state) = signal.implied_state() {
+                weights.insert(progress.video_id.clone(), state.weight(config));
+            }
+        }
+
+        let put_down = matches!(explicit, Some(WatchState::Watched) | Some(WatchState::Dropped));
+        if signal != ShowSignal::InProgress || put_down {
+            continue;
+        }
+        let video = library.iter().find(|v| v.video_id == progress.video_id);
+        if let (Some(video), Some(next)) = (video, next_episode(progress, &episodes, now.date_naive())) {
+            continuing.push((video.clone(), next.clone()));
+        }
+    }
+
+    Ok((entries, weights, continuing))
+}
+
+// Pulls unseen shows reachable through related_ids into the candidate pool
+async fn graph_expand(
+    library: &[Video],
+    candidates: &mut Vec<Video>,
+    user_vec: &[f64],
+    config: &RecommenderConfig,
+) -> HashMap<String, String> {
+    let mut why = HashMap::new();
+    if config.graph_candidates == 0 {
+        return why;
+    }
+
+    let mut seeds: Vec<&Video> = library.iter().collect();
+    seeds.sort_by(|a, b| {
+        let score_a = SVD::predict_match(user_vec, a);
+        let score_b = SVD::predict_match(user_vec, b);
+        score_b.partial_cmp(&score_a).unwrap_or(std::cmp::Ordering::Equal)
+    });
+    // Small floor so a seed with no genre overlap still gets a restart share
+    let seed_weights: HashMap<String, f64> = seeds
+        .into_iter()
+        .take(config.graph_seeds.max(1))
+        .map(|v| (v.video_id.clone(), SVD::predict_match(user_vec, v) + 0.01))
+        .collect();
+
+    let ids: Vec<String> = graph_candidates(library, &seed_weights, config.graph_restart, config.graph_candidates)
+        .into_iter()
+        .map(|(id, _)| id)
+        .filter(|id| !candidates.iter().any(|c| &c.video_id == id))
+        .collect();
+    let mut shows = Vec::with_capacity(ids.len());
+    for chunk in ids.chunks(FETCH_CONCURRENCY) {
+        shows.extend(join_all(chunk.iter().map(|id| fetch_show(id))).await);
+    }
+
+    for (id, show) in ids.into_iter().zip(shows) {
+        match show {
+            Ok(v) => {
+                if let Some(from) = library.iter().find(|l| l.related_ids.contains(&id)) {
+                    why.insert(id.clone(), format!("Related to {}", from.title));
+                }
+                candidates.push(v);
+            }
+            Err(e) => leptos::logging::error!("FEED: Failed to load related show {}: {:?}", id, e),
+        }
+    }
+    why
+}
+
+// Blends the base score with how many familiar people a show shares with
+// the library, and returns the names behind each boost for the explanation
+async fn people_rerank(
+    library: &[Video],
+    candidates: &mut [Video],
+    base_score: &impl Fn(&Video) -> f64,
+    config: &RecommenderConfig,
+) -> HashMap<String, String> {
+    let mut why = HashMap::new();
+    if config.people_weight <= 0.0 || candidates.is_empty() {
+        return why;
+    }
+
+    let mut newest: Vec<&Video> = library.iter().collect();
+    newest.sort_by_key(|v| std::cmp::Reverse(v.saved_at));
+    let library_ids: Vec<String> = newest
+        .iter()
+        .take(PEOPLE_PROFILE_DEPTH)
+        .map(|v| v.video_id.clone())
+        .collect();
+
+    let profile = match credits_for(&library_ids).await {
+        Ok(credits) => PeopleProfile::from_library(&credits),
+        Err(e) => {
+            leptos::logging::error!("FEED: Credits Error: {:?}", e);
+            return why;
+        }
+    };
+
+    let depth = candidates.len().min(PEOPLE_RERANK_DEPTH);
+    let head_ids: Vec<String> = candidates[..depth].iter().map(|v| v.video_id.clone()).collect();
+    let head_credits = credits_for(&head_ids).await.unwrap_or_default();
+
+    let mut scores: HashMap<String, f64> = HashMap::new();
+    for v in &candidates[..depth] {
+        let (people_score, names) = head_credits
+            .get(&v.video_id)
+            .map(|c| profile.score(c))
+            .unwrap_or((0.0, Vec::new()));
+        if !names.is_empty() {
+            let shown: Vec<String> = names.into_iter().take(3).collect();
+            why.insert(v.video_id.clone(), format!("With {}", shown.join(", ")));
+        }
+        scores.insert(
+            v.video_id.clone(),
+            base_score(v) + config.people_weight * people_score,
+        );
+    }
+
+    candidates[..depth].sort_by(|a, b| {
+        let score_a = scores.get(&a.video_id).copied().unwrap_or(0.0);
+        let score_b = scores.get(&b.video_id).copied().unwrap_or(0.0);
+        score_b.partial_cmp(&score_a).unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    why
+}
+
+// Poster grid shared by every feed row
+fn video_grid(videos: Vec<Video>, reasons: HashMap<String, String>) -> impl IntoView {
+    view! {
+        <div class="movie-grid" style="display: grid; grid-template-columns: repeat(auto-fill, minmax(150px, 1fr)); gap: 20px; margin-top: 20px;">
+            {videos.into_iter().map(|video| {
+                let reason = reasons.get(&video.video_id).cloned();
+                view! {
+                    <div class="movie-card" style="background: rgba(255,255,255,0.05); padding: 10px; border-radius: 8px;">
+                        <img src={video.thumbnail_url} style="width: 100%; border-radius: 4px;" />
+                        <h4 style="color: white; font-size: 0.9rem; margin-top: 5px;">{video.title}</h4>
+                        <div style="font-size: 0.7rem; color: #aaa;">
+                            {video.genres.join(", ")}
                         </div>
-                    }.into_any()
-                } else {
-                    view! {
-                        <div class="movie-grid" style="display: grid; grid-template-columns: repeat(auto-fill, minmax(150px, 1fr)); gap: 20px; margin-top: 20px;">
-                            {v.into_iter().map(|video| view! {
-                                <div class="movie-card" style="background: rgba(255,255,255,0.05); padding: 10px; border-radius: 8px;">
-                                    <img src={video.thumbnail_url} style="width: 100%; border-radius: 4px;" />
-                                    <h4 style="color: white; font-size: 0.9rem; margin-top: 5px;">{video.title}</h4>
-                                    <div style="font-size: 0.7rem; color: #aaa;">
-                                        {video.genres.join(", ")}
-                                    </div>
-                                </div>
-                            }).collect::<Vec<_>>()}
+                        {reason.map(|r| view! {
+                            <div style="font-size: 0.7rem; color: #646cff; margin-top: 4px;">{r}</div>
+                        })}
+                    </div>
+                }
+            }).collect::<Vec<_>>()}
+        </div>
+    }
+}
+
+// Like video_grid, but each card opens the show's episode list
+fn continue_grid(items: Vec<(Video, Episode)>) -> impl IntoView {
+    view! {
+        <div class="movie-grid" style="display: grid; grid-template-columns: repeat(auto-fill, minmax(150px, 1fr)); gap: 20px; margin-top: 20px;">
+            {items.into_iter().map(|(video, next)| {
+                view! {
+                    <a href=format!("/shows/{}/episodes", video.video_id) style="text-decoration: none;">
+                        <div class="movie-card" style="background: rgba(255,255,255,0.05); padding: 10px; border-radius: 8px;">
+                            <img src={video.thumbnail_url} style="width: 100%; border-radius: 4px;" />
+                            <h4 style="color: white; font-size: 0.9rem; margin-top: 5px;">{video.title}</h4>
+                            <div style="font-size: 0.7rem; color: #646cff; margin-top: 4px;">
+                                {format!("Next: {} · {}", next.code(), next.name)}
+                            </div>
                         </div>
-                    }.into_any()
+                    </a>
                 }
-            }}
+            }).collect::<Vec<_>>()}
         </div>
     }
 }
@@ -1,3 +1,6 @@
 pub mod feed;
 pub mod movie_modal;
 pub mod search;
+pub mod startup;
+pub mod storage_notice;
+pub mod vault_gate;
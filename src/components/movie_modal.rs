@@ -1,4 +1,9 @@
+use crate::model::feed_control::FeedTrigger;
+use crate::model::lists::{add_to_list, get_lists, UserList};
+use crate::model::notes::{get_notes, save_note};
+use crate::model::session::SessionState;
 use crate::model::video::Video;
+use crate::model::watchlist::{clear_watch_state, get_watchlist, set_watch_state, WatchState};
 use leptos::prelude::*;
 use leptos_meta::Stylesheet;
 
@@ -10,6 +15,126 @@ pub fn MovieModal(
     let set_video_overlay = set_video;
     let set_video_btn = set_video;
 
+    // Watch state of the open show (signed-in users only)
+    let session = use_context::<SessionState>();
+    let feed_trigger = use_context::<FeedTrigger>();
+    let watch_state = RwSignal::new(Option::<WatchState>::None);
+    let (watch_msg, set_watch_msg) = signal(Option::<String>::None);
+    // The user's lists and the one picked in the "Add to list" select (index)
+    let lists = RwSignal::new(Vec::<UserList>::new());
+    let (list_choice, set_list_choice) = signal(0usize);
+    // Personal note and tags (comma-separated while editing)
+    let (note_text, set_note_text) = signal(String::new());
+    let (note_tags, set_note_tags) = signal(String::new());
+
+    let current_user = move || session.and_then(|s| s.user_thing());
+
+    Effect::new(move |_| {
+        watch_state.set(None);
+        set_watch_msg.set(None);
+        set_note_text.set(String::new());
+        set_note_tags.set(String::new());
+        let (Some(v), Some(user)) = (video.get(), current_user()) else {
+            return;
+        };
+        leptos::task::spawn_local(async move {
+            match get_watchlist(user.clone()).await {
+                Ok(entries) => watch_state.set(entries.into_iter().find(|e| e.video_id == v.video_id).map(|e| e.state)),
+                Err(e) => leptos::logging::error!("MODAL: Watchlist error: {:?}", e),
+            }
+            match get_lists(user.clone()).await {
+                Ok(all) => lists.set(all),
+                Err(e) => leptos::logging::error!("MODAL: Lists error: {:?}", e),
+            }
+            match get_notes(user).await {
+                Ok(notes) => {
+                    if let Some(note) = notes.into_iter().find(|n| n.video_id == v.video_id) {
+                        set_note_text.set(note.text);
+                        set_note_tags.set(note.tags.join(", "));
+                    }
+                }
+                Err(e) => leptos::logging::error!("MODAL: Notes error: {:?}", e),
+            }
+        });
+    });
+
+    // Clicking the active state again clears it
+    let on_state = move |state: WatchState| {
+        let (Some(v), Some(user)) = (video.get_untracked(), current_user()) else {
+            return;
+        };
+        let clear = watch_state.get_untracked() == Some(state);
+        leptos::task::spawn_local(async move {
+            let result = if clear {
+                clear_watch_state(user, v.video_id.clone()).await.map(|_| None)
+            } else {
+                set_watch_state(user, v, state).await.map(|e| Some(e.state))
+            };
+            match result {
+                Ok(new_state) => {
+                    watch_state.set(new_state);
+                    set_watch_msg.set(None);
+                    if let Some(trigger) = feed_trigger {
+                        trigger.0.update(|c| *c += 1);
+                    }
+                }
+                Err(e) => {
+                    leptos::logging::error!("MODAL: Watch state error: {:?}", e);
+                    set_watch_msg.set(Some(format!("ERROR: {}", e)));
+                }
+            }
+        });
+    };
+
+    let on_add_to_list = move |_| {
+        let Some(v) = video.get_untracked() else {
+            return;
+        };
+        let Some(list) = lists.get_untracked().get(list_choice.get_untracked()).cloned() else {
+            return;
+        };
+        if list.contains(&v.video_id) {
+            set_watch_msg.set(Some(format!("Already in '{}'.", list.name)));
+            return;
+        }
+        leptos::task::spawn_local(async move {
+            match add_to_list(list, v).await {
+                Ok(saved) => {
+                    set_watch_msg.set(Some(format!("Added to '{}'.", saved.name)));
+                    lists.update(|all| {
+                        if let Some(existing) = all.iter_mut().find(|l| l.id == saved.id) {
+                            *existing = saved;
+                        }
+                    });
+                }
+                Err(e) => {
+                    leptos::logging::error!("MODAL: Add to list error: {:?}", e);
+                    set_watch_msg.set(Some(format!("ERROR: {}", e)));
+                }
+            }
+        });
+    };
+
+    let on_save_note = move |_| {
+        let (Some(v), Some(user)) = (video.get_untracked(), current_user()) else {
+            return;
+        };
+        let (text, tags) = (note_text.get_untracked(), note_tags.get_untracked());
+        leptos::task::spawn_local(async move {
+            match save_note(user, v, text, tags).await {
+                Ok(Some(note)) => {
+                    set_note_tags.set(note.tags.join(", "));
+                    set_watch_msg.set(Some("Note saved.".to_string()));
+                }
+                Ok(None) => set_watch_msg.set(Some("Note removed.".to_string())),
+                Err(e) => {
+                    leptos::logging::error!("MODAL: Note error: {:?}", e);
+                    set_watch_msg.set(Some(format!("ERROR: {}", e)));
+                }
+            }
+        });
+    };
+
     view! {
         <Stylesheet href="movie_modal.css"/>
 
@@ -52,6 +177,54 @@ pub fn MovieModal(
                                 // Description section
                                 <div class="modal-description-section">
                                     <h3>"description and other data or text"</h3>
+                                    {move || current_user().map(|_| view! {
+                                        <div class="modal-watch-states">
+                                            {WatchState::ALL.into_iter().map(|state| view! {
+                                                <button
+                                                    type="button"
+                                                    class=move || if watch_state.get() == Some(state) { "modal-watch-btn active" } else { "modal-watch-btn" }
+                                                    on:click=move |_| on_state(state)
+                                                >
+                                                    {state.label()}
+                                                </button>
+                                            }).collect::<Vec<_>>()}
+                                        </div>
+                                    })}
+                                    {move || (current_user().is_some() && !lists.get().is_empty()).then(|| view! {
+                                        <div class="modal-list-add">
+                                            <select on:change=move |ev| set_list_choice.set(event_target_value(&ev).parse().unwrap_or(0))>
+                                                {lists.get().into_iter().enumerate().map(|(i, l)| view! {
+                                                    <option value=i.to_string() selected=move || list_choice.get() == i>{l.name}</option>
+                                                }).collect::<Vec<_>>()}
+                                            </select>
+                                            <button type="button" class="modal-watch-btn" on:click=on_add_to_list>"Add to list"</button>
+                                        </div>
+                                    })}
+                                    {move || current_user().map(|_| view! {
+                                        <div class="modal-note">
+                                            <textarea
+                                                rows="3"
+                                                placeholder="Your private note"
+                                                prop:value=note_text
+                                                on:input=move |ev| set_note_text.set(event_target_value(&ev))
+                                            ></textarea>
+                                            <input
+                                                type="text"
+                                                placeholder="Tags, comma-separated (cozy, rewatch)"
+                                                prop:value=note_tags
+                                                on:input=move |ev| set_note_tags.set(event_target_value(&ev))
+                                            />
+                                            <button type="button" class="modal-watch-btn" on:click=on_save_note>"Save note"</button>
+                                        </div>
+                                    })}
+                                    {move || watch_msg.get().map(|msg| view! { <p class="modal-watch-status">{msg}</p> })}
+                                    <a
+                                        class="modal-episodes-link"
+                                        href=format!("/shows/{}/episodes", v.video_id)
+                                        on:click=move |_| set_video_btn.set(None)
+                                    >
+                                        "Episodes & progress"
+                                    </a>
                                     <div class="modal-info-grid">
                                         <div class="modal-info-item">
                                             <strong>"Title:"</strong>
@@ -1,9 +1,16 @@
 use crate::api::search::search_videos;
 use crate::cards::search_results::SearchResults;
+use crate::model::history::{get_searches, SearchEntry};
+use crate::model::queries::suggest_queries;
+use crate::model::search_index::{build_index, LocalHit};
 use crate::model::session::SessionState;
 use crate::model::video::Video;
 use leptos::prelude::*;
 use leptos_meta::{Script, Stylesheet};
+use leptos_router::hooks::use_query_map;
+use std::collections::HashMap;
+
+const SUGGESTION_LIMIT: usize = 6;
 
 #[component]
 pub fn Search() -> impl IntoView {
@@ -12,6 +19,15 @@ pub fn Search() -> impl IntoView {
     let (videos, set_videos) = signal(Vec::<Video>::new());
     let (error_message, set_error_message) = signal(Option::<String>::None);
 
+    // "From your library": the local index, searched before TVMaze (works offline)
+    let (local_videos, set_local_videos) = signal(Vec::<Video>::new());
+    let (note_lines, set_note_lines) = signal(HashMap::<String, String>::new());
+    let (past_queries, set_past_queries) = signal(Vec::<String>::new());
+
+    // Earlier searches under the input while it has focus (reloaded on every focus)
+    let history = RwSignal::new(Vec::<SearchEntry>::new());
+    let (show_suggestions, set_show_suggestions) = signal(false);
+
     let on_input = move |ev| {
         set_query.set(event_target_value(&ev));
     };
@@ -26,6 +42,36 @@ pub fn Search() -> impl IntoView {
         set_error_message.set(None);
         set_videos.set(Vec::new());
 
+        let user = use_context::<SessionState>().and_then(|s| s.user_thing());
+
+        // Local first: it only reads IndexedDB, so it answers before TVMaze does
+        let q_for_local = q.clone();
+        let user_for_local = user.clone();
+        leptos::task::spawn_local(async move {
+            match build_index(user_for_local).await {
+                Ok(index) => {
+                    let mut found = Vec::new();
+                    let mut lines = HashMap::new();
+                    let mut queries = Vec::new();
+                    for hit in index.search(&q_for_local) {
+                        match hit {
+                            LocalHit::Show { video, note } => {
+                                if let Some(line) = note {
+                                    lines.insert(video.video_id.clone(), line);
+                                }
+                                found.push(video);
+                            }
+                            LocalHit::Query(query) => queries.push(query),
+                        }
+                    }
+                    set_note_lines.set(lines);
+                    set_local_videos.set(found);
+                    set_past_queries.set(queries);
+                }
+                Err(e) => leptos::logging::error!("SEARCH: Local index error: {:?}", e),
+            }
+        });
+
         let q_for_search = q.clone();
         leptos::task::spawn_local(async move {
             // Updated to pass String, not &str, as per server function signature
@@ -49,17 +95,10 @@ pub fn Search() -> impl IntoView {
         });
 
         // Save history (fire and forget)
-        let sess = use_context::<SessionState>();
-        if let Some(s) = sess {
-            let session_data = s.1.get();
-            if let Some(uid_str) = session_data.user_id {
-                let query_val = q.clone();
-                leptos::task::spawn_local(async move {
-                    if let Ok(thing) = surrealdb::sql::thing(&uid_str) {
-                        let _ = crate::model::history::save_search(thing, query_val).await;
-                    }
-                });
-            }
+        if let Some(thing) = user {
+            leptos::task::spawn_local(async move {
+                let _ = crate::model::history::save_search(thing, q).await;
+            });
         }
     };
 
@@ -67,8 +106,34 @@ pub fn Search() -> impl IntoView {
         search_action();
     };
 
+    let on_focus = move |_| {
+        set_show_suggestions.set(true);
+        let user = use_context::<SessionState>().and_then(|s| s.user_thing());
+        if let Some(thing) = user {
+            leptos::task::spawn_local(async move {
+                match get_searches(thing).await {
+                    Ok(list) => history.set(list),
+                    Err(e) => leptos::logging::error!("SEARCH: History error: {:?}", e),
+                }
+            });
+        }
+    };
+
+    let run_suggestion = move |q: String| {
+        set_show_suggestions.set(false);
+        set_query.set(q);
+        search_action();
+    };
+
+    // "/?q=..." (the profile page links here) runs that search straight away
+    if let Some(q) = use_query_map().get_untracked().get("q").filter(|q| !q.trim().is_empty()) {
+        set_query.set(q);
+        search_action();
+    }
+
     let on_keydown = move |ev: web_sys::KeyboardEvent| {
         if ev.key() == "Enter" {
+            set_show_suggestions.set(false);
             search_action();
         }
     };
@@ -86,6 +151,8 @@ pub fn Search() -> impl IntoView {
                     on:input=on_input
                     prop:value=query
                     on:keydown=on_keydown
+                    on:focus=on_focus
+                    on:blur=move |_| set_show_suggestions.set(false)
                 />
                 <button type="button" class="search-button" on:click=on_search_click>
                     <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
@@ -95,6 +162,28 @@ pub fn Search() -> impl IntoView {
                 </button>
             </div>
 
+            // Recent searches matching what is typed; mousedown so it fires before the blur
+            {move || {
+                let suggestions = if show_suggestions.get() {
+                    suggest_queries(&history.get(), &query.get(), SUGGESTION_LIMIT)
+                } else {
+                    Vec::new()
+                };
+                (!suggestions.is_empty()).then(|| view! {
+                    <div class="search-suggestions">
+                        {suggestions.into_iter().map(|sq| {
+                            let label = sq.clone();
+                            view! {
+                                <button type="button" class="search-suggestion" on:mousedown=move |ev| {
+                                    ev.prevent_default();
+                                    run_suggestion(sq.clone());
+                                }>{label}</button>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </div>
+                })
+            }}
+
             // Show Loading Indicator
             {move || if is_loading.get() {
                 view! { <div class="search-loading" style="color: white; padding: 10px;">"Searching..."</div> }.into_any()
@@ -108,6 +197,28 @@ pub fn Search() -> impl IntoView {
                 None => view! { <div/> }.into_any()
             }}
 
+            // Earlier searches that match, one click to run again
+            {move || {
+                let queries = past_queries.get();
+                (!queries.is_empty()).then(|| view! {
+                    <div class="search-past-queries">
+                        <span>"You searched:"</span>
+                        {queries.into_iter().map(|pq| {
+                            let label = pq.clone();
+                            view! {
+                                <button type="button" class="search-past-query" on:click=move |_| run_suggestion(pq.clone())>{label}</button>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </div>
+                })
+            }}
+
+            // The user's own shows (titles, descriptions, notes, tags), above the TVMaze results
+            {move || (!local_videos.get().is_empty()).then(|| view! {
+                <h3 class="search-section-title">"From your library"</h3>
+                <SearchResults videos=local_videos notes=note_lines />
+            })}
+
             // Render Results
             <SearchResults videos=videos />
         </div>
@@ -0,0 +1,45 @@
+use crate::model::db::{DbState, DbStatus};
+use leptos::prelude::*;
+
+// Covers the app while the local vault opens, and explains what to do if it can't
+#[component]
+pub fn StartupScreen() -> impl IntoView {
+    let db_state = use_context::<DbState>().expect("DbState not found");
+
+    let on_retry = move |_| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.location().reload();
+        }
+    };
+
+    let overlay_style = "position: fixed; inset: 0; z-index: 2000; display: flex; flex-direction: column; align-items: center; justify-content: center; gap: 15px; background: #0f0f13; color: white; text-align: center; padding: 20px;";
+
+    view! {
+        {move || match db_state.0.get() {
+            DbStatus::Ready => ().into_any(),
+            DbStatus::Initialising => view! {
+                <div style=overlay_style>
+                    <h1>"GlassBox"</h1>
+                    <p style="color: #888;">"Opening your local vault..."</p>
+                </div>
+            }.into_any(),
+            DbStatus::Failed(reason) => view! {
+                <div style=overlay_style>
+                    <h1>"Your local vault could not be opened"</h1>
+                    <p style="color: #aaa; max-width: 500px;">
+                        "GlassBox stores everything in your browser's IndexedDB. Private browsing windows, "
+                        "full disks or blocked site storage can prevent it from opening."
+                    </p>
+                    <p style="color: #ff6b6b; font-family: monospace; font-size: 0.8rem;">{reason}</p>
+                    <button
+                        type="button"
+                        style="background: #646cff; border: none; border-radius: 8px; padding: 10px 16px; color: white; cursor: pointer;"
+                        on:click=on_retry
+                    >
+                        "Try again"
+                    </button>
+                </div>
+            }.into_any(),
+        }}
+    }
+}
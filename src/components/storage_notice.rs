@@ -0,0 +1,56 @@
+use crate::model::storage::{format_bytes, storage_status, PersistenceAsked, StorageStatus};
+use leptos::prelude::*;
+use leptos_router::components::A;
+
+// Home-page warning when the browser may throw the vault away: storage close
+// to the quota (export now), or persistence denied (keep a backup)
+#[component]
+pub fn StorageNotice() -> impl IntoView {
+    let status = RwSignal::new(Option::<StorageStatus>::None);
+    let (dismissed, set_dismissed) = signal(false);
+
+    // Reads the status only after the startup request has been answered,
+    // otherwise a first visit shows "not persisted" before the browser decides
+    let asked = use_context::<PersistenceAsked>();
+    Effect::new(move |done: Option<bool>| {
+        if done == Some(true) || !asked.map(|a| a.0.get()).unwrap_or(true) {
+            return done.unwrap_or(false);
+        }
+        leptos::task::spawn_local(async move {
+            match storage_status().await {
+                Ok(s) => status.set(Some(s)),
+                Err(e) => leptos::logging::error!("STORAGE: Estimate error: {:?}", e),
+            }
+        });
+        true
+    });
+
+    let box_style = "display: flex; align-items: center; gap: 10px; flex-wrap: wrap; padding: 10px 15px; margin-bottom: 20px; border-radius: 8px; background: rgba(0,0,0,0.3);";
+    let link_style = "color: #646cff;";
+    let small_button = "background: transparent; border: 1px solid rgba(255,255,255,0.2); border-radius: 6px; padding: 4px 10px; color: #ccc; cursor: pointer;";
+
+    view! {
+        {move || match status.get() {
+            Some(s) if s.near_quota() => view! {
+                <div style={format!("{} border: 1px solid #ff6b6b;", box_style)}>
+                    <span style="flex: 1;">{format!(
+                        "Browser storage is {:.0}% full ({} of {}). Export your data or download a backup before the browser starts clearing it.",
+                        s.fraction() * 100.0,
+                        format_bytes(s.usage),
+                        format_bytes(s.quota)
+                    )}</span>
+                    <A href="/settings" attr:style=link_style>"Export"</A>
+                    <A href="/backup" attr:style=link_style>"Backup"</A>
+                </div>
+            }.into_any(),
+            Some(s) if !s.persisted && !dismissed.get() => view! {
+                <div style=box_style>
+                    <span style="flex: 1; color: #aaa;">"This browser did not allow GlassBox to keep its storage, so it may clear your vault when space runs low. Keep a backup."</span>
+                    <A href="/backup" attr:style=link_style>"Backup"</A>
+                    <button type="button" style=small_button on:click=move |_| set_dismissed.set(true)>"Dismiss"</button>
+                </div>
+            }.into_any(),
+            _ => ().into_any(),
+        }}
+    }
+}
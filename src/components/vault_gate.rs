@@ -0,0 +1,25 @@
+use crate::model::session::SessionState;
+use leptos::prelude::*;
+use leptos_router::components::A;
+
+// Stands in for a page's data while the session is remembered but the vault
+// key is not (after a reload): sealed rows can't be read, so the page would
+// only look empty
+#[component]
+pub fn VaultGate(children: ChildrenFn) -> impl IntoView {
+    let session = use_context::<SessionState>();
+    let locked = move || session.is_some_and(|s| s.is_locked());
+
+    view! {
+        {move || if locked() {
+            view! {
+                <div style="display: flex; align-items: center; gap: 10px; flex-wrap: wrap; padding: 15px; border-radius: 8px; background: rgba(0,0,0,0.3); border: 1px solid rgba(255,255,255,0.1);">
+                    <span style="flex: 1; color: #aaa;">"Your vault is locked. Enter your password again to read your history on this device."</span>
+                    <A href="/login" attr:style="color: #646cff;">"Unlock your vault"</A>
+                </div>
+            }.into_any()
+        } else {
+            children().into_any()
+        }}
+    }
+}
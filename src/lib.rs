@@ -0,0 +1,17 @@
+// The app's modules live in a library so the browser app (main.rs) and the
+// native tools in src/bin share the same model code.
+
+#[allow(non_snake_case, dead_code)]
+pub mod api;
+pub mod cards;
+pub mod components;
+pub mod model;
+pub mod navbar;
+pub mod pages;
+
+// Re-export server functions so they can be called from client
+// #[cfg(not(target_arch = "wasm32"))]
+// pub use api::search::search_videos;
+
+// #[cfg(not(target_arch = "wasm32"))]
+// pub use api::youtube::fetch_video;
@@ -3,27 +3,22 @@ use leptos_meta::provide_meta_context;
 use leptos_router::components::{Route, Router, Routes};
 use leptos_router::path;
 
-use model::db::init_db;
-use model::session::SessionState;
-use navbar::navbar::Navbar;
-use pages::home::Home;
-use pages::login::Login;
-use pages::signup::Signup;
-
-#[allow(non_snake_case, dead_code)]
-mod api;
-mod cards;
-mod components;
-mod model;
-mod navbar;
-mod pages;
-
-// Re-export server functions so they can be called from client
-// #[cfg(not(target_arch = "wasm32"))]
-// pub use api::search::search_videos;
-
-// #[cfg(not(target_arch = "wasm32"))]
-// pub use api::youtube::fetch_video;
+use glassbox::components::startup::StartupScreen;
+use glassbox::model::db::{init_db, DbState};
+use glassbox::model::session::SessionState;
+use glassbox::model::storage::{request_persistence, PersistenceAsked};
+use glassbox::navbar::navbar::Navbar;
+use glassbox::pages::backup::Backup;
+use glassbox::pages::episodes::Episodes;
+use glassbox::pages::history::History;
+use glassbox::pages::home::Home;
+use glassbox::pages::import::ImportHistory;
+use glassbox::pages::library::Library;
+use glassbox::pages::lists::Lists;
+use glassbox::pages::login::Login;
+use glassbox::pages::profile::Profile;
+use glassbox::pages::settings::Settings;
+use glassbox::pages::signup::Signup;
 
 fn main() {
     console_error_panic_hook::set_once();
@@ -34,15 +29,25 @@ fn main() {
 fn App() -> impl IntoView {
     provide_meta_context();
 
-    // Initialize DB
-    leptos::task::spawn_local(async {
-        init_db().await;
+    // Initialize DB (status is reactive so the UI can wait for it)
+    let db_state = DbState::new();
+    provide_context(db_state);
+    let asked = PersistenceAsked(RwSignal::new(false));
+    provide_context(asked);
+    leptos::task::spawn_local(async move {
+        init_db(db_state).await;
+        // Ask the browser not to evict the vault (see storage.rs)
+        if let Err(e) = request_persistence().await {
+            leptos::logging::error!("STORAGE: Persistence request failed: {:?}", e);
+        }
+        asked.0.set(true);
     });
 
     let session = SessionState::new();
     provide_context(session);
 
     view! {
+        <StartupScreen />
         <Router>
             // Navbar is outside <Routes>, so it stays visible on every page
             <Navbar />
@@ -52,6 +57,14 @@ fn App() -> impl IntoView {
                     <Route path=path!("/") view=Home />
                     <Route path=path!("/login") view=Login />
                     <Route path=path!("/signup") view=Signup />
+                    <Route path=path!("/settings") view=Settings />
+                    <Route path=path!("/import") view=ImportHistory />
+                    <Route path=path!("/history") view=History />
+                    <Route path=path!("/profile") view=Profile />
+                    <Route path=path!("/backup") view=Backup />
+                    <Route path=path!("/library") view=Library />
+                    <Route path=path!("/lists") view=Lists />
+                    <Route path=path!("/shows/:id/episodes") view=Episodes />
                 </Routes>
             </main>
         </Router>
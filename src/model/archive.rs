@@ -0,0 +1,743 @@
+// <------- Portable archive: everything one user owns, as one JSON file ------->
+// Record IDs and the password hash are left out on purpose, so the file can be
+// read anywhere (another browser, or natively via load_archive for the tuning
+// tools: `archive.library` goes straight into tuning::temporal_split).
+
+use crate::model::config::{load_config_with, RecommenderConfig, UserSettings};
+use crate::model::download::download_text;
+use crate::model::episodes::EpisodeProgress;
+use crate::model::error::GlassBoxError;
+use crate::model::history::{single_event, InteractionEntry, SearchEntry};
+use crate::model::lists::{ListItem, SharedList, UserList};
+use crate::model::notes::{parse_tags, TitleNote};
+use crate::model::ratings::Rating;
+use crate::model::store::{Store, SurrealStore, UserHistory};
+use crate::model::tuning::Signals;
+use crate::model::vault::is_unlocked;
+use crate::model::video::Video;
+use crate::model::watchlist::{WatchEntry, WatchState};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use surrealdb::sql::Thing;
+
+// Tells a GlassBox archive apart from any other JSON file
+pub const ARCHIVE_FORMAT: &str = "glassbox-archive";
+
+// Bump when the layout changes, and teach from_json to read the old one.
+// 2: watch states, episode progress, lists and notes
+pub const ARCHIVE_VERSION: u32 = 2;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ArchivedProfile {
+    pub username: String,
+    pub email: String,
+    pub joined_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ArchivedInteraction {
+    pub video_id: String,
+    pub video_title: String,
+    pub interaction_type: String,
+    pub timestamp: String,
+    // Repeats folded into this row; older files have one event per row
+    #[serde(default = "single_event")]
+    pub count: u32,
+    #[serde(default)]
+    pub last_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ArchivedSearch {
+    pub query: String,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ArchivedRating {
+    pub video_id: String,
+    pub video_title: String,
+    pub score: f64,
+    pub source: String,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ArchivedWatch {
+    pub video_id: String,
+    pub video_title: String,
+    pub state: WatchState,
+    pub added_at: String,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ArchivedProgress {
+    pub video_id: String,
+    pub video_title: String,
+    pub watched: Vec<String>, // TVMaze episode IDs
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ArchivedNote {
+    pub video_id: String,
+    pub video_title: String,
+    pub text: String,
+    pub tags: Vec<String>,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Archive {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub profile: ArchivedProfile,
+    pub library: Vec<Video>, // Shows in this user's library, IDs stripped
+    pub interactions: Vec<ArchivedInteraction>,
+    pub searches: Vec<ArchivedSearch>,
+    pub ratings: Vec<ArchivedRating>,
+    // Version 2; a version 1 file has none of these
+    #[serde(default)]
+    pub watchlist: Vec<ArchivedWatch>,
+    #[serde(default)]
+    pub progress: Vec<ArchivedProgress>,
+    // Same layout as a shared list
+    #[serde(default)]
+    pub lists: Vec<SharedList>,
+    #[serde(default)]
+    pub notes: Vec<ArchivedNote>,
+    pub settings: RecommenderConfig,
+}
+
+impl Archive {
+    pub fn to_json(&self) -> Result<String, GlassBoxError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    // Reads versions 1 and 2; refuses files that aren't archives, or were
+    // written by a newer GlassBox
+    pub fn from_json(json: &str) -> Result<Self, GlassBoxError> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+
+        if value.get("format").and_then(|f| f.as_str()) != Some(ARCHIVE_FORMAT) {
+            return Err(GlassBoxError::Validation("This file is not a GlassBox archive".to_string()));
+        }
+        let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+        if version == 0 || version > ARCHIVE_VERSION as u64 {
+            return Err(GlassBoxError::Validation(format!(
+                "Archive version {} is not supported (this GlassBox reads up to version {})",
+                version, ARCHIVE_VERSION
+            )));
+        }
+
+        Ok(serde_json::from_value(value)?)
+    }
+
+    pub fn filename(&self) -> String {
+        let date = self.exported_at.get(..10).unwrap_or("export");
+        format!("glassbox-{}-{}.json", self.profile.username, date)
+    }
+
+    // What the offline evaluator scores with besides the library; the rows
+    // are never stored, so any user will do
+    pub fn signals(&self) -> Signals {
+        let user = Thing::from(("user", "archive"));
+        Signals {
+            watchlist: self.watchlist.iter().map(|w| w.row(&user)).collect(),
+            notes: self.notes.iter().filter_map(|n| n.row(&user)).collect(),
+            searches: self.searches.iter().map(|s| s.row(&user)).collect(),
+        }
+    }
+}
+
+pub async fn export_archive(user_id: Thing) -> Result<Archive, GlassBoxError> {
+    export_archive_with(&SurrealStore, user_id).await
+}
+
+pub async fn export_archive_with<S: Store>(store: &S, user_id: Thing) -> Result<Archive, GlassBoxError> {
+    let user = store
+        .find_user(&user_id)
+        .await?
+        .ok_or(GlassBoxError::NotFound("Your account".to_string()))?;
+
+    let interactions: Vec<ArchivedInteraction> = store
+        .user_interactions(&user_id)
+        .await?
+        .into_iter()
+        .map(|i| ArchivedInteraction {
+            video_id: i.video_id,
+            video_title: i.video_title,
+            interaction_type: i.interaction_type,
+            timestamp: i.timestamp,
+            count: i.count,
+            last_at: i.last_at,
+        })
+        .collect();
+
+    let searches: Vec<ArchivedSearch> = store
+        .searches(&user_id)
+        .await?
+        .into_iter()
+        .map(|s| ArchivedSearch {
+            query: s.query,
+            timestamp: s.timestamp,
+        })
+        .collect();
+
+    let ratings: Vec<ArchivedRating> = store
+        .ratings(&user_id)
+        .await?
+        .into_iter()
+        .map(|r| ArchivedRating {
+            video_id: r.video_id,
+            video_title: r.video_title,
+            score: r.score,
+            source: r.source,
+            timestamp: r.timestamp,
+        })
+        .collect();
+
+    let watchlist: Vec<ArchivedWatch> = store
+        .watchlist(&user_id)
+        .await?
+        .into_iter()
+        .map(|e| ArchivedWatch {
+            video_id: e.video_id,
+            video_title: e.video_title,
+            state: e.state,
+            added_at: e.added_at,
+            timestamp: e.timestamp,
+        })
+        .collect();
+
+    let progress: Vec<ArchivedProgress> = store
+        .progress(&user_id)
+        .await?
+        .into_iter()
+        .map(|p| ArchivedProgress {
+            video_id: p.video_id,
+            video_title: p.video_title,
+            watched: p.watched,
+            timestamp: p.timestamp,
+        })
+        .collect();
+
+    let lists: Vec<SharedList> = store.lists(&user_id).await?.iter().map(SharedList::from_list).collect();
+
+    let notes: Vec<ArchivedNote> = store
+        .notes(&user_id)
+        .await?
+        .into_iter()
+        .map(|n| ArchivedNote {
+            video_id: n.video_id,
+            video_title: n.video_title,
+            text: n.text,
+            tags: n.tags,
+            timestamp: n.timestamp,
+        })
+        .collect();
+
+    let library: Vec<Video> = store.library(&user_id).await?;
+
+    let settings = load_config_with(store, user_id).await?;
+
+    Ok(Archive {
+        format: ARCHIVE_FORMAT.to_string(),
+        version: ARCHIVE_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        profile: ArchivedProfile {
+            username: user.username,
+            email: user.email,
+            joined_at: user.joined_at,
+        },
+        library,
+        interactions,
+        searches,
+        ratings,
+        watchlist,
+        progress,
+        lists,
+        notes,
+        settings,
+    })
+}
+
+// Builds the archive and hands it to the browser as a .json download
+pub async fn download_archive(user_id: Thing) -> Result<Archive, GlassBoxError> {
+    let archive = export_archive(user_id).await?;
+    download_text(&archive.filename(), "application/json", &archive.to_json()?)?;
+    Ok(archive)
+}
+
+// <------- Import ------->
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportMode {
+    // Keep what is already here and add what's missing
+    Merge,
+    // Swap this user's history, searches, ratings, watch states, episode progress, lists, notes
+    // and settings for the archive's, all at once
+    Replace,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    pub library: usize,
+    pub interactions: usize,
+    pub searches: usize,
+    pub ratings: usize,
+    pub watchlist: usize,
+    pub progress: usize,
+    pub lists: usize,
+    pub notes: usize,
+    pub settings: bool,
+    pub skipped: usize, // Duplicates (merge) or invalid rows
+}
+
+pub async fn import_archive(user_id: Thing, archive: &Archive, mode: ImportMode) -> Result<ImportReport, GlassBoxError> {
+    // Imported rows are sealed with this user's key, so it has to be in memory
+    if !is_unlocked(&user_id.to_string()) {
+        return Err(GlassBoxError::VaultLocked);
+    }
+    import_archive_with(&SurrealStore, user_id, archive, mode).await
+}
+
+// <------- Archived rows back into store rows, owned by the importing user ------->
+
+impl ArchivedInteraction {
+    fn row(&self, user: &Thing) -> InteractionEntry {
+        InteractionEntry {
+            id: None,
+            user: user.clone(),
+            video_id: self.video_id.clone(),
+            video_title: self.video_title.clone(),
+            interaction_type: self.interaction_type.clone(),
+            timestamp: self.timestamp.clone(),
+            count: self.count.max(1),
+            last_at: self.last_at.clone(),
+        }
+    }
+}
+
+impl ArchivedSearch {
+    fn row(&self, user: &Thing) -> SearchEntry {
+        SearchEntry {
+            id: None,
+            user: user.clone(),
+            query: self.query.clone(),
+            timestamp: self.timestamp.clone(),
+        }
+    }
+}
+
+impl ArchivedRating {
+    // None for a score outside 1-10
+    fn row(&self, user: &Thing) -> Option<Rating> {
+        (1.0..=10.0).contains(&self.score).then(|| Rating {
+            id: None,
+            user: user.clone(),
+            video_id: self.video_id.clone(),
+            video_title: self.video_title.clone(),
+            score: self.score,
+            source: self.source.clone(),
+            timestamp: self.timestamp.clone(),
+        })
+    }
+}
+
+impl ArchivedWatch {
+    fn row(&self, user: &Thing) -> WatchEntry {
+        WatchEntry {
+            id: None,
+            user: user.clone(),
+            video_id: self.video_id.clone(),
+            video_title: self.video_title.clone(),
+            state: self.state,
+            added_at: self.added_at.clone(),
+            timestamp: self.timestamp.clone(),
+        }
+    }
+}
+
+impl ArchivedProgress {
+    fn row(&self, user: &Thing) -> EpisodeProgress {
+        EpisodeProgress {
+            id: None,
+            user: user.clone(),
+            video_id: self.video_id.clone(),
+            video_title: self.video_title.clone(),
+            watched: self.watched.clone(),
+            timestamp: self.timestamp.clone(),
+        }
+    }
+}
+
+impl ArchivedNote {
+    // None for a note with neither text nor tags
+    fn row(&self, user: &Thing) -> Option<TitleNote> {
+        let tags = parse_tags(&self.tags.join(","));
+        let text = self.text.trim().to_string();
+        (!text.is_empty() || !tags.is_empty()).then(|| TitleNote {
+            id: None,
+            user: user.clone(),
+            video_id: self.video_id.clone(),
+            video_title: self.video_title.clone(),
+            text,
+            tags,
+            timestamp: self.timestamp.clone(),
+        })
+    }
+}
+
+fn list_row(user: &Thing, list: &SharedList) -> UserList {
+    let now = chrono::Utc::now().to_rfc3339();
+    UserList {
+        id: None,
+        user: user.clone(),
+        name: list.name.clone(),
+        description: list.description.clone(),
+        items: list
+            .items
+            .iter()
+            .map(|i| ListItem {
+                video_id: i.video_id.clone(),
+                video_title: i.title.clone(),
+                note: i.note.clone(),
+            })
+            .collect(),
+        created_at: now.clone(),
+        timestamp: now,
+    }
+}
+
+// Every record is re-created under `user_id`, whoever exported the file
+pub async fn import_archive_with<S: Store>(
+    store: &S,
+    user_id: Thing,
+    archive: &Archive,
+    mode: ImportMode,
+) -> Result<ImportReport, GlassBoxError> {
+    let report = ImportReport::default();
+
+    // Check before touching anything, so a bad file changes nothing
+    archive.settings.validate()?;
+
+    match mode {
+        ImportMode::Replace => replace_from_archive(store, user_id, archive, report).await,
+        ImportMode::Merge => merge_from_archive(store, user_id, archive, report).await,
+    }
+}
+
+// Builds the whole new history first, then swaps it in with one replace_history
+async fn replace_from_archive<S: Store>(store: &S, user_id: Thing, archive: &Archive, mut report: ImportReport) -> Result<ImportReport, GlassBoxError> {
+    let ratings: Vec<Rating> = archive.ratings.iter().filter_map(|r| r.row(&user_id)).collect();
+    let notes: Vec<TitleNote> = archive.notes.iter().filter_map(|n| n.row(&user_id)).collect();
+    report.skipped += (archive.ratings.len() - ratings.len()) + (archive.notes.len() - notes.len());
+
+    // The retention policy is a choice made on this browser, not part of the archive
+    let retention = store.load_settings(&user_id).await?.map(|c| c.retention).unwrap_or_default();
+
+    let history = UserHistory {
+        interactions: archive.interactions.iter().map(|i| i.row(&user_id)).collect(),
+        searches: archive.searches.iter().map(|s| s.row(&user_id)).collect(),
+        ratings,
+        watchlist: archive.watchlist.iter().map(|w| w.row(&user_id)).collect(),
+        progress: archive.progress.iter().map(|p| p.row(&user_id)).collect(),
+        lists: archive.lists.iter().map(|l| list_row(&user_id, l)).collect(),
+        notes,
+        library: archive.library.iter().map(|v| Video { id: None, ..v.clone() }).collect(),
+        settings: Some(UserSettings {
+            id: None,
+            user: user_id.clone(),
+            recommender: archive.settings.clone(),
+            retention,
+        }),
+    };
+    report.interactions = history.interactions.len();
+    report.searches = history.searches.len();
+    report.ratings = history.ratings.len();
+    report.watchlist = history.watchlist.len();
+    report.progress = history.progress.len();
+    report.lists = history.lists.len();
+    report.notes = history.notes.len();
+    report.library = history.library.len();
+    report.settings = true;
+
+    store.replace_history(&user_id, history).await?;
+    Ok(report)
+}
+
+// Picks what the archive adds first, then writes it with one merge_history,
+// so a failure part-way leaves the local history as it was
+async fn merge_from_archive<S: Store>(store: &S, user_id: Thing, archive: &Archive, mut report: ImportReport) -> Result<ImportReport, GlassBoxError> {
+    // The archived copy of a show is as good as the cached one
+    let mut history = UserHistory {
+        library: archive.library.iter().map(|v| Video { id: None, ..v.clone() }).collect(),
+        ..Default::default()
+    };
+
+    // An event is a duplicate if the same thing happened at the same moment
+    let existing: HashSet<(String, String, String)> = store
+        .user_interactions(&user_id)
+        .await?
+        .into_iter()
+        .map(|i| (i.video_id, i.interaction_type, i.timestamp))
+        .collect();
+    for i in &archive.interactions {
+        if existing.contains(&(i.video_id.clone(), i.interaction_type.clone(), i.timestamp.clone())) {
+            report.skipped += 1;
+            continue;
+        }
+        history.interactions.push(i.row(&user_id));
+    }
+
+    let existing: HashSet<(String, String)> = store
+        .searches(&user_id)
+        .await?
+        .into_iter()
+        .map(|s| (s.query, s.timestamp))
+        .collect();
+    for s in &archive.searches {
+        if existing.contains(&(s.query.clone(), s.timestamp.clone())) {
+            report.skipped += 1;
+            continue;
+        }
+        history.searches.push(s.row(&user_id));
+    }
+
+    // One rating per show: the newer of (local, archived) wins
+    let existing: Vec<Rating> = store.ratings(&user_id).await?;
+    for r in &archive.ratings {
+        let newer_here = existing
+            .iter()
+            .any(|e| e.video_id == r.video_id && e.timestamp >= r.timestamp);
+        match r.row(&user_id) {
+            Some(rating) if !newer_here => history.ratings.push(rating),
+            _ => report.skipped += 1,
+        }
+    }
+
+    // One state per show, same rule as ratings
+    let existing: Vec<WatchEntry> = store.watchlist(&user_id).await?;
+    for w in &archive.watchlist {
+        if existing.iter().any(|e| e.video_id == w.video_id && e.timestamp >= w.timestamp) {
+            report.skipped += 1;
+            continue;
+        }
+        history.watchlist.push(w.row(&user_id));
+    }
+
+    // Progress is one row per show as well: the newer one wins
+    let existing: Vec<EpisodeProgress> = store.progress(&user_id).await?;
+    for p in &archive.progress {
+        if existing.iter().any(|e| e.video_id == p.video_id && e.timestamp >= p.timestamp) {
+            report.skipped += 1;
+            continue;
+        }
+        history.progress.push(p.row(&user_id));
+    }
+
+    // Lists are matched by name: merge keeps the local one
+    let existing: Vec<UserList> = store.lists(&user_id).await?;
+    for l in &archive.lists {
+        if existing.iter().any(|e| e.name.eq_ignore_ascii_case(&l.name)) {
+            report.skipped += 1;
+            continue;
+        }
+        history.lists.push(list_row(&user_id, l));
+    }
+
+    // One note per show: the newer one wins, like ratings
+    let existing: Vec<TitleNote> = store.notes(&user_id).await?;
+    for n in &archive.notes {
+        let newer_here = existing.iter().any(|e| e.video_id == n.video_id && e.timestamp >= n.timestamp);
+        match n.row(&user_id) {
+            Some(note) if !newer_here => history.notes.push(note),
+            _ => report.skipped += 1,
+        }
+    }
+
+    // Merge never overrides settings the user already tuned here
+    if store.load_settings(&user_id).await?.is_none() {
+        history.settings = Some(UserSettings {
+            id: None,
+            user: user_id.clone(),
+            recommender: archive.settings.clone(),
+            retention: Default::default(),
+        });
+    }
+
+    report.library = history.library.len();
+    report.interactions = history.interactions.len();
+    report.searches = history.searches.len();
+    report.ratings = history.ratings.len();
+    report.watchlist = history.watchlist.len();
+    report.progress = history.progress.len();
+    report.lists = history.lists.len();
+    report.notes = history.notes.len();
+    report.settings = history.settings.is_some();
+
+    store.merge_history(&user_id, history).await?;
+    Ok(report)
+}
+
+// Native loader for the offline evaluator (src/bin/evaluate.rs)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_archive(path: &std::path::Path) -> Result<Archive, GlassBoxError> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| GlassBoxError::NotFound(format!("{} ({})", path.display(), e)))?;
+    Archive::from_json(&json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::store::MemoryStore;
+    use futures::executor::block_on;
+
+    fn archive() -> Archive {
+        Archive {
+            format: ARCHIVE_FORMAT.to_string(),
+            version: ARCHIVE_VERSION,
+            exported_at: "2025-02-01T00:00:00+00:00".to_string(),
+            profile: ArchivedProfile {
+                username: "ann".to_string(),
+                email: "ann@example.org".to_string(),
+                joined_at: "2025-01-01T00:00:00+00:00".to_string(),
+            },
+            library: Vec::new(),
+            interactions: Vec::new(),
+            searches: Vec::new(),
+            ratings: Vec::new(),
+            watchlist: Vec::new(),
+            progress: Vec::new(),
+            lists: Vec::new(),
+            notes: Vec::new(),
+            settings: RecommenderConfig::default(),
+        }
+    }
+
+    fn archived_rating(video_id: &str, score: f64) -> ArchivedRating {
+        ArchivedRating {
+            video_id: video_id.to_string(),
+            video_title: format!("Show {}", video_id),
+            score,
+            source: "glassbox".to_string(),
+            timestamp: "2025-01-10T00:00:00+00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn replace_swaps_the_whole_history() {
+        let store = MemoryStore::new();
+        let user = Thing::from(("user", "ann"));
+        block_on(store.add_search(SearchEntry {
+            id: None,
+            user: user.clone(),
+            query: "old".to_string(),
+            timestamp: "2024-01-01T00:00:00+00:00".to_string(),
+        }))
+        .unwrap();
+
+        let mut incoming = archive();
+        incoming.searches = vec![ArchivedSearch { query: "new".to_string(), timestamp: "2025-01-01T00:00:00+00:00".to_string() }];
+        incoming.ratings = vec![archived_rating("1", 8.0), archived_rating("2", 42.0)];
+
+        let report = block_on(import_archive_with(&store, user.clone(), &incoming, ImportMode::Replace)).unwrap();
+        assert_eq!((report.searches, report.ratings, report.skipped), (1, 1, 1));
+        assert!(report.settings);
+
+        let searches = block_on(store.searches(&user)).unwrap();
+        assert_eq!(searches.iter().map(|s| s.query.as_str()).collect::<Vec<_>>(), vec!["new"]);
+        assert_eq!(block_on(store.ratings(&user)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn merge_adds_only_what_is_new() {
+        let store = MemoryStore::new();
+        let user = Thing::from(("user", "ann"));
+        // Rated here after the archive was made, so the local score stays
+        block_on(store.save_rating(Rating {
+            id: None,
+            user: user.clone(),
+            video_id: "1".to_string(),
+            video_title: "Show 1".to_string(),
+            score: 9.0,
+            source: "glassbox".to_string(),
+            timestamp: "2025-03-01T00:00:00+00:00".to_string(),
+        }))
+        .unwrap();
+
+        let mut incoming = archive();
+        incoming.searches = vec![ArchivedSearch { query: "new".to_string(), timestamp: "2025-01-01T00:00:00+00:00".to_string() }];
+        incoming.ratings = vec![archived_rating("1", 4.0), archived_rating("2", 8.0)];
+
+        let report = block_on(import_archive_with(&store, user.clone(), &incoming, ImportMode::Merge)).unwrap();
+        assert_eq!((report.searches, report.ratings, report.skipped), (1, 1, 1));
+        assert!(report.settings);
+
+        // A second merge of the same file finds nothing new
+        let again = block_on(import_archive_with(&store, user.clone(), &incoming, ImportMode::Merge)).unwrap();
+        assert_eq!((again.searches, again.ratings, again.skipped, again.settings), (0, 0, 3, false));
+
+        assert_eq!(block_on(store.searches(&user)).unwrap().len(), 1);
+        let mut scores: Vec<(String, f64)> = block_on(store.ratings(&user)).unwrap().into_iter().map(|r| (r.video_id, r.score)).collect();
+        scores.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(scores, vec![("1".to_string(), 9.0), ("2".to_string(), 8.0)]);
+    }
+
+    #[test]
+    fn a_bad_archive_changes_nothing() {
+        let store = MemoryStore::new();
+        let user = Thing::from(("user", "ann"));
+        block_on(store.save_rating(Rating {
+            id: None,
+            user: user.clone(),
+            video_id: "1".to_string(),
+            video_title: "Show 1".to_string(),
+            score: 7.0,
+            source: "glassbox".to_string(),
+            timestamp: "2024-01-01T00:00:00+00:00".to_string(),
+        }))
+        .unwrap();
+
+        let mut incoming = archive();
+        incoming.settings.default_weight = -1.0;
+        assert!(block_on(import_archive_with(&store, user.clone(), &incoming, ImportMode::Replace)).is_err());
+        assert_eq!(block_on(store.ratings(&user)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn the_evaluator_reads_what_the_browser_exports() {
+        let path = std::env::temp_dir().join(format!("glassbox-archive-{}.json", std::process::id()));
+        std::fs::write(&path, archive().to_json().unwrap()).unwrap();
+        let loaded = load_archive(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(loaded.unwrap().profile.username, "ann");
+    }
+
+    #[test]
+    fn newer_archives_are_refused() {
+        let json = archive().to_json().unwrap().replace(
+            &format!("\"version\": {}", ARCHIVE_VERSION),
+            &format!("\"version\": {}", ARCHIVE_VERSION + 1),
+        );
+        assert!(matches!(Archive::from_json(&json), Err(GlassBoxError::Validation(_))));
+    }
+
+    #[test]
+    fn version_one_archives_still_read() {
+        let mut value = serde_json::to_value(archive()).unwrap();
+        let fields = value.as_object_mut().unwrap();
+        fields.insert("version".to_string(), 1.into());
+        for added in ["watchlist", "progress", "lists", "notes"] {
+            fields.remove(added);
+        }
+        let old = Archive::from_json(&value.to_string()).unwrap();
+        assert_eq!(old.version, 1);
+        assert_eq!(old.library.len(), archive().library.len());
+        assert!(old.watchlist.is_empty() && old.notes.is_empty());
+    }
+}
@@ -0,0 +1,397 @@
+// <------- Full backup of everything on this browser ------->
+// The archive (archive.rs) is one user's history in plaintext, without record
+// IDs. A backup is the opposite: every local account exactly as stored (IDs,
+// password hashes, vault salts, sealed rows), so after a restore each account
+// logs in and decrypts as before. IndexedDB can be evicted by the browser; this
+// file is what survives that.
+//
+// The TVMaze caches (person, credited, trending) are left out: they refill
+// from the API on their own.
+
+use crate::model::config::UserSettings;
+use crate::model::crypto::{decode_bytes, decrypt, derive_key, encode_bytes, encrypt, is_sealed, random_bytes, sha256_hex, SALT_LEN};
+use crate::model::db::db_ready;
+use crate::model::download::download_text;
+use crate::model::episodes::EpisodeProgress;
+use crate::model::error::GlassBoxError;
+use crate::model::history::{InteractionEntry, SearchEntry};
+use crate::model::lists::UserList;
+use crate::model::migrations::{latest_version, run_migrations};
+use crate::model::notes::TitleNote;
+use crate::model::ratings::Rating;
+use crate::model::session::clear_stored_session;
+use crate::model::users::User;
+use crate::model::vault::{lock, VaultMeta};
+use crate::model::video::{LibraryItem, Video};
+use crate::model::watchlist::WatchEntry;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+// Tells a GlassBox backup apart from an archive or any other JSON file
+pub const BACKUP_FORMAT: &str = "glassbox-backup";
+
+// Bump when the layout changes, and teach open_backup to read the old one
+pub const BACKUP_VERSION: u32 = 1;
+
+// Every row of every backed-up table; field names are the table names
+#[derive(Serialize, Deserialize)]
+struct BackupRows {
+    user: Vec<User>,
+    vault: Vec<VaultMeta>,
+    settings: Vec<UserSettings>,
+    // Only rows of accounts that haven't signed in since libraries moved (vault.rs)
+    video: Vec<Video>,
+    // Backups made before per-user libraries don't have this table
+    #[serde(default)]
+    library_item: Vec<LibraryItem>,
+    interaction: Vec<InteractionEntry>,
+    search_history: Vec<SearchEntry>,
+    rating: Vec<Rating>,
+    watchlist: Vec<WatchEntry>,
+    episode_progress: Vec<EpisodeProgress>,
+    user_list: Vec<UserList>,
+    title_note: Vec<TitleNote>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TableManifest {
+    pub name: String,
+    pub rows: usize,
+    pub sha256: String, // of the table's rows as JSON
+}
+
+// Readable even when the rows are sealed with a passphrase
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BackupManifest {
+    pub created_at: String,
+    pub schema_version: u32,
+    pub accounts: usize,
+    pub encrypted: bool,
+    pub tables: Vec<TableManifest>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupFile {
+    pub format: String,
+    pub version: u32,
+    pub manifest: BackupManifest,
+    // Table name -> rows; None when a passphrase sealed them into `sealed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tables: Option<Map<String, Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sealed: Option<String>,
+    // base64 salt the passphrase key was derived with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub salt: Option<String>,
+}
+
+impl BackupFile {
+    pub fn to_json(&self) -> Result<String, GlassBoxError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn filename(&self) -> String {
+        let date = self.manifest.created_at.get(..10).unwrap_or("backup");
+        format!("glassbox-backup-{}.json", date)
+    }
+}
+
+// A backup whose checksums all matched; nothing has been written yet
+pub struct VerifiedBackup {
+    pub manifest: BackupManifest,
+    tables: Map<String, Value>,
+}
+
+// What a restore would do to one table
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableDiff {
+    pub name: String,
+    pub added: usize,
+    pub changed: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RestorePlan {
+    pub accounts: Vec<String>, // usernames in the backup
+    pub tables: Vec<TableDiff>,
+}
+
+impl RestorePlan {
+    pub fn is_noop(&self) -> bool {
+        self.tables.iter().all(|t| t.added + t.changed + t.removed == 0)
+    }
+}
+
+// Raw rows straight from IndexedDB, sealed or not (like vault::load_rows, for everyone)
+async fn load_all() -> Result<BackupRows, GlassBoxError> {
+    let db = db_ready().await?;
+    let mut response = db
+        .query("SELECT * FROM user")
+        .query("SELECT * FROM vault")
+        .query("SELECT * FROM settings")
+        .query("SELECT * FROM video")
+        .query("SELECT * FROM library_item")
+        .query("SELECT * FROM interaction")
+        .query("SELECT * FROM search_history")
+        .query("SELECT * FROM rating")
+        .query("SELECT * FROM watchlist")
+        .query("SELECT * FROM episode_progress")
+        .query("SELECT * FROM user_list")
+        .query("SELECT * FROM title_note")
+        .await?;
+    Ok(BackupRows {
+        user: response.take(0)?,
+        vault: response.take(1)?,
+        settings: response.take(2)?,
+        video: response.take(3)?,
+        library_item: response.take(4)?,
+        interaction: response.take(5)?,
+        search_history: response.take(6)?,
+        rating: response.take(7)?,
+        watchlist: response.take(8)?,
+        episode_progress: response.take(9)?,
+        user_list: response.take(10)?,
+        title_note: response.take(11)?,
+    })
+}
+
+fn to_tables(rows: BackupRows) -> Result<Map<String, Value>, GlassBoxError> {
+    match serde_json::to_value(rows)? {
+        Value::Object(map) => Ok(map),
+        _ => Err(GlassBoxError::Parse("backup rows are not an object".to_string())),
+    }
+}
+
+async fn table_manifest(name: &str, rows: &Value) -> Result<TableManifest, GlassBoxError> {
+    Ok(TableManifest {
+        name: name.to_string(),
+        rows: rows.as_array().map(|r| r.len()).unwrap_or(0),
+        sha256: sha256_hex(&serde_json::to_string(rows)?).await?,
+    })
+}
+
+// <------- Backup ------->
+
+// A blank passphrase leaves the rows readable (private history is still sealed
+// per account by the vault; the passphrase also hides the rest)
+pub async fn create_backup(passphrase: Option<&str>) -> Result<BackupFile, GlassBoxError> {
+    let tables = to_tables(load_all().await?)?;
+
+    let mut manifests = Vec::with_capacity(tables.len());
+    for (name, rows) in &tables {
+        manifests.push(table_manifest(name, rows).await?);
+    }
+    let accounts = tables.get("user").and_then(|u| u.as_array()).map(|u| u.len()).unwrap_or(0);
+
+    let passphrase = passphrase.map(str::trim).filter(|p| !p.is_empty());
+    let manifest = BackupManifest {
+        created_at: chrono::Utc::now().to_rfc3339(),
+        schema_version: latest_version(),
+        accounts,
+        encrypted: passphrase.is_some(),
+        tables: manifests,
+    };
+
+    let (tables, sealed, salt) = match passphrase {
+        Some(p) => {
+            let salt = random_bytes(SALT_LEN)?;
+            let key = derive_key(p, &salt).await?;
+            let sealed = encrypt(&key, &serde_json::to_string(&tables)?).await?;
+            (None, Some(sealed), Some(encode_bytes(&salt)))
+        }
+        None => (Some(tables), None, None),
+    };
+
+    Ok(BackupFile {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        manifest,
+        tables,
+        sealed,
+        salt,
+    })
+}
+
+pub async fn download_backup(passphrase: Option<&str>) -> Result<BackupFile, GlassBoxError> {
+    let backup = create_backup(passphrase).await?;
+    download_text(&backup.filename(), "application/json", &backup.to_json()?)?;
+    leptos::logging::log!("BACKUP: Saved {} accounts to {}", backup.manifest.accounts, backup.filename());
+    Ok(backup)
+}
+
+// <------- Restore ------->
+
+// Checks the format, unseals with the passphrase and verifies every table
+// against the manifest. Nothing is written here.
+pub async fn open_backup(json: &str, passphrase: Option<&str>) -> Result<VerifiedBackup, GlassBoxError> {
+    let value: Value = serde_json::from_str(json)?;
+    if value.get("format").and_then(|f| f.as_str()) != Some(BACKUP_FORMAT) {
+        return Err(GlassBoxError::Validation("This file is not a GlassBox backup".to_string()));
+    }
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version == 0 || version > BACKUP_VERSION as u64 {
+        return Err(GlassBoxError::Validation(format!(
+            "Backup version {} is not supported (this GlassBox reads up to version {})",
+            version, BACKUP_VERSION
+        )));
+    }
+    let file: BackupFile = serde_json::from_value(value)?;
+    if file.manifest.schema_version > latest_version() {
+        return Err(GlassBoxError::Validation(format!(
+            "This backup was made by a newer GlassBox (schema v{}, this one knows up to v{})",
+            file.manifest.schema_version,
+            latest_version()
+        )));
+    }
+
+    let tables = match (file.tables, file.sealed, file.salt) {
+        (Some(tables), _, _) => tables,
+        (None, Some(sealed), Some(salt)) if is_sealed(&sealed) => {
+            let Some(p) = passphrase.map(str::trim).filter(|p| !p.is_empty()) else {
+                return Err(GlassBoxError::Validation("This backup is protected: enter its passphrase".to_string()));
+            };
+            let key = derive_key(p, &decode_bytes(&salt)?).await?;
+            // A wrong key fails the GCM tag check
+            let json = decrypt(&key, &sealed)
+                .await
+                .map_err(|_| GlassBoxError::Validation("Wrong passphrase, or the file is damaged".to_string()))?;
+            serde_json::from_str(&json)?
+        }
+        _ => return Err(GlassBoxError::Validation("This backup has no data in it".to_string())),
+    };
+
+    for expected in &file.manifest.tables {
+        let Some(rows) = tables.get(&expected.name) else {
+            return Err(GlassBoxError::Validation(format!("The backup is missing the {} table", expected.name)));
+        };
+        let actual = table_manifest(&expected.name, rows).await?;
+        if actual != *expected {
+            return Err(GlassBoxError::Validation(format!(
+                "The {} table failed its integrity check: the file is damaged or was edited",
+                expected.name
+            )));
+        }
+    }
+    if let Some(extra) = tables.keys().find(|k| !file.manifest.tables.iter().any(|t| &t.name == *k)) {
+        return Err(GlassBoxError::Validation(format!("The {} table is not in the backup's manifest", extra)));
+    }
+
+    // Every row has to load as this build's types before anything is touched
+    serde_json::from_value::<BackupRows>(Value::Object(tables.clone()))?;
+
+    Ok(VerifiedBackup {
+        manifest: file.manifest,
+        tables,
+    })
+}
+
+// Rows keyed by record id, for the diff
+fn by_id(rows: Option<&Value>) -> HashMap<String, &Value> {
+    rows.and_then(|r| r.as_array())
+        .map(|rows| rows.iter().map(|row| (row.get("id").map(|id| id.to_string()).unwrap_or_default(), row)).collect())
+        .unwrap_or_default()
+}
+
+// The dry run: what a restore would add, change and remove, table by table
+pub async fn plan_restore(backup: &VerifiedBackup) -> Result<RestorePlan, GlassBoxError> {
+    let current = to_tables(load_all().await?)?;
+
+    let tables = backup
+        .manifest
+        .tables
+        .iter()
+        .map(|t| {
+            let incoming = by_id(backup.tables.get(&t.name));
+            let existing = by_id(current.get(&t.name));
+            let mut diff = TableDiff {
+                name: t.name.clone(),
+                ..Default::default()
+            };
+            for (id, row) in &incoming {
+                match existing.get(id) {
+                    None => diff.added += 1,
+                    Some(old) if old != row => diff.changed += 1,
+                    Some(_) => diff.unchanged += 1,
+                }
+            }
+            diff.removed = existing.keys().filter(|id| !incoming.contains_key(*id)).count();
+            diff
+        })
+        .collect();
+
+    let accounts = backup
+        .tables
+        .get("user")
+        .and_then(|u| u.as_array())
+        .map(|users| users.iter().filter_map(|u| u.get("username")?.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+
+    Ok(RestorePlan { accounts, tables })
+}
+
+// Replaces every backed-up table with the backup's rows, all-or-nothing. Keys
+// in memory may belong to accounts that changed, so the vault is locked after
+// and the remembered session dropped (its account may not be in the backup).
+pub async fn restore_backup(backup: VerifiedBackup) -> Result<(), GlassBoxError> {
+    let rows: BackupRows = serde_json::from_value(Value::Object(backup.tables))?;
+    let db = db_ready().await?;
+
+    // Rows from an older schema are marked as such, so the migrations after
+    // them run again below (or on the next start if that fails)
+    let schema = backup.manifest.schema_version;
+    let schema_sql = if schema < latest_version() {
+        "UPSERT schema_version:current CONTENT { version: $schema, applied_at: $applied_at };"
+    } else {
+        ""
+    };
+    let sql = format!(
+        "BEGIN TRANSACTION;
+         DELETE user; DELETE vault; DELETE settings; DELETE video; DELETE library_item;
+         DELETE interaction; DELETE search_history; DELETE rating; DELETE watchlist;
+         DELETE episode_progress; DELETE user_list; DELETE title_note;
+         FOR $row IN $users {{ UPSERT $row.id CONTENT $row; }};
+         FOR $row IN $vaults {{ UPSERT $row.id CONTENT $row; }};
+         FOR $row IN $settings {{ UPSERT $row.id CONTENT $row; }};
+         FOR $row IN $videos {{ UPSERT $row.id CONTENT $row; }};
+         FOR $row IN $library {{ UPSERT $row.id CONTENT $row; }};
+         FOR $row IN $interactions {{ UPSERT $row.id CONTENT $row; }};
+         FOR $row IN $searches {{ UPSERT $row.id CONTENT $row; }};
+         FOR $row IN $ratings {{ UPSERT $row.id CONTENT $row; }};
+         FOR $row IN $watchlist {{ UPSERT $row.id CONTENT $row; }};
+         FOR $row IN $progress {{ UPSERT $row.id CONTENT $row; }};
+         FOR $row IN $lists {{ UPSERT $row.id CONTENT $row; }};
+         FOR $row IN $notes {{ UPSERT $row.id CONTENT $row; }};
+         {}
+         COMMIT TRANSACTION;",
+        schema_sql
+    );
+    db.query(sql)
+        .bind(("users", rows.user))
+        .bind(("vaults", rows.vault))
+        .bind(("settings", rows.settings))
+        .bind(("videos", rows.video))
+        .bind(("library", rows.library_item))
+        .bind(("interactions", rows.interaction))
+        .bind(("searches", rows.search_history))
+        .bind(("ratings", rows.rating))
+        .bind(("watchlist", rows.watchlist))
+        .bind(("progress", rows.episode_progress))
+        .bind(("lists", rows.user_list))
+        .bind(("notes", rows.title_note))
+        .bind(("schema", schema))
+        .bind(("applied_at", chrono::Utc::now().to_rfc3339()))
+        .await?
+        .check()?;
+
+    lock();
+    clear_stored_session();
+    if schema < latest_version() {
+        let version = run_migrations(db).await?;
+        leptos::logging::log!("BACKUP: Migrated the restored rows from schema v{} to v{}", schema, version);
+    }
+    leptos::logging::log!("BACKUP: Restored {} accounts from {}", backup.manifest.accounts, backup.manifest.created_at);
+    Ok(())
+}
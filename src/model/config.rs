@@ -0,0 +1,209 @@
+// <------- Every tunable knob of the recommendation engine lives here ------->
+
+use crate::model::error::GlassBoxError;
+use crate::model::store::{Store, SurrealStore};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct RecommenderConfig {
+    // Multipliers for the newest library items (index 0 = newest)
+    pub recency_multipliers: Vec<f64>,
+    // Weight used for videos without a rating
+    pub default_weight: f64,
+    // Query used when the user vector has no clear top genre
+    pub fallback_query: String,
+    // How many top genres are searched for candidates
+    pub candidate_genres: usize,
+    // Share of the "because you just looked at" score taken from the click
+    // sequence (the rest comes from the long-term genre profile)
+    pub session_blend: f64,
+    // A pause longer than this between clicks starts a new session
+    pub session_gap_minutes: i64,
+    // Extra score for sharing cast & crew with the library (0 = ignore people)
+    pub people_weight: f64,
+    // Library titles the graph walk restarts from
+    pub graph_seeds: usize,
+    // Restart probability of the personalised PageRank walk
+    pub graph_restart: f64,
+    // Unseen shows pulled from the graph into the candidate pool (0 = off)
+    pub graph_candidates: usize,
+    // Taste-vector weight of a show by watch state (see watchlist.rs);
+    // click_weight covers shows that were only clicked or rated
+    pub click_weight: f64,
+    pub want_weight: f64,
+    pub watching_weight: f64,
+    pub watched_weight: f64,
+    pub dropped_weight: f64,
+    // Days without an episode tick before a half-watched show counts as abandoned
+    pub abandon_days: i64,
+    // Extra score for resembling the shows the user tagged (0 = ignore tags)
+    pub tag_weight: f64,
+    // Extra score for matching what the user searches for (0 = ignore searches)
+    pub query_weight: f64,
+}
+
+impl Default for RecommenderConfig {
+    fn default() -> Self {
+        Self {
+            recency_multipliers: vec![5.0, 3.0, 2.0],
+            default_weight: 5.0,
+            fallback_query: "Trending".to_string(),
+            candidate_genres: 1,
+            session_blend: 0.7,
+            session_gap_minutes: 30,
+            people_weight: 0.5,
+            graph_seeds: 5,
+            graph_restart: 0.15,
+            graph_candidates: 8,
+            click_weight: 0.3,
+            want_weight: 0.6,
+            watching_weight: 1.0,
+            watched_weight: 1.5,
+            dropped_weight: 0.0,
+            abandon_days: 60,
+            tag_weight: 0.3,
+            query_weight: 0.1,
+        }
+    }
+}
+
+impl RecommenderConfig {
+    pub fn to_json(&self) -> Result<String, GlassBoxError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    // Used by the settings screen and by offline tools reading a config file
+    pub fn from_json(json: &str) -> Result<Self, GlassBoxError> {
+        let config: Self = serde_json::from_str(json)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), GlassBoxError> {
+        if self.recency_multipliers.iter().any(|m| !m.is_finite() || *m < 0.0) {
+            return Err(GlassBoxError::Validation("Recency multipliers must be positive numbers".to_string()));
+        }
+        if !self.default_weight.is_finite() || self.default_weight <= 0.0 {
+            return Err(GlassBoxError::Validation("Default weight must be greater than 0".to_string()));
+        }
+        if self.fallback_query.trim().is_empty() {
+            return Err(GlassBoxError::Validation("Fallback query cannot be empty".to_string()));
+        }
+        if self.candidate_genres == 0 {
+            return Err(GlassBoxError::Validation("At least one candidate genre is required".to_string()));
+        }
+        if !(0.0..=1.0).contains(&self.session_blend) {
+            return Err(GlassBoxError::Validation("Session blend must be between 0 and 1".to_string()));
+        }
+        if self.session_gap_minutes <= 0 {
+            return Err(GlassBoxError::Validation("Session gap must be at least one minute".to_string()));
+        }
+        if !self.people_weight.is_finite() || self.people_weight < 0.0 {
+            return Err(GlassBoxError::Validation("People weight cannot be negative".to_string()));
+        }
+        if !(self.graph_restart > 0.0 && self.graph_restart < 1.0) {
+            return Err(GlassBoxError::Validation("Graph restart must be between 0 and 1".to_string()));
+        }
+        let state_weights = [self.click_weight, self.want_weight, self.watching_weight, self.watched_weight, self.dropped_weight];
+        if state_weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            return Err(GlassBoxError::Validation("Watch state weights cannot be negative".to_string()));
+        }
+        if self.abandon_days <= 0 {
+            return Err(GlassBoxError::Validation("Abandon after at least one day".to_string()));
+        }
+        if !self.tag_weight.is_finite() || self.tag_weight < 0.0 {
+            return Err(GlassBoxError::Validation("Tag weight cannot be negative".to_string()));
+        }
+        if !self.query_weight.is_finite() || self.query_weight < 0.0 {
+            return Err(GlassBoxError::Validation("Search weight cannot be negative".to_string()));
+        }
+        Ok(())
+    }
+
+    // Multiplier for the item at position `idx` in the newest-first history
+    pub fn recency_multiplier(&self, idx: usize) -> f64 {
+        self.recency_multipliers.get(idx).copied().unwrap_or(1.0)
+    }
+}
+
+// Offered on the History page; nothing is purged until the user saves a policy
+pub const SUGGESTED_SEARCH_DAYS: u32 = 90;
+
+// How long history is kept before it is purged automatically (None = forever)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    pub search_days: Option<u32>,
+    pub interaction_days: Option<u32>,
+}
+
+// One "settings" record per user, keyed by the user's record id
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UserSettings {
+    pub id: Option<Thing>,
+    pub user: Thing,
+    #[serde(default)]
+    pub recommender: RecommenderConfig,
+    #[serde(default)]
+    pub retention: RetentionPolicy,
+}
+
+pub async fn load_config(user_id: Thing) -> Result<RecommenderConfig, GlassBoxError> {
+    load_config_with(&SurrealStore, user_id).await
+}
+
+pub async fn load_config_with<S: Store>(store: &S, user_id: Thing) -> Result<RecommenderConfig, GlassBoxError> {
+    let settings = store.load_settings(&user_id).await?;
+
+    // No record yet -> the user has never touched the settings screen
+    Ok(settings.map(|s| s.recommender).unwrap_or_default())
+}
+
+pub async fn save_config(user_id: Thing, config: RecommenderConfig) -> Result<RecommenderConfig, GlassBoxError> {
+    save_config_with(&SurrealStore, user_id, config).await
+}
+
+pub async fn save_config_with<S: Store>(store: &S, user_id: Thing, config: RecommenderConfig) -> Result<RecommenderConfig, GlassBoxError> {
+    config.validate()?;
+
+    // Keep the rest of the record (retention) as it was
+    let retention = store.load_settings(&user_id).await?.map(|s| s.retention).unwrap_or_default();
+    let settings = UserSettings {
+        id: None,
+        user: user_id,
+        recommender: config,
+        retention,
+    };
+
+    store.save_settings(settings).await.map(|s| s.recommender)
+}
+
+pub async fn load_retention(user_id: Thing) -> Result<RetentionPolicy, GlassBoxError> {
+    load_retention_with(&SurrealStore, user_id).await
+}
+
+pub async fn load_retention_with<S: Store>(store: &S, user_id: Thing) -> Result<RetentionPolicy, GlassBoxError> {
+    Ok(store.load_settings(&user_id).await?.map(|s| s.retention).unwrap_or_default())
+}
+
+pub async fn save_retention(user_id: Thing, retention: RetentionPolicy) -> Result<RetentionPolicy, GlassBoxError> {
+    save_retention_with(&SurrealStore, user_id, retention).await
+}
+
+pub async fn save_retention_with<S: Store>(store: &S, user_id: Thing, retention: RetentionPolicy) -> Result<RetentionPolicy, GlassBoxError> {
+    if retention.search_days == Some(0) || retention.interaction_days == Some(0) {
+        return Err(GlassBoxError::Validation("Keep history for at least one day, or leave it blank to keep it forever".to_string()));
+    }
+
+    let recommender = load_config_with(store, user_id.clone()).await?;
+    let settings = UserSettings {
+        id: None,
+        user: user_id,
+        recommender,
+        retention,
+    };
+
+    store.save_settings(settings).await.map(|s| s.retention)
+}
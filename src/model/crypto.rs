@@ -0,0 +1,159 @@
+// <------- WebCrypto primitives: PBKDF2 -> AES-GCM ------->
+// Sealed strings look like "enc1:<base64(iv || ciphertext)>", so a field can
+// hold either plaintext (rows written before encryption) or ciphertext. Only
+// the vault decides whether plaintext is acceptable; decrypt never passes it on.
+
+use crate::model::error::GlassBoxError;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use js_sys::{Array, Object, Reflect, Uint8Array};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{CryptoKey, SubtleCrypto};
+
+const SEALED_PREFIX: &str = "enc1:";
+
+// OWASP's 2023 floor for PBKDF2-HMAC-SHA256
+const PBKDF2_ITERATIONS: u32 = 310_000;
+
+pub const SALT_LEN: usize = 16;
+pub const ROW_SECRET_LEN: usize = 32;
+const IV_LEN: usize = 12;
+
+fn subtle() -> Result<SubtleCrypto, GlassBoxError> {
+    let window = web_sys::window().ok_or(GlassBoxError::Browser("no window".to_string()))?;
+    Ok(window.crypto()?.subtle())
+}
+
+// Plain JS object literal from (key, value) pairs
+fn js_object(fields: &[(&str, JsValue)]) -> Result<Object, GlassBoxError> {
+    let obj = Object::new();
+    for (k, v) in fields {
+        Reflect::set(&obj, &JsValue::from_str(k), v)?;
+    }
+    Ok(obj)
+}
+
+fn usages(names: &[&str]) -> Array {
+    names.iter().map(|n| JsValue::from_str(n)).collect()
+}
+
+pub fn random_bytes(len: usize) -> Result<Vec<u8>, GlassBoxError> {
+    let mut buf = vec![0u8; len];
+    getrandom::fill(&mut buf).map_err(|e| GlassBoxError::Browser(format!("random: {}", e)))?;
+    Ok(buf)
+}
+
+pub fn is_sealed(value: &str) -> bool {
+    value.starts_with(SEALED_PREFIX)
+}
+
+// Non-extractable AES-256-GCM key; it never leaves this tab's memory
+pub async fn derive_key(password: &str, salt: &[u8]) -> Result<CryptoKey, GlassBoxError> {
+    let subtle = subtle()?;
+
+    let raw = Uint8Array::from(password.as_bytes());
+    let base = JsFuture::from(subtle.import_key_with_str("raw", &raw, "PBKDF2", false, &usages(&["deriveKey"]))?).await?;
+    let base: CryptoKey = base.dyn_into()?;
+
+    let pbkdf2 = js_object(&[
+        ("name", JsValue::from_str("PBKDF2")),
+        ("salt", Uint8Array::from(salt).into()),
+        ("iterations", JsValue::from(PBKDF2_ITERATIONS)),
+        ("hash", JsValue::from_str("SHA-256")),
+    ])?;
+    let aes = js_object(&[("name", JsValue::from_str("AES-GCM")), ("length", JsValue::from(256))])?;
+
+    let key = JsFuture::from(subtle.derive_key_with_object_and_object(
+        &pbkdf2,
+        &base,
+        &aes,
+        false,
+        &usages(&["encrypt", "decrypt"]),
+    )?)
+    .await?;
+    Ok(key.dyn_into()?)
+}
+
+pub async fn encrypt(key: &CryptoKey, plaintext: &str) -> Result<String, GlassBoxError> {
+    let iv = random_bytes(IV_LEN)?;
+    let params = js_object(&[("name", JsValue::from_str("AES-GCM")), ("iv", Uint8Array::from(iv.as_slice()).into())])?;
+
+    let data = Uint8Array::from(plaintext.as_bytes());
+    let sealed = JsFuture::from(subtle()?.encrypt_with_object_and_buffer_source(&params, key, &data)?).await?;
+
+    let mut out = iv;
+    out.extend(Uint8Array::new(&sealed).to_vec());
+    Ok(format!("{}{}", SEALED_PREFIX, STANDARD.encode(out)))
+}
+
+// The base64 part of a sealed value; anything else is not ours to decrypt
+fn sealed_payload(value: &str) -> Result<&str, GlassBoxError> {
+    value
+        .strip_prefix(SEALED_PREFIX)
+        .ok_or(GlassBoxError::Parse("expected a sealed field, found plaintext".to_string()))
+}
+
+// Plaintext is an error, and a wrong key fails the GCM tag check
+pub async fn decrypt(key: &CryptoKey, value: &str) -> Result<String, GlassBoxError> {
+    let encoded = sealed_payload(value)?;
+    let bytes = STANDARD
+        .decode(encoded)
+        .map_err(|e| GlassBoxError::Parse(format!("sealed field: {}", e)))?;
+    if bytes.len() <= IV_LEN {
+        return Err(GlassBoxError::Parse("sealed field is too short".to_string()));
+    }
+    let (iv, ciphertext) = bytes.split_at(IV_LEN);
+
+    let params = js_object(&[("name", JsValue::from_str("AES-GCM")), ("iv", Uint8Array::from(iv).into())])?;
+    let data = Uint8Array::from(ciphertext);
+    let plain = JsFuture::from(subtle()?.decrypt_with_object_and_buffer_source(&params, key, &data)?).await?;
+
+    String::from_utf8(Uint8Array::new(&plain).to_vec()).map_err(|e| GlassBoxError::Parse(format!("sealed field: {}", e)))
+}
+
+// Hex SHA-256 of a string (backup checksums)
+pub async fn sha256_hex(text: &str) -> Result<String, GlassBoxError> {
+    let data = Uint8Array::from(text.as_bytes());
+    let digest = JsFuture::from(subtle()?.digest_with_str_and_buffer_source("SHA-256", &data)?).await?;
+    Ok(to_hex(&Uint8Array::new(&digest).to_vec()))
+}
+
+// Non-extractable HMAC-SHA256 key over a random secret (row IDs, see vault.rs)
+pub async fn import_hmac_key(secret: &[u8]) -> Result<CryptoKey, GlassBoxError> {
+    let raw = Uint8Array::from(secret);
+    let hmac = js_object(&[("name", JsValue::from_str("HMAC")), ("hash", JsValue::from_str("SHA-256"))])?;
+    let key = JsFuture::from(subtle()?.import_key_with_object("raw", &raw, &hmac, false, &usages(&["sign"]))?).await?;
+    Ok(key.dyn_into()?)
+}
+
+// Hex HMAC of a string: the same input always gives the same value
+pub async fn hmac_hex(key: &CryptoKey, text: &str) -> Result<String, GlassBoxError> {
+    let data = Uint8Array::from(text.as_bytes());
+    let mac = JsFuture::from(subtle()?.sign_with_str_and_buffer_source("HMAC", key, &data)?).await?;
+    Ok(to_hex(&Uint8Array::new(&mac).to_vec()))
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn encode_bytes(bytes: &[u8]) -> String {
+    STANDARD.encode(bytes)
+}
+
+pub fn decode_bytes(encoded: &str) -> Result<Vec<u8>, GlassBoxError> {
+    STANDARD.decode(encoded).map_err(|e| GlassBoxError::Parse(format!("base64: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_sealed_values_have_a_payload() {
+        assert_eq!(sealed_payload("enc1:AAAA").unwrap(), "AAAA");
+        assert!(matches!(sealed_payload("Severance"), Err(GlassBoxError::Parse(_))));
+        assert!(!is_sealed("Severance"));
+    }
+}
@@ -1,35 +1,135 @@
-use surrealdb::engine::local::Db;
-use surrealdb::Surreal;
-use surrealdb::engine::local::IndxDb;
+use crate::model::error::GlassBoxError;
+use crate::model::migrations::run_migrations;
+use leptos::prelude::*;
+use futures::future::{select, Either};
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::pin;
 use std::sync::OnceLock;
+use std::task::{Poll, Waker};
+use surrealdb::engine::any::{connect, Any};
+use surrealdb::Surreal;
 
-// Wrapper to allow Surreal<Db> in OnceLock (WASM is single-threaded, so this is safe-ish)
-pub struct SafeSurreal(pub Surreal<Db>);
+// Wrapper to allow Surreal<Any> in OnceLock (WASM is single-threaded, so this is safe-ish)
+pub struct SafeSurreal(pub Surreal<Any>);
 unsafe impl Sync for SafeSurreal {}
 unsafe impl Send for SafeSurreal {}
 
 impl std::ops::Deref for SafeSurreal {
-    type Target = Surreal<Db>;
+    type Target = Surreal<Any>;
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
-pub static DB: OnceLock<SafeSurreal> = OnceLock::new(); 
+pub static DB: OnceLock<SafeSurreal> = OnceLock::new();
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DbStatus {
+    Initialising,
+    Ready,
+    Failed(String),
+}
+
+// Reactive copy of the status for the UI (provided via context in App)
+#[derive(Copy, Clone)]
+pub struct DbState(pub RwSignal<DbStatus>);
+
+impl DbState {
+    pub fn new() -> Self {
+        Self(RwSignal::new(DbStatus::Initialising))
+    }
+}
+
+impl Default for DbState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Non-reactive copy + everyone awaiting db_ready()
+thread_local! {
+    static STATUS: RefCell<DbStatus> = const { RefCell::new(DbStatus::Initialising) };
+    static WAITERS: RefCell<Vec<Waker>> = const { RefCell::new(Vec::new()) };
+}
+
+fn set_status(state: DbState, status: DbStatus) {
+    STATUS.with(|s| *s.borrow_mut() = status.clone());
+    state.0.set(status);
 
-pub async fn init_db() {
+    for waker in WAITERS.with(|w| std::mem::take(&mut *w.borrow_mut())) {
+        waker.wake();
+    }
+}
+
+// Long enough for migrations on a big vault; past it, the caller gets DbNotReady
+const READY_TIMEOUT_MS: u32 = 30_000;
+
+// Resolves once init_db has finished, instead of failing "too early"
+pub async fn db_ready() -> Result<&'static SafeSurreal, GlassBoxError> {
+    db_ready_before(gloo_timers::future::TimeoutFuture::new(READY_TIMEOUT_MS)).await
+}
+
+// Gives up with DbNotReady if `deadline` comes first (e.g. the vault is stuck opening)
+async fn db_ready_before(deadline: impl Future<Output = ()>) -> Result<&'static SafeSurreal, GlassBoxError> {
+    let ready = std::future::poll_fn(|cx| {
+        let status = STATUS.with(|s| s.borrow().clone());
+        match status {
+            DbStatus::Ready => Poll::Ready(DB.get().ok_or(GlassBoxError::DbNotReady)),
+            DbStatus::Failed(reason) => Poll::Ready(Err(GlassBoxError::Database(reason))),
+            DbStatus::Initialising => {
+                WAITERS.with(|w| w.borrow_mut().push(cx.waker().clone()));
+                Poll::Pending
+            }
+        }
+    });
+
+    match select(pin!(ready), pin!(deadline)).await {
+        Either::Left((result, _)) => result,
+        Either::Right(_) => Err(GlassBoxError::DbNotReady),
+    }
+}
+
+pub async fn init_db(state: DbState) {
     // This creates a folder inside the user's browser named "glassbox" via IndexedDB
-    match Surreal::new::<IndxDb>("glassbox").await {
+    // The "any" engine picks IndexedDB from the address; only the browser build
+    // enables it, so native builds (tests) compile without a storage engine
+    match connect("indxdb://glassbox").await {
         Ok(client) => {
             if let Err(e) = client.use_ns("user_private").use_db("history").await {
                 leptos::logging::error!("Failed to select DB: {:?}", e);
+                set_status(state, DbStatus::Failed(e.to_string()));
                 return;
             }
+            // Bring older vaults up to the current schema before anyone reads them
+            match run_migrations(&client).await {
+                Ok(version) => leptos::logging::log!("GlassBox schema: v{}", version),
+                Err(e) => {
+                    leptos::logging::error!("Schema migration failed: {:?}", e);
+                    set_status(state, DbStatus::Failed(e.to_string()));
+                    return;
+                }
+            }
             let _ = DB.set(SafeSurreal(client));
             leptos::logging::log!("GlassBox Secure Vault: ONLINE");
+            set_status(state, DbStatus::Ready);
         },
         Err(e) => {
-            leptos::logging::error!("CRITICAL: Could not init Local DB: {:?}", e);
+            leptos::logging::error!("Could not open local DB: {:?}", e);
+            set_status(state, DbStatus::Failed(e.to_string()));
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn waiting_past_the_deadline_is_db_not_ready() {
+        // Natively init_db never runs, so the vault stays "Initialising"
+        let result = block_on(db_ready_before(std::future::ready(())));
+        assert_eq!(result.err(), Some(GlassBoxError::DbNotReady));
+    }
+}
@@ -0,0 +1,29 @@
+// <------- Save a file from the browser ------->
+// Everything lives in IndexedDB, so "download" means handing the browser a Blob
+// and clicking a temporary <a download> link for the user.
+
+use crate::model::error::GlassBoxError;
+use wasm_bindgen::JsCast;
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+
+pub fn download_text(filename: &str, mime: &str, contents: &str) -> Result<(), GlassBoxError> {
+    let window = web_sys::window().ok_or(GlassBoxError::Browser("no window".to_string()))?;
+    let document = window.document().ok_or(GlassBoxError::Browser("no document".to_string()))?;
+
+    let parts = js_sys::Array::of1(&wasm_bindgen::JsValue::from_str(contents));
+    let options = BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+
+    let link: HtmlAnchorElement = document
+        .create_element("a")?
+        .dyn_into()
+        .map_err(|_| GlassBoxError::Browser("<a> is not an anchor element".to_string()))?;
+    link.set_href(&url);
+    link.set_download(filename);
+    link.click();
+
+    Url::revoke_object_url(&url)?;
+    Ok(())
+}
@@ -0,0 +1,332 @@
+// <------- Episode lists and per-user progress ------->
+// Episode lists are public TVMaze data, cached in episode_list:<show id> like
+// the trending list. Which episodes a user ticked off is their history, so it
+// goes through the Store (sealed in the vault) like ratings and watch states.
+
+use crate::api::episodes::fetch_episodes;
+use crate::model::config::RecommenderConfig;
+use crate::model::db::db_ready;
+use crate::model::error::GlassBoxError;
+use crate::model::store::{Store, SurrealStore};
+use crate::model::video::Video;
+use crate::model::watchlist::WatchState;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use surrealdb::sql::Thing;
+
+// New episodes air weekly at most; a day keeps "next episode" current
+const CACHE_HOURS: i64 = 24;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Episode {
+    pub episode_id: String, // TVMaze episode ID
+    pub season: u32,
+    pub number: u32,
+    pub name: String,
+    pub airdate: Option<NaiveDate>, // None = not announced yet
+    pub runtime: Option<u32>,       // minutes
+}
+
+impl Episode {
+    // "S02E05"
+    pub fn code(&self) -> String {
+        format!("S{:02}E{:02}", self.season, self.number)
+    }
+
+    pub fn has_aired(&self, today: NaiveDate) -> bool {
+        self.airdate.map(|d| d <= today).unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct EpisodeCache {
+    id: Option<Thing>,
+    fetched_at: DateTime<Utc>,
+    episodes: Vec<Episode>,
+}
+
+// Cached list if it is fresh enough, otherwise refetch; a stale list beats none offline
+pub async fn episodes_for(video_id: &str) -> Result<Vec<Episode>, GlassBoxError> {
+    let db = db_ready().await?;
+    let now = Utc::now();
+
+    let cached: Option<EpisodeCache> = db.select(("episode_list", video_id)).await?;
+    if let Some(cache) = &cached {
+        if (now - cache.fetched_at).num_hours() < CACHE_HOURS {
+            return Ok(cache.episodes.clone());
+        }
+    }
+
+    match fetch_episodes(video_id).await {
+        Ok(episodes) => {
+            let cache = EpisodeCache {
+                id: None,
+                fetched_at: now,
+                episodes: episodes.clone(),
+            };
+            let _: Option<EpisodeCache> = db.upsert(("episode_list", video_id)).content(cache).await?;
+            Ok(episodes)
+        }
+        Err(e) => match cached {
+            Some(cache) => {
+                leptos::logging::error!("EPISODES: Refresh failed, using cached list: {:?}", e);
+                Ok(cache.episodes)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+// <------- Progress ------->
+
+// One row per (user, show); `watched` holds TVMaze episode IDs
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EpisodeProgress {
+    pub id: Option<Thing>,
+    pub user: Thing,
+    pub video_id: String,
+    pub video_title: String,
+    pub watched: Vec<String>,
+    pub timestamp: String, // last tick or untick
+}
+
+impl EpisodeProgress {
+    pub fn has_watched(&self, episode_id: &str) -> bool {
+        self.watched.iter().any(|w| w == episode_id)
+    }
+}
+
+// Ticks (or unticks) a batch of episodes: one for a checkbox, a whole season for the season button
+pub async fn set_episodes_watched(user_id: Thing, video: Video, episode_ids: Vec<String>, watched: bool) -> Result<EpisodeProgress, GlassBoxError> {
+    set_episodes_watched_with(&SurrealStore, user_id, video, episode_ids, watched).await
+}
+
+pub async fn set_episodes_watched_with<S: Store>(
+    store: &S,
+    user_id: Thing,
+    video: Video,
+    episode_ids: Vec<String>,
+    watched: bool,
+) -> Result<EpisodeProgress, GlassBoxError> {
+    // The feed reads show metadata from the user's library
+    if !store.library(&user_id).await?.iter().any(|v| v.video_id == video.video_id) {
+        store.save_video(&user_id, video.clone()).await?;
+    }
+
+    let mut ticked: Vec<String> = store
+        .progress(&user_id)
+        .await?
+        .into_iter()
+        .find(|p| p.video_id == video.video_id)
+        .map(|p| p.watched)
+        .unwrap_or_default();
+
+    if watched {
+        for id in episode_ids {
+            if !ticked.contains(&id) {
+                ticked.push(id);
+            }
+        }
+    } else {
+        let remove: HashSet<String> = episode_ids.into_iter().collect();
+        ticked.retain(|id| !remove.contains(id));
+    }
+
+    let progress = EpisodeProgress {
+        id: None,
+        user: user_id,
+        video_id: video.video_id,
+        video_title: video.title,
+        watched: ticked,
+        timestamp: Utc::now().to_rfc3339(),
+    };
+    store.save_progress(progress).await
+}
+
+// Newest activity first
+pub async fn get_progress(user_id: Thing) -> Result<Vec<EpisodeProgress>, GlassBoxError> {
+    SurrealStore.progress(&user_id).await
+}
+
+// <------- Show-level signals derived from progress ------->
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShowSignal {
+    NotStarted,
+    // Ticks are recent, or the user is caught up and waiting for new episodes
+    InProgress,
+    // Every episode of an ended run is ticked
+    Finished,
+    // Aired episodes left and no tick for config.abandon_days
+    Abandoned { after_season: u32 },
+}
+
+impl ShowSignal {
+    pub fn label(&self) -> String {
+        match self {
+            ShowSignal::NotStarted => "Not started".to_string(),
+            ShowSignal::InProgress => "In progress".to_string(),
+            ShowSignal::Finished => "Finished".to_string(),
+            ShowSignal::Abandoned { after_season } => format!("Abandoned after season {}", after_season),
+        }
+    }
+
+    // What the progress says when the user hasn't picked a watch state
+    pub fn implied_state(&self) -> Option<WatchState> {
+        match self {
+            ShowSignal::NotStarted => None,
+            ShowSignal::InProgress => Some(WatchState::Watching),
+            ShowSignal::Finished => Some(WatchState::Watched),
+            ShowSignal::Abandoned { .. } => Some(WatchState::Dropped),
+        }
+    }
+}
+
+pub fn show_signal(progress: &EpisodeProgress, episodes: &[Episode], config: &RecommenderConfig, now: DateTime<Utc>) -> ShowSignal {
+    let ticked: Vec<&Episode> = episodes.iter().filter(|e| progress.has_watched(&e.episode_id)).collect();
+    if ticked.is_empty() {
+        return ShowSignal::NotStarted;
+    }
+
+    let today = now.date_naive();
+    let aired_left = episodes.iter().any(|e| e.has_aired(today) && !progress.has_watched(&e.episode_id));
+    let unaired_left = episodes.iter().any(|e| !e.has_aired(today));
+
+    if !aired_left {
+        return if unaired_left { ShowSignal::InProgress } else { ShowSignal::Finished };
+    }
+
+    let idle_days = DateTime::parse_from_rfc3339(&progress.timestamp)
+        .map(|t| (now - t.with_timezone(&Utc)).num_days())
+        .unwrap_or(0);
+    if idle_days >= config.abandon_days {
+        let after_season = ticked.iter().map(|e| e.season).max().unwrap_or(1);
+        return ShowSignal::Abandoned { after_season };
+    }
+    ShowSignal::InProgress
+}
+
+// First aired, unticked episode after the furthest one ticked (airing order)
+pub fn next_episode<'a>(progress: &EpisodeProgress, episodes: &'a [Episode], today: NaiveDate) -> Option<&'a Episode> {
+    let mut ordered: Vec<&Episode> = episodes.iter().collect();
+    ordered.sort_by_key(|e| (e.season, e.number));
+
+    let furthest = ordered.iter().rposition(|e| progress.has_watched(&e.episode_id))?;
+    ordered[furthest + 1..]
+        .iter()
+        .find(|e| e.has_aired(today) && !progress.has_watched(&e.episode_id))
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::migrations::declared_type;
+    use surrealdb::sql::{from_value, to_value, Value};
+
+    #[test]
+    fn cache_row_matches_the_schema_and_round_trips() {
+        let cache = EpisodeCache {
+            id: None,
+            fetched_at: Utc::now(),
+            episodes: vec![Episode {
+                episode_id: "1".to_string(),
+                season: 1,
+                number: 1,
+                name: "Pilot".to_string(),
+                airdate: NaiveDate::from_ymd_opt(2024, 1, 1),
+                runtime: Some(45),
+            }],
+        };
+
+        // What SurrealDB checks against the field definition on upsert
+        let row = to_value(cache.clone()).unwrap();
+        let Value::Object(fields) = &row else {
+            panic!("cache row is not an object: {}", row);
+        };
+        assert!(matches!(fields.get("fetched_at"), Some(Value::Strand(_))));
+        assert_eq!(declared_type("episode_list", "fetched_at").as_deref(), Some("string"));
+
+        let back: EpisodeCache = from_value(row).unwrap();
+        assert_eq!(back.fetched_at, cache.fetched_at);
+        assert_eq!(back.episodes, cache.episodes);
+    }
+
+    fn episode(id: &str, season: u32, number: u32, airdate: Option<&str>) -> Episode {
+        Episode {
+            episode_id: id.to_string(),
+            season,
+            number,
+            name: format!("Episode {}", id),
+            airdate: airdate.map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap()),
+            runtime: Some(45),
+        }
+    }
+
+    // Two aired seasons of two episodes, listed out of order, and one announced
+    fn episodes() -> Vec<Episode> {
+        vec![
+            episode("21", 2, 1, Some("2024-02-01")),
+            episode("11", 1, 1, Some("2024-01-01")),
+            episode("12", 1, 2, Some("2024-01-08")),
+            episode("22", 2, 2, Some("2024-02-08")),
+            episode("31", 3, 1, None),
+        ]
+    }
+
+    fn progress(watched: &[&str], timestamp: &str) -> EpisodeProgress {
+        EpisodeProgress {
+            id: None,
+            user: Thing::from(("user", "ann")),
+            video_id: "1".to_string(),
+            video_title: "Show 1".to_string(),
+            watched: watched.iter().map(|w| w.to_string()).collect(),
+            timestamp: timestamp.to_string(),
+        }
+    }
+
+    fn at(day: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(&format!("{}T12:00:00+00:00", day)).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn progress_turns_into_a_show_signal() {
+        let config = RecommenderConfig { abandon_days: 30, ..RecommenderConfig::default() };
+        let now = at("2024-06-01");
+        let signal = |watched: &[&str], last: &str, episodes: &[Episode]| show_signal(&progress(watched, &at(last).to_rfc3339()), episodes, &config, now);
+
+        assert_eq!(signal(&[], "2024-05-30", &episodes()), ShowSignal::NotStarted);
+        assert_eq!(signal(&["11"], "2024-05-30", &episodes()), ShowSignal::InProgress);
+        // Idle past abandon_days with aired episodes left
+        assert_eq!(signal(&["11", "12", "21"], "2024-03-01", &episodes()), ShowSignal::Abandoned { after_season: 2 });
+        // Caught up and waiting for season 3 is never abandoned
+        assert_eq!(signal(&["11", "12", "21", "22"], "2024-03-01", &episodes()), ShowSignal::InProgress);
+        // Everything of an ended run
+        let ended: Vec<Episode> = episodes().into_iter().filter(|e| e.airdate.is_some()).collect();
+        assert_eq!(signal(&["11", "12", "21", "22"], "2024-03-01", &ended), ShowSignal::Finished);
+        assert_eq!(ShowSignal::Abandoned { after_season: 2 }.implied_state(), Some(WatchState::Dropped));
+    }
+
+    #[test]
+    fn an_unreadable_tick_date_is_not_abandonment() {
+        let config = RecommenderConfig { abandon_days: 30, ..RecommenderConfig::default() };
+        assert_eq!(show_signal(&progress(&["11"], "last spring"), &episodes(), &config, at("2024-06-01")), ShowSignal::InProgress);
+    }
+
+    #[test]
+    fn next_episode_follows_the_furthest_tick() {
+        let today = at("2024-06-01").date_naive();
+        let next = |watched: &[&str]| next_episode(&progress(watched, ""), &episodes(), today).map(|e| e.code());
+
+        assert_eq!(next(&["11"]), Some("S01E02".to_string()));
+        // Across the season break, and skipping what was ticked out of order
+        assert_eq!(next(&["11", "12"]), Some("S02E01".to_string()));
+        assert_eq!(next(&["11", "21"]), Some("S02E02".to_string()));
+        // Nothing aired is left, and nothing started yet
+        assert_eq!(next(&["11", "12", "21", "22"]), None);
+        assert_eq!(next(&[]), None);
+        // Not aired yet on the day before
+        assert_eq!(next_episode(&progress(&["21"], ""), &episodes(), NaiveDate::from_ymd_opt(2024, 2, 7).unwrap()), None);
+    }
+}
@@ -0,0 +1,126 @@
+// <------- One error type for the whole app ------->
+// Display is what the user sees, so every message says what to do next.
+// Debug keeps the raw detail for the console.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GlassBoxError {
+    // The IndexedDB vault has not finished opening yet
+    DbNotReady,
+    // The vault is open but a query failed
+    Database(String),
+    // Request never reached TVMaze (offline, DNS, CORS...)
+    Network(String),
+    // TVMaze answered with a non-2xx status
+    HttpStatus(u16),
+    // A response or document could not be decoded
+    Parse(String),
+    // Wrong username or password
+    AuthFailed,
+    // User input that we refuse to store
+    Validation(String),
+    // A record we expected is missing
+    NotFound(String),
+    // A browser API (Blob, WebCrypto, Storage...) refused the call
+    Browser(String),
+    // Signed in, but the encryption key isn't in memory (e.g. after a reload)
+    VaultLocked,
+    // Hashing or checking a password failed (not a wrong password)
+    Crypto(String),
+}
+
+impl fmt::Display for GlassBoxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GlassBoxError::DbNotReady => {
+                write!(f, "Your local vault is still starting up. Please try again in a moment.")
+            }
+            GlassBoxError::Database(_) => {
+                write!(f, "Something went wrong while reading or writing your local data. Reload the page and try again.")
+            }
+            GlassBoxError::Network(_) => {
+                write!(f, "Could not reach TVMaze. Check your internet connection and try again.")
+            }
+            GlassBoxError::HttpStatus(429) => {
+                write!(f, "TVMaze is rate limiting us. Wait a few seconds and try again.")
+            }
+            GlassBoxError::HttpStatus(code) if *code >= 500 => {
+                write!(f, "TVMaze is having trouble right now (error {}). Try again later.", code)
+            }
+            GlassBoxError::HttpStatus(code) => {
+                write!(f, "TVMaze rejected the request (error {}).", code)
+            }
+            GlassBoxError::Parse(_) => {
+                write!(f, "We received data we could not understand. Try again, and if it keeps happening the file or service may have changed.")
+            }
+            GlassBoxError::AuthFailed => write!(f, "Invalid username or password"),
+            GlassBoxError::Validation(msg) => write!(f, "{}", msg),
+            GlassBoxError::NotFound(what) => write!(f, "{} could not be found.", what),
+            GlassBoxError::VaultLocked => {
+                write!(f, "Your vault is locked. Log in again to unlock your history.")
+            }
+            GlassBoxError::Browser(_) => {
+                write!(f, "Your browser blocked this action. Check its privacy settings and try again.")
+            }
+            GlassBoxError::Crypto(_) => {
+                write!(f, "Something went wrong while checking or protecting your password. Try again.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GlassBoxError {}
+
+impl From<surrealdb::Error> for GlassBoxError {
+    fn from(e: surrealdb::Error) -> Self {
+        GlassBoxError::Database(e.to_string())
+    }
+}
+
+impl From<reqwest::Error> for GlassBoxError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_decode() {
+            GlassBoxError::Parse(e.to_string())
+        } else if let Some(status) = e.status() {
+            GlassBoxError::HttpStatus(status.as_u16())
+        } else {
+            GlassBoxError::Network(e.to_string())
+        }
+    }
+}
+
+impl From<serde_json::Error> for GlassBoxError {
+    fn from(e: serde_json::Error) -> Self {
+        GlassBoxError::Parse(e.to_string())
+    }
+}
+
+impl From<bcrypt::BcryptError> for GlassBoxError {
+    fn from(e: bcrypt::BcryptError) -> Self {
+        GlassBoxError::Crypto(format!("password hash: {}", e))
+    }
+}
+
+impl From<wasm_bindgen::JsValue> for GlassBoxError {
+    fn from(e: wasm_bindgen::JsValue) -> Self {
+        GlassBoxError::Browser(format!("{:?}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_broken_hash_is_a_crypto_error() {
+        let err: GlassBoxError = bcrypt::verify("hunter2", "not a bcrypt hash").unwrap_err().into();
+        assert!(matches!(err, GlassBoxError::Crypto(_)));
+    }
+
+    #[test]
+    fn rate_limits_get_their_own_message() {
+        assert!(GlassBoxError::HttpStatus(429).to_string().contains("rate limiting"));
+        assert!(GlassBoxError::HttpStatus(503).to_string().contains("error 503"));
+    }
+}
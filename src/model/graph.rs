@@ -0,0 +1,268 @@
+// <------- Show-to-show graph built on Video.related_ids ------->
+// Edges come from catalog signals: same network + genre, shared cast & crew,
+// and shows opened in the same session. Candidates are found by a
+// personalised PageRank walk that restarts at the user's favourite titles.
+
+use crate::model::error::GlassBoxError;
+use crate::model::history::get_user_interactions;
+use crate::model::next_item::TransitionModel;
+use crate::model::person::get_credits;
+use crate::model::video::{save_video, Video};
+use std::collections::{HashMap, HashSet};
+use surrealdb::sql::Thing;
+
+const MAX_RELATED: usize = 12;
+
+// Networks that say nothing about a show (our placeholder for "no network")
+const GENERIC_NETWORKS: &[&str] = &["Web Series"];
+
+fn same_network_and_genre(a: &Video, b: &Video) -> bool {
+    if a.channel_name != b.channel_name
+        || GENERIC_NETWORKS.iter().any(|n| a.channel_name.eq_ignore_ascii_case(n))
+    {
+        return false;
+    }
+    a.genres
+        .iter()
+        .any(|g| b.genres.iter().any(|h| g.eq_ignore_ascii_case(h)))
+}
+
+fn merge_related(existing: &mut Vec<String>, own_id: &str, new_ids: impl IntoIterator<Item = String>) {
+    for id in new_ids {
+        if id != own_id && !existing.contains(&id) {
+            existing.push(id);
+        }
+    }
+    existing.truncate(MAX_RELATED);
+}
+
+// <------- Links shows that came back in the same API response ------->
+pub fn link_batch(videos: &mut [Video]) {
+    let links: Vec<Vec<String>> = videos
+        .iter()
+        .map(|a| {
+            videos
+                .iter()
+                .filter(|b| b.video_id != a.video_id && same_network_and_genre(a, b))
+                .map(|b| b.video_id.clone())
+                .collect()
+        })
+        .collect();
+
+    for (video, ids) in videos.iter_mut().zip(links) {
+        let own_id = video.video_id.clone();
+        merge_related(&mut video.related_ids, &own_id, ids);
+    }
+}
+
+// <------- Links a freshly saved show to the rest of the library ------->
+// Shared cast/crew and co-visits are both symmetric, so both ends get the edge.
+// Edges live in the user's own (sealed) library rows: co-visits are history.
+pub async fn enrich_related(user_id: &Thing, video_id: &str, library: &[Video], session_gap_minutes: i64) -> Result<(), GlassBoxError> {
+    let Some(target) = library.iter().find(|v| v.video_id == video_id) else {
+        return Ok(());
+    };
+
+    let ids: Vec<String> = library.iter().map(|v| v.video_id.clone()).collect();
+    let credits = get_credits(&ids).await?;
+    let people: HashSet<&str> = credits
+        .get(video_id)
+        .map(|c| c.iter().map(|p| p.person_id.as_str()).collect())
+        .unwrap_or_default();
+
+    let log = get_user_interactions(user_id.clone()).await?;
+    let co_visits = TransitionModel::from_interactions(&log, session_gap_minutes).co_visited(video_id, MAX_RELATED);
+
+    let mut neighbours: Vec<String> = Vec::new();
+    for other in library.iter().filter(|v| v.video_id != video_id) {
+        let shares_people = credits
+            .get(&other.video_id)
+            .map(|c| c.iter().any(|p| people.contains(p.person_id.as_str())))
+            .unwrap_or(false);
+        if shares_people || same_network_and_genre(target, other) {
+            neighbours.push(other.video_id.clone());
+        }
+    }
+    // Co-visits are kept even for shows that are no longer in the library
+    neighbours.extend(co_visits);
+
+    let mut updated = target.clone();
+    merge_related(&mut updated.related_ids, video_id, neighbours.clone());
+    if updated.related_ids != target.related_ids {
+        save_video(user_id.clone(), updated).await?;
+    }
+
+    for other in library.iter().filter(|v| neighbours.contains(&v.video_id)) {
+        let mut back = other.clone();
+        merge_related(&mut back.related_ids, &other.video_id, [video_id.to_string()]);
+        if back.related_ids != other.related_ids {
+            save_video(user_id.clone(), back).await?;
+        }
+    }
+    Ok(())
+}
+
+// Undirected adjacency list from every related_ids edge we know about
+pub fn adjacency(library: &[Video]) -> HashMap<String, Vec<String>> {
+    let mut adj: HashMap<String, Vec<String>> = HashMap::new();
+    for v in library {
+        for r in &v.related_ids {
+            let forward = adj.entry(v.video_id.clone()).or_default();
+            if !forward.contains(r) {
+                forward.push(r.clone());
+            }
+            let backward = adj.entry(r.clone()).or_default();
+            if !backward.contains(&v.video_id) {
+                backward.push(v.video_id.clone());
+            }
+        }
+    }
+    adj
+}
+
+// <------- Personalised PageRank (power iteration) ------->
+// `restart` is the chance of jumping back to a seed at every step.
+pub fn personalized_pagerank(
+    adj: &HashMap<String, Vec<String>>,
+    seeds: &HashMap<String, f64>,
+    restart: f64,
+    iterations: usize,
+) -> HashMap<String, f64> {
+    let seed_total: f64 = seeds.values().sum();
+    if seed_total <= 0.0 {
+        return HashMap::new();
+    }
+    let teleport: HashMap<&str, f64> = seeds.iter().map(|(id, w)| (id.as_str(), w / seed_total)).collect();
+
+    let mut rank: HashMap<String, f64> = teleport.iter().map(|(id, p)| (id.to_string(), *p)).collect();
+
+    for _ in 0..iterations {
+        let mut next: HashMap<String, f64> = teleport
+            .iter()
+            .map(|(id, p)| (id.to_string(), restart * p))
+            .collect();
+
+        for (node, mass) in &rank {
+            match adj.get(node).filter(|n| !n.is_empty()) {
+                Some(neighbours) => {
+                    let share = (1.0 - restart) * mass / neighbours.len() as f64;
+                    for n in neighbours {
+                        *next.entry(n.clone()).or_insert(0.0) += share;
+                    }
+                }
+                // Dangling node: its mass goes back to the seeds
+                None => {
+                    for (id, p) in &teleport {
+                        *next.entry(id.to_string()).or_insert(0.0) += (1.0 - restart) * mass * p;
+                    }
+                }
+            }
+        }
+        rank = next;
+    }
+    rank
+}
+
+// Walks the graph from the best-matching library titles and returns unseen
+// show IDs, highest visit probability first
+pub fn graph_candidates(library: &[Video], seeds: &HashMap<String, f64>, restart: f64, limit: usize) -> Vec<(String, f64)> {
+    let adj = adjacency(library);
+    let rank = personalized_pagerank(&adj, seeds, restart, 20);
+
+    let owned: HashSet<&str> = library.iter().map(|v| v.video_id.as_str()).collect();
+    let mut ranked: Vec<(String, f64)> = rank
+        .into_iter()
+        .filter(|(id, _)| !owned.contains(id.as_str()))
+        .collect();
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    ranked.truncate(limit);
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn show(id: &str, network: &str, genre: &str, related: &[&str]) -> Video {
+        Video {
+            id: None,
+            video_id: id.to_string(),
+            title: format!("Show {}", id),
+            description: String::new(),
+            thumbnail_url: String::new(),
+            rating: 7.0,
+            genres: vec![genre.to_string()],
+            channel_name: network.to_string(),
+            related_ids: related.iter().map(|r| r.to_string()).collect(),
+            saved_at: Utc::now(),
+        }
+    }
+
+    fn seeds(ids: &[&str]) -> HashMap<String, f64> {
+        ids.iter().map(|id| (id.to_string(), 1.0)).collect()
+    }
+
+    #[test]
+    fn a_batch_links_same_network_and_genre_only() {
+        let mut batch = vec![
+            show("1", "HBO", "Drama", &[]),
+            show("2", "HBO", "drama", &[]),
+            show("3", "HBO", "Comedy", &[]),
+            show("4", "Web Series", "Drama", &[]),
+            show("5", "Web Series", "Drama", &[]),
+        ];
+        link_batch(&mut batch);
+        assert_eq!(batch[0].related_ids, vec!["2"]);
+        assert_eq!(batch[1].related_ids, vec!["1"]);
+        assert!(batch[2].related_ids.is_empty());
+        assert!(batch[3].related_ids.is_empty());
+    }
+
+    #[test]
+    fn adjacency_is_undirected_without_duplicates() {
+        let library = vec![show("a", "", "", &["b"]), show("b", "", "", &["a", "c"])];
+        let adj = adjacency(&library);
+        assert_eq!(adj["a"], vec!["b"]);
+        assert_eq!(adj["b"], vec!["a", "c"]);
+        assert_eq!(adj["c"], vec!["b"]);
+    }
+
+    #[test]
+    fn rank_falls_off_with_distance_and_keeps_its_mass() {
+        // a - b - c - d
+        let library = vec![show("a", "", "", &["b"]), show("b", "", "", &["c"]), show("c", "", "", &["d"])];
+        let rank = personalized_pagerank(&adjacency(&library), &seeds(&["a"]), 0.15, 30);
+        // b also collects from c, so it can outrank the seed; past it every hop loses mass
+        assert!(rank["b"] > rank["c"] && rank["c"] > rank["d"]);
+        assert!(rank["a"] > rank["c"]);
+        assert!((rank.values().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_dangling_seed_sends_its_mass_back_to_the_seeds() {
+        // "lonely" has no edges at all
+        let library = vec![show("a", "", "", &["b"])];
+        let rank = personalized_pagerank(&adjacency(&library), &seeds(&["a", "lonely"]), 0.15, 30);
+        assert!((rank.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(rank["lonely"] > 0.0 && rank["b"] > 0.0);
+    }
+
+    #[test]
+    fn no_seeds_rank_nothing() {
+        let library = vec![show("a", "", "", &["b"])];
+        assert!(personalized_pagerank(&adjacency(&library), &HashMap::new(), 0.15, 10).is_empty());
+        assert!(personalized_pagerank(&adjacency(&library), &HashMap::from([("a".to_string(), 0.0)]), 0.15, 10).is_empty());
+    }
+
+    #[test]
+    fn candidates_are_unseen_shows_nearest_first() {
+        // Library a - b (and an unlinked c); x hangs off a, z off b
+        let library = vec![show("a", "", "", &["b", "x"]), show("b", "", "", &["z"]), show("c", "", "", &[])];
+        let found = graph_candidates(&library, &seeds(&["a"]), 0.15, 10);
+        let ids: Vec<&str> = found.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["x", "z"]);
+        assert!(found[0].1 > found[1].1);
+        assert_eq!(graph_candidates(&library, &seeds(&["a"]), 0.15, 1).len(), 1);
+    }
+}
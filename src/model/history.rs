@@ -1,6 +1,10 @@
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use surrealdb::sql::Thing;
-use crate::model::db::DB; 
+use crate::model::config::{load_config_with, load_retention_with};
+use crate::model::error::GlassBoxError;
+use crate::model::store::{Store, SurrealStore};
 use crate::model::video::Video;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -11,8 +15,11 @@ pub struct SearchEntry {
     pub timestamp: String,
 }
 
-pub async fn save_search(user_id: Thing, query: String) -> Result<(), String> {
-    let db = DB.get().ok_or("Database not initialized")?;
+pub async fn save_search(user_id: Thing, query: String) -> Result<(), GlassBoxError> {
+    save_search_with(&SurrealStore, user_id, query).await
+}
+
+pub async fn save_search_with<S: Store>(store: &S, user_id: Thing, query: String) -> Result<(), GlassBoxError> {
     let entry = SearchEntry {
         id: None,
         user: user_id,
@@ -20,10 +27,12 @@ pub async fn save_search(user_id: Thing, query: String) -> Result<(), String> {
         timestamp: chrono::Utc::now().to_rfc3339(),
     };
     // Use "search_history" table
-    let _: Option<SearchEntry> = db.create("search_history").content(entry).await.map_err(|e| e.to_string())?;
-    Ok(())
+    store.add_search(entry).await
 }
 
+// One row per run of the same event: clicking a card again within a session
+// bumps `count` and `last_at` instead of adding a row, so the log keeps its
+// order (the Markov model walks it) without repeats piling up.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct InteractionEntry {
     pub id: Option<Thing>,
@@ -31,21 +40,356 @@ pub struct InteractionEntry {
     pub video_id: String,
     pub video_title: String,
     pub interaction_type: String, // "click", "view"
-    pub timestamp: String,
+    pub timestamp: String,        // first event of the run
+    #[serde(default = "single_event")]
+    pub count: u32,
+    #[serde(default)]
+    pub last_at: String, // last event of the run; empty on rows older than runs
+}
+
+// Rows (and archives) from before runs existed stand for one event
+pub(crate) fn single_event() -> u32 {
+    1
+}
+
+impl InteractionEntry {
+    pub fn last_seen(&self) -> &str {
+        if self.last_at.is_empty() {
+            &self.timestamp
+        } else {
+            &self.last_at
+        }
+    }
+
+    // Same event as `other`, close enough after it to count as a repeat
+    fn repeats(&self, other: &InteractionEntry, gap_minutes: i64) -> bool {
+        if self.user != other.user || self.video_id != other.video_id || self.interaction_type != other.interaction_type {
+            return false;
+        }
+        match (parse_timestamp(self.last_seen()), parse_timestamp(&other.timestamp)) {
+            (Some(prev), Some(next)) => next >= prev && (next - prev).num_minutes() <= gap_minutes,
+            _ => false,
+        }
+    }
+}
+
+// Returns the row the event ended up in (count > 1 means it was a repeat)
+pub async fn save_interaction(user_id: Thing, video: Video, interaction_type: String) -> Result<InteractionEntry, GlassBoxError> {
+    save_interaction_with(&SurrealStore, user_id, video, interaction_type).await
 }
 
-pub async fn save_interaction(user_id: Thing, video: Video, interaction_type: String) -> Result<(), String> {
-    let db = DB.get().ok_or("Database not initialized")?;
-    
+pub async fn save_interaction_with<S: Store>(store: &S, user_id: Thing, video: Video, interaction_type: String) -> Result<InteractionEntry, GlassBoxError> {
+    let now = chrono::Utc::now().to_rfc3339();
     let entry = InteractionEntry {
         id: None,
-        user: user_id,
+        user: user_id.clone(),
         video_id: video.video_id,
         video_title: video.title,
         interaction_type,
-        timestamp: chrono::Utc::now().to_rfc3339(),
+        timestamp: now.clone(),
+        count: 1,
+        last_at: now.clone(),
     };
+
+    // A repeat within the same session (same gap as the Markov model) extends the last run
+    let gap = load_config_with(store, user_id.clone()).await?.session_gap_minutes;
+    if let Some(last) = store.latest_interaction(&user_id).await? {
+        if last.repeats(&entry, gap) {
+            let run = InteractionEntry {
+                count: last.count + 1,
+                last_at: now,
+                ..last
+            };
+            store.update_interaction(run.clone()).await?;
+            return Ok(run);
+        }
+    }
+
     // Use "interaction" table
-    let _: Option<InteractionEntry> = db.create("interaction").content(entry).await.map_err(|e| e.to_string())?;
-    Ok(())
+    store.add_interaction(entry.clone()).await?;
+    Ok(entry)
+}
+
+// <------- Aggregates ------->
+
+// Everything one user did with one show, per interaction type
+#[derive(Debug, Clone, PartialEq)]
+pub struct InteractionStat {
+    pub user: Thing,
+    pub video_id: String,
+    pub video_title: String,
+    pub interaction_type: String,
+    pub count: u32,
+    pub first_at: String,
+    pub last_at: String,
+}
+
+// Collapses rows per (user, video, type); most frequent first
+pub fn aggregate_interactions(entries: &[InteractionEntry]) -> Vec<InteractionStat> {
+    let mut stats: HashMap<(String, String, String), InteractionStat> = HashMap::new();
+    for e in entries {
+        let key = (e.user.to_string(), e.video_id.clone(), e.interaction_type.clone());
+        let stat = stats.entry(key).or_insert_with(|| InteractionStat {
+            user: e.user.clone(),
+            video_id: e.video_id.clone(),
+            video_title: e.video_title.clone(),
+            interaction_type: e.interaction_type.clone(),
+            count: 0,
+            first_at: e.timestamp.clone(),
+            last_at: e.last_seen().to_string(),
+        });
+        stat.count += e.count.max(1);
+        if e.timestamp < stat.first_at {
+            stat.first_at = e.timestamp.clone();
+        }
+        if e.last_seen() > stat.last_at.as_str() {
+            stat.last_at = e.last_seen().to_string();
+            stat.video_title = e.video_title.clone();
+        }
+    }
+    let mut stats: Vec<InteractionStat> = stats.into_values().collect();
+    stats.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| b.last_at.cmp(&a.last_at)));
+    stats
+}
+
+pub async fn get_interaction_stats(user_id: Thing) -> Result<Vec<InteractionStat>, GlassBoxError> {
+    Ok(aggregate_interactions(&get_user_interactions_with(&SurrealStore, user_id).await?))
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompactionReport {
+    pub rows_before: usize,
+    pub rows_after: usize,
+}
+
+// Folds runs of repeated raw rows (written before runs existed, or imported)
+// into one row each. Counts and first/last times survive; only the
+// duplicate rows go, in the same transaction that rewrites the runs.
+pub async fn compact_interactions(user_id: Thing) -> Result<CompactionReport, GlassBoxError> {
+    compact_interactions_with(&SurrealStore, user_id).await
+}
+
+pub async fn compact_interactions_with<S: Store>(store: &S, user_id: Thing) -> Result<CompactionReport, GlassBoxError> {
+    let gap = load_config_with(store, user_id.clone()).await?.session_gap_minutes;
+    let rows = store.user_interactions(&user_id).await?; // oldest first
+
+    let mut report = CompactionReport {
+        rows_before: rows.len(),
+        rows_after: 0,
+    };
+    let mut kept: Vec<(InteractionEntry, bool)> = Vec::new(); // (row, changed)
+    let mut folded: Vec<Thing> = Vec::new();
+    for row in rows {
+        match kept.last_mut() {
+            Some((run, changed)) if run.repeats(&row, gap) => {
+                run.count += row.count.max(1);
+                if row.last_seen() > run.last_seen() {
+                    run.last_at = row.last_seen().to_string();
+                }
+                *changed = true;
+                folded.extend(row.id);
+            }
+            _ => kept.push((row, false)),
+        }
+    }
+
+    report.rows_after = kept.len();
+    let changed: Vec<InteractionEntry> = kept.into_iter().filter(|(_, changed)| *changed).map(|(run, _)| run).collect();
+    if !changed.is_empty() {
+        store.prune_history(folded, changed).await?;
+    }
+    if report.rows_after < report.rows_before {
+        leptos::logging::log!("HISTORY: Compacted {} interaction rows into {}", report.rows_before, report.rows_after);
+    }
+    Ok(report)
+}
+
+// <------- Reading and forgetting one user's history ------->
+
+pub async fn get_user_interactions(user_id: Thing) -> Result<Vec<InteractionEntry>, GlassBoxError> {
+    get_user_interactions_with(&SurrealStore, user_id).await
+}
+
+// Newest first, like searches()
+pub async fn get_user_interactions_with<S: Store>(store: &S, user_id: Thing) -> Result<Vec<InteractionEntry>, GlassBoxError> {
+    let mut entries = store.user_interactions(&user_id).await?;
+    entries.reverse();
+    Ok(entries)
+}
+
+pub async fn get_searches(user_id: Thing) -> Result<Vec<SearchEntry>, GlassBoxError> {
+    SurrealStore.searches(&user_id).await
+}
+
+// Shows this user clicked, rated, gave a watch state or ticked episodes of. Because it is derived
+// from the history, forgetting an entry also takes the show out of the taste
+// vector (a watch state stays until it is cleared on the library page).
+pub async fn get_user_library(user_id: Thing) -> Result<Vec<Video>, GlassBoxError> {
+    get_user_library_with(&SurrealStore, user_id).await
+}
+
+pub async fn get_user_library_with<S: Store>(store: &S, user_id: Thing) -> Result<Vec<Video>, GlassBoxError> {
+    let mut mine: HashSet<String> = store.user_interactions(&user_id).await?.into_iter().map(|i| i.video_id).collect();
+    mine.extend(store.ratings(&user_id).await?.into_iter().map(|r| r.video_id));
+    mine.extend(store.watchlist(&user_id).await?.into_iter().map(|e| e.video_id));
+    mine.extend(store.progress(&user_id).await?.into_iter().map(|p| p.video_id));
+
+    Ok(store.library(&user_id).await?.into_iter().filter(|v| mine.contains(&v.video_id)).collect())
+}
+
+pub async fn delete_interaction(id: Thing) -> Result<(), GlassBoxError> {
+    SurrealStore.delete_interaction(&id).await
+}
+
+pub async fn delete_search(id: Thing) -> Result<(), GlassBoxError> {
+    SurrealStore.delete_search(&id).await
+}
+
+fn parse_timestamp(ts: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(ts).ok().map(|t| t.with_timezone(&Utc))
+}
+
+fn in_range(ts: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> bool {
+    parse_timestamp(ts).map(|t| t >= from && t < to).unwrap_or(false)
+}
+
+// Removes searches and interactions with from <= timestamp < to.
+// Returns (searches, interactions) deleted.
+pub async fn delete_range(user_id: Thing, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<(usize, usize), GlassBoxError> {
+    delete_range_with(&SurrealStore, user_id, from, to).await
+}
+
+pub async fn delete_range_with<S: Store>(store: &S, user_id: Thing, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<(usize, usize), GlassBoxError> {
+    let searches = searches_between(store, &user_id, from, to).await?;
+    let interactions = interactions_between(store, user_id, from, to).await?;
+    let counts = (searches.len(), interactions.len());
+    store.prune_history(searches.into_iter().chain(interactions).collect(), Vec::new()).await?;
+    Ok(counts)
+}
+
+// Purges whatever is older than the user's retention policy
+pub async fn apply_retention(user_id: Thing) -> Result<(usize, usize), GlassBoxError> {
+    apply_retention_with(&SurrealStore, user_id).await
+}
+
+pub async fn apply_retention_with<S: Store>(store: &S, user_id: Thing) -> Result<(usize, usize), GlassBoxError> {
+    let policy = load_retention_with(store, user_id.clone()).await?;
+    let now = Utc::now();
+    // Rows with unreadable timestamps are never purged by accident
+    let epoch = DateTime::<Utc>::UNIX_EPOCH;
+
+    let searches = match policy.search_days {
+        Some(days) => searches_between(store, &user_id, epoch, now - Duration::days(days as i64)).await?,
+        None => Vec::new(),
+    };
+    let interactions = match policy.interaction_days {
+        Some(days) => interactions_between(store, user_id, epoch, now - Duration::days(days as i64)).await?,
+        None => Vec::new(),
+    };
+    let counts = (searches.len(), interactions.len());
+
+    if counts.0 + counts.1 > 0 {
+        // One transaction, so a purge is never left half done
+        store.prune_history(searches.into_iter().chain(interactions).collect(), Vec::new()).await?;
+        leptos::logging::log!("RETENTION: Purged {} searches and {} interactions", counts.0, counts.1);
+    }
+    Ok(counts)
+}
+
+// IDs of the rows with from <= timestamp < to, for one prune_history call
+async fn searches_between<S: Store>(store: &S, user_id: &Thing, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<Thing>, GlassBoxError> {
+    Ok(store
+        .searches(user_id)
+        .await?
+        .into_iter()
+        .filter(|e| in_range(&e.timestamp, from, to))
+        .filter_map(|e| e.id)
+        .collect())
+}
+
+// A run counts as happening when it was last seen, so one still going is kept
+async fn interactions_between<S: Store>(store: &S, user_id: Thing, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<Thing>, GlassBoxError> {
+    Ok(get_user_interactions_with(store, user_id)
+        .await?
+        .into_iter()
+        .filter(|e| in_range(e.last_seen(), from, to))
+        .filter_map(|e| e.id)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::config::{save_retention_with, RetentionPolicy};
+    use crate::model::store::MemoryStore;
+    use futures::executor::block_on;
+
+    fn raw(user: &Thing, video_id: &str, at: DateTime<Utc>) -> InteractionEntry {
+        InteractionEntry {
+            id: None,
+            user: user.clone(),
+            video_id: video_id.to_string(),
+            video_title: format!("Show {}", video_id),
+            interaction_type: "click".to_string(),
+            timestamp: at.to_rfc3339(),
+            count: 1,
+            last_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn compaction_folds_repeats_into_their_run() {
+        let store = MemoryStore::new();
+        let user = Thing::from(("user", "ann"));
+        let start = Utc::now() - Duration::hours(1);
+        for (video_id, minutes) in [("1", 0), ("1", 2), ("1", 4), ("2", 6), ("1", 8)] {
+            block_on(store.add_interaction(raw(&user, video_id, start + Duration::minutes(minutes)))).unwrap();
+        }
+
+        let report = block_on(compact_interactions_with(&store, user.clone())).unwrap();
+        assert_eq!(report, CompactionReport { rows_before: 5, rows_after: 3 });
+        let rows = block_on(get_user_interactions_with(&store, user.clone())).unwrap();
+        let counts: Vec<(&str, u32)> = rows.iter().rev().map(|r| (r.video_id.as_str(), r.count)).collect();
+        assert_eq!(counts, vec![("1", 3), ("2", 1), ("1", 1)]);
+
+        // The next click lands on the newest run
+        let latest = block_on(store.latest_interaction(&user)).unwrap().unwrap();
+        assert_eq!(latest.video_id, "1");
+    }
+
+    #[test]
+    fn retention_purges_only_what_is_past_the_policy() {
+        let store = MemoryStore::new();
+        let user = Thing::from(("user", "ann"));
+        let now = Utc::now();
+        block_on(store.add_interaction(raw(&user, "1", now - Duration::days(400)))).unwrap();
+        block_on(store.add_interaction(raw(&user, "2", now - Duration::days(1)))).unwrap();
+        // Started before the cutoff, still clicked yesterday
+        let ongoing = InteractionEntry {
+            count: 30,
+            last_at: (now - Duration::days(1)).to_rfc3339(),
+            ..raw(&user, "3", now - Duration::days(500))
+        };
+        block_on(store.add_interaction(ongoing)).unwrap();
+        for days in [200, 1] {
+            let search = SearchEntry {
+                id: None,
+                user: user.clone(),
+                query: format!("{} days ago", days),
+                timestamp: (now - Duration::days(days)).to_rfc3339(),
+            };
+            block_on(store.add_search(search)).unwrap();
+        }
+
+        let policy = RetentionPolicy {
+            search_days: Some(90),
+            interaction_days: Some(365),
+        };
+        block_on(save_retention_with(&store, user.clone(), policy)).unwrap();
+        assert_eq!(block_on(apply_retention_with(&store, user.clone())).unwrap(), (1, 1));
+        let mut kept: Vec<String> = block_on(get_user_interactions_with(&store, user.clone())).unwrap().into_iter().map(|i| i.video_id).collect();
+        kept.sort();
+        assert_eq!(kept, vec!["2", "3"]);
+        assert_eq!(block_on(store.searches(&user)).unwrap()[0].query, "1 days ago");
+    }
 }
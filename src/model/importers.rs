@@ -0,0 +1,609 @@
+// <------- Bring your history from Letterboxd, IMDb and Trakt ------->
+// 1. parse_export turns a file into ImportRows (title, year, IMDb ID, rating)
+// 2. match_row finds the TVMaze show: IMDb lookup first, fuzzy title+year otherwise
+// 3. the user reviews anything Ambiguous, then commit_import writes the picks
+//    as library entries, "imported" interactions and explicit ratings.
+
+use crate::api::lookup::{lookup_imdb, search_candidates, ShowCandidate};
+use crate::model::error::GlassBoxError;
+use crate::model::history::InteractionEntry;
+use crate::model::ratings::Rating;
+use crate::model::store::{Store, SurrealStore};
+use crate::model::text::edit_distance;
+use crate::model::video::Video;
+use crate::model::watchlist::{WatchEntry, WatchState};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::{HashMap, HashSet};
+use surrealdb::sql::Thing;
+
+// Title similarity a fuzzy match needs before we take it without asking
+const AUTO_MATCH_SIMILARITY: f64 = 0.9;
+// ...and how far ahead of the runner-up it has to be
+const AUTO_MATCH_MARGIN: f64 = 0.15;
+// Candidates offered on the review screen
+const REVIEW_CANDIDATES: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportSource {
+    Letterboxd,
+    Imdb,
+    Trakt,
+}
+
+impl ImportSource {
+    // Stored in Rating.source
+    pub fn key(&self) -> &'static str {
+        match self {
+            ImportSource::Letterboxd => "letterboxd",
+            ImportSource::Imdb => "imdb",
+            ImportSource::Trakt => "trakt",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImportSource::Letterboxd => "Letterboxd",
+            ImportSource::Imdb => "IMDb",
+            ImportSource::Trakt => "Trakt",
+        }
+    }
+}
+
+// One title from an export, before it is matched to TVMaze
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportRow {
+    pub title: String,
+    pub year: Option<i32>,
+    pub imdb_id: Option<String>,
+    pub rating: Option<f64>, // Already on GlassBox's 1-10 scale
+    pub watched_at: Option<DateTime<Utc>>,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatchOutcome {
+    Matched(Video),
+    Ambiguous(Vec<Video>),
+    Unmatched,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingImport {
+    pub row: ImportRow,
+    pub outcome: MatchOutcome,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportSummary {
+    pub titles: usize,
+    pub ratings: usize,
+    pub skipped: usize, // already imported from this file (same show and watch date)
+}
+
+// <------- Parsing ------->
+
+// Picks the parser from the file name and header line
+pub fn parse_export(filename: &str, text: &str) -> Result<(ImportSource, Vec<ImportRow>), GlassBoxError> {
+    if filename.to_lowercase().ends_with(".json") {
+        return Ok((ImportSource::Trakt, parse_trakt(text)?));
+    }
+
+    let header = text.lines().next().unwrap_or("");
+    if header.contains("Letterboxd URI") {
+        Ok((ImportSource::Letterboxd, parse_letterboxd(text)?))
+    } else if header.contains("Const") && header.contains("Your Rating") {
+        Ok((ImportSource::Imdb, parse_imdb(text)?))
+    } else {
+        Err(GlassBoxError::Validation(
+            "Unrecognised file. Use Letterboxd's diary.csv / ratings.csv, IMDb's ratings.csv or a Trakt .json export".to_string(),
+        ))
+    }
+}
+
+// diary.csv: Date,Name,Year,Letterboxd URI,Rating,Rewatch,Tags,Watched Date
+// ratings.csv: Date,Name,Year,Letterboxd URI,Rating
+pub fn parse_letterboxd(text: &str) -> Result<Vec<ImportRow>, GlassBoxError> {
+    let (columns, records) = csv_with_header(text)?;
+    let name = column(&columns, "Name")?;
+    let year = columns.get("Year").copied();
+    let rating = columns.get("Rating").copied();
+    let watched = columns.get("Watched Date").or(columns.get("Date")).copied();
+
+    Ok(records
+        .iter()
+        .filter_map(|r| {
+            let title = r.get(name)?.trim().to_string();
+            if title.is_empty() {
+                return None;
+            }
+            Some(ImportRow {
+                title,
+                year: year.and_then(|i| r.get(i)).and_then(|y| y.trim().parse().ok()),
+                imdb_id: None,
+                // Half stars from 0.5 to 5
+                rating: rating
+                    .and_then(|i| r.get(i))
+                    .and_then(|s| s.trim().parse::<f64>().ok())
+                    .map(|stars| (stars * 2.0).clamp(1.0, 10.0)),
+                watched_at: watched.and_then(|i| r.get(i)).and_then(|d| parse_date(d)),
+            })
+        })
+        .collect())
+}
+
+// Const,Your Rating,Date Rated,Title,...,Title Type,...,Year,...
+pub fn parse_imdb(text: &str) -> Result<Vec<ImportRow>, GlassBoxError> {
+    let (columns, records) = csv_with_header(text)?;
+    let id = column(&columns, "Const")?;
+    let title = column(&columns, "Title")?;
+    let rating = columns.get("Your Rating").copied();
+    let rated = columns.get("Date Rated").copied();
+    let year = columns.get("Year").copied();
+    let kind = columns.get("Title Type").copied();
+
+    Ok(records
+        .iter()
+        .filter(|r| {
+            // TVMaze only has series, so films would just come back unmatched
+            kind.and_then(|i| r.get(i))
+                .map(|k| {
+                    let k = k.to_lowercase();
+                    k.contains("series") || k.contains("tv mini")
+                })
+                .unwrap_or(true)
+        })
+        .filter_map(|r| {
+            Some(ImportRow {
+                title: r.get(title)?.trim().to_string(),
+                year: year.and_then(|i| r.get(i)).and_then(|y| y.trim().parse().ok()),
+                imdb_id: r.get(id).map(|s| s.trim().to_string()).filter(|s| s.starts_with("tt")),
+                rating: rating
+                    .and_then(|i| r.get(i))
+                    .and_then(|s| s.trim().parse::<f64>().ok())
+                    .map(|s| s.clamp(1.0, 10.0)),
+                watched_at: rated.and_then(|i| r.get(i)).and_then(|d| parse_date(d)),
+            })
+        })
+        .collect())
+}
+
+// Any of Trakt's show files (ratings-shows, watched-shows, history...):
+// an array whose items carry a "show" object with title, year and ids.imdb
+pub fn parse_trakt(text: &str) -> Result<Vec<ImportRow>, GlassBoxError> {
+    let items: Vec<serde_json::Value> = serde_json::from_str(text)?;
+
+    Ok(items
+        .iter()
+        .filter_map(|item| {
+            let show = item.get("show")?;
+            let title = show.get("title")?.as_str()?.to_string();
+            let when = ["rated_at", "last_watched_at", "watched_at"]
+                .iter()
+                .find_map(|k| item.get(*k).and_then(|v| v.as_str()))
+                .and_then(parse_date);
+            Some(ImportRow {
+                title,
+                year: show.get("year").and_then(|y| y.as_i64()).map(|y| y as i32),
+                imdb_id: show
+                    .get("ids")
+                    .and_then(|ids| ids.get("imdb"))
+                    .and_then(|i| i.as_str())
+                    .map(|s| s.to_string()),
+                // Episode ratings also carry a "show"; only a show-level rating counts
+                rating: if item.get("type").and_then(|t| t.as_str()) == Some("show") {
+                    item.get("rating").and_then(|r| r.as_f64()).map(|r| r.clamp(1.0, 10.0))
+                } else {
+                    None
+                },
+                watched_at: when,
+            })
+        })
+        .collect())
+}
+
+fn column(columns: &HashMap<String, usize>, name: &str) -> Result<usize, GlassBoxError> {
+    columns
+        .get(name)
+        .copied()
+        .ok_or(GlassBoxError::Validation(format!("The file has no '{}' column", name)))
+}
+
+// Column name -> index, then the data rows
+type CsvTable = (HashMap<String, usize>, Vec<Vec<String>>);
+
+fn csv_with_header(text: &str) -> Result<CsvTable, GlassBoxError> {
+    let mut records = parse_csv(text);
+    if records.is_empty() {
+        return Err(GlassBoxError::Validation("The file is empty".to_string()));
+    }
+    let header = records.remove(0);
+    let columns = header
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| (name.trim().trim_start_matches('\u{feff}').to_string(), i))
+        .collect();
+    Ok((columns, records))
+}
+
+// Minimal RFC 4180: quoted fields may contain commas, newlines and "" escapes
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            _ => field.push(c),
+        }
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.is_empty()) {
+        records.push(record);
+    }
+    records
+}
+
+fn parse_date(raw: &str) -> Option<DateTime<Utc>> {
+    let raw = raw.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(raw) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(12, 0, 0))
+        .map(|dt| dt.and_utc())
+}
+
+// <------- Matching ------->
+
+// Lowercase, punctuation dropped, leading "the" ignored
+fn normalise_title(title: &str) -> String {
+    let cleaned: String = title
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    let words: Vec<&str> = cleaned.split_whitespace().collect();
+    match words.split_first() {
+        Some((&"the", rest)) if !rest.is_empty() => rest.join(" "),
+        _ => words.join(" "),
+    }
+}
+
+// 1 - normalised Levenshtein distance
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+    let a = normalise_title(a);
+    let b = normalise_title(b);
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - edit_distance(&a, &b) as f64 / longest as f64
+}
+
+// Title similarity, nudged up when the premiere year agrees and down when it doesn't
+fn candidate_score(row: &ImportRow, candidate: &ShowCandidate) -> f64 {
+    let title = title_similarity(&row.title, &candidate.video.title);
+    match (row.year, candidate.year()) {
+        (Some(a), Some(b)) if a == b => title + 0.1,
+        (Some(a), Some(b)) if (a - b).abs() > 1 => title - 0.2,
+        _ => title,
+    }
+}
+
+pub fn pick_match(row: &ImportRow, candidates: Vec<ShowCandidate>) -> MatchOutcome {
+    let mut scored: Vec<(f64, ShowCandidate)> = candidates
+        .into_iter()
+        .map(|c| (candidate_score(row, &c), c))
+        .collect();
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let Some(best) = scored.first().map(|(s, _)| *s) else {
+        return MatchOutcome::Unmatched;
+    };
+    let runner_up = scored.get(1).map(|(s, _)| *s).unwrap_or(0.0);
+
+    if best >= AUTO_MATCH_SIMILARITY && best - runner_up >= AUTO_MATCH_MARGIN {
+        MatchOutcome::Matched(scored.swap_remove(0).1.video)
+    } else {
+        MatchOutcome::Ambiguous(scored.into_iter().take(REVIEW_CANDIDATES).map(|(_, c)| c.video).collect())
+    }
+}
+
+// Network errors are not fatal for a bulk import: the row just shows up as unmatched
+pub async fn match_row(row: &ImportRow) -> MatchOutcome {
+    if let Some(imdb_id) = &row.imdb_id {
+        match with_backoff(|| lookup_imdb(imdb_id)).await {
+            Ok(Some(found)) => return MatchOutcome::Matched(found.video),
+            Ok(None) => {}
+            Err(e) => leptos::logging::error!("IMPORT: IMDb lookup {} failed: {:?}", imdb_id, e),
+        }
+    }
+
+    match with_backoff(|| search_candidates(&row.title)).await {
+        Ok(candidates) => pick_match(row, candidates),
+        Err(e) => {
+            leptos::logging::error!("IMPORT: Search for '{}' failed: {:?}", row.title, e);
+            MatchOutcome::Unmatched
+        }
+    }
+}
+
+// TVMaze allows ~20 calls per 10 seconds; a big export hits that quickly
+async fn with_backoff<T, F, Fut>(call: F) -> Result<T, GlassBoxError>
+where
+    F: Fn() -> Fut,
+    Fut: std::future::Future<Output = Result<T, GlassBoxError>>,
+{
+    match call().await {
+        Err(GlassBoxError::HttpStatus(429)) => {
+            gloo_timers::future::sleep(std::time::Duration::from_secs(10)).await;
+            call().await
+        }
+        other => other,
+    }
+}
+
+// <------- Writing ------->
+
+pub async fn commit_import(user_id: Thing, source: ImportSource, picks: Vec<(ImportRow, Video)>) -> Result<ImportSummary, GlassBoxError> {
+    commit_import_with(&SurrealStore, user_id, source, picks).await
+}
+
+pub async fn commit_import_with<S: Store>(
+    store: &S,
+    user_id: Thing,
+    source: ImportSource,
+    picks: Vec<(ImportRow, Video)>,
+) -> Result<ImportSummary, GlassBoxError> {
+    let mut summary = ImportSummary::default();
+
+    // Diaries list a show once per watch: keep the latest row per show
+    let mut latest: HashMap<String, (ImportRow, Video)> = HashMap::new();
+    for (row, video) in picks {
+        let newer = latest
+            .get(&video.video_id)
+            .map(|(old, _)| row.watched_at >= old.watched_at)
+            .unwrap_or(true);
+        if newer {
+            latest.insert(video.video_id.clone(), (row, video));
+        }
+    }
+
+    // A state the user already picked here wins over the export
+    let stated: HashSet<String> = store.watchlist(&user_id).await?.into_iter().map(|e| e.video_id).collect();
+    let saved: HashSet<String> = store.library(&user_id).await?.into_iter().map(|v| v.video_id).collect();
+    // Importing the same export twice must not log every watch twice
+    let imported: HashSet<(String, String)> = store
+        .user_interactions(&user_id)
+        .await?
+        .into_iter()
+        .filter(|i| i.interaction_type == "imported")
+        .map(|i| (i.video_id, i.timestamp))
+        .collect();
+
+    for (row, video) in latest.into_values() {
+        // Recency weighting should see when it was watched, not when it was imported.
+        // That date belongs on the user's own rows below (the watch state's
+        // timestamp is what the feed's recency boost reads), not on the show.
+        let when = row.watched_at.unwrap_or_else(Utc::now);
+        if imported.contains(&(video.video_id.clone(), when.to_rfc3339())) {
+            summary.skipped += 1;
+            continue;
+        }
+        if !saved.contains(&video.video_id) {
+            store.save_video(&user_id, Video { id: None, ..video.clone() }).await?;
+        }
+
+        store
+            .add_interaction(InteractionEntry {
+                id: None,
+                user: user_id.clone(),
+                video_id: video.video_id.clone(),
+                video_title: video.title.clone(),
+                interaction_type: "imported".to_string(),
+                timestamp: when.to_rfc3339(),
+                count: 1,
+                last_at: when.to_rfc3339(),
+            })
+            .await?;
+        summary.titles += 1;
+
+        // These exports are watch logs, so the show was watched
+        if !stated.contains(&video.video_id) {
+            store
+                .save_watch(WatchEntry {
+                    id: None,
+                    user: user_id.clone(),
+                    video_id: video.video_id.clone(),
+                    video_title: video.title.clone(),
+                    state: WatchState::Watched,
+                    added_at: when.to_rfc3339(),
+                    timestamp: when.to_rfc3339(),
+                })
+                .await?;
+        }
+
+        if let Some(score) = row.rating {
+            store
+                .save_rating(Rating {
+                    id: None,
+                    user: user_id.clone(),
+                    video_id: video.video_id.clone(),
+                    video_title: video.title.clone(),
+                    score,
+                    source: source.key().to_string(),
+                    timestamp: when.to_rfc3339(),
+                })
+                .await?;
+            summary.ratings += 1;
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: &str, title: &str, premiered: Option<&str>) -> ShowCandidate {
+        ShowCandidate {
+            video: Video {
+                id: None,
+                video_id: id.to_string(),
+                title: title.to_string(),
+                description: String::new(),
+                thumbnail_url: String::new(),
+                rating: 0.0,
+                genres: Vec::new(),
+                channel_name: String::new(),
+                related_ids: Vec::new(),
+                saved_at: Utc::now(),
+            },
+            premiered: premiered.map(str::to_string),
+        }
+    }
+
+    fn row(title: &str, year: Option<i32>) -> ImportRow {
+        ImportRow {
+            title: title.to_string(),
+            year,
+            imdb_id: None,
+            rating: None,
+            watched_at: None,
+        }
+    }
+
+    #[test]
+    fn csv_keeps_quoted_commas_quotes_and_newlines() {
+        let text = "Name,Year\n\"Love, Death & Robots\",2019\n\"The \"\"Office\"\"\",2005\n\"Two\nLines\",2001\n";
+        assert_eq!(
+            parse_csv(text),
+            vec![
+                vec!["Name".to_string(), "Year".to_string()],
+                vec!["Love, Death & Robots".to_string(), "2019".to_string()],
+                vec!["The \"Office\"".to_string(), "2005".to_string()],
+                vec!["Two\nLines".to_string(), "2001".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_drops_carriage_returns_and_blank_lines() {
+        let text = "a,b\r\n1,2\r\n\r\n3,4";
+        assert_eq!(parse_csv(text).len(), 3);
+        assert_eq!(parse_csv(text)[1], vec!["1".to_string(), "2".to_string()]);
+    }
+
+    #[test]
+    fn letterboxd_export_with_a_bom_and_crlf() {
+        let text = "\u{feff}Date,Name,Year,Letterboxd URI,Rating\r\n2024-01-02,\"Fleabag, Live\",2019,https://boxd.it/x,4.5\r\n";
+        let (source, rows) = parse_export("ratings.csv", text).unwrap();
+        assert_eq!(source, ImportSource::Letterboxd);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].title, "Fleabag, Live");
+        assert_eq!(rows[0].year, Some(2019));
+        assert_eq!(rows[0].rating, Some(9.0));
+        assert_eq!(rows[0].watched_at.map(|d| d.date_naive().to_string()).as_deref(), Some("2024-01-02"));
+    }
+
+    #[test]
+    fn similarity_ignores_case_punctuation_and_a_leading_the() {
+        assert_eq!(title_similarity("The Office", "office"), 1.0);
+        assert_eq!(title_similarity("Marvel's Daredevil", "marvel s daredevil"), 1.0);
+        assert_eq!(title_similarity("", ""), 1.0);
+        assert!(title_similarity("Severance", "Succession") < AUTO_MATCH_SIMILARITY);
+    }
+
+    #[test]
+    fn a_clear_winner_is_matched() {
+        let picked = pick_match(
+            &row("Severance", Some(2022)),
+            vec![candidate("1", "Severance", Some("2022-02-18")), candidate("2", "Succession", Some("2018-06-03"))],
+        );
+        assert!(matches!(picked, MatchOutcome::Matched(v) if v.video_id == "1"));
+    }
+
+    #[test]
+    fn a_tie_goes_to_review() {
+        // Both score 1.0: the margin check, not the order, decides
+        let picked = pick_match(
+            &row("The Office", None),
+            vec![candidate("1", "The Office", Some("2001-07-09")), candidate("2", "The Office", Some("2005-03-24"))],
+        );
+        match picked {
+            MatchOutcome::Ambiguous(options) => assert_eq!(options.len(), 2),
+            other => panic!("expected a review, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn a_wrong_year_or_no_candidates_is_not_a_match() {
+        // Same title, but premiered years apart: below the threshold
+        let picked = pick_match(&row("Dune", Some(2021)), vec![candidate("1", "Dune", Some("2000-12-03"))]);
+        assert!(matches!(picked, MatchOutcome::Ambiguous(_)));
+        assert_eq!(pick_match(&row("Dune", None), Vec::new()), MatchOutcome::Unmatched);
+    }
+
+    #[test]
+    fn an_import_dates_the_users_rows_not_the_show() {
+        use crate::model::store::MemoryStore;
+        use futures::executor::block_on;
+
+        let store = MemoryStore::new();
+        let user = Thing::from(("user", "ann"));
+        let show = candidate("1", "Severance", None).video;
+        let saved_at = show.saved_at;
+        block_on(store.save_video(&user, show.clone())).unwrap();
+
+        let watched = NaiveDate::from_ymd_opt(2020, 5, 1).unwrap().and_hms_opt(12, 0, 0).unwrap().and_utc();
+        let picks = vec![(ImportRow { watched_at: Some(watched), ..row("Severance", None) }, show)];
+        block_on(commit_import_with(&store, user.clone(), ImportSource::Trakt, picks)).unwrap();
+
+        assert_eq!(block_on(store.library(&user)).unwrap()[0].saved_at, saved_at);
+        let logged = block_on(store.latest_interaction(&user)).unwrap().unwrap();
+        assert_eq!(logged.timestamp, watched.to_rfc3339());
+        assert_eq!(block_on(store.watchlist(&user)).unwrap()[0].timestamp, watched.to_rfc3339());
+    }
+
+    #[test]
+    fn importing_the_same_export_twice_skips_what_is_there() {
+        use crate::model::store::MemoryStore;
+        use futures::executor::block_on;
+
+        let store = MemoryStore::new();
+        let user = Thing::from(("user", "ann"));
+        let watched = NaiveDate::from_ymd_opt(2020, 5, 1).unwrap().and_hms_opt(12, 0, 0).unwrap().and_utc();
+        let picks = || {
+            vec![
+                (ImportRow { watched_at: Some(watched), ..row("Severance", None) }, candidate("1", "Severance", None).video),
+                (ImportRow { watched_at: Some(watched), ..row("Dark", None) }, candidate("2", "Dark", None).video),
+            ]
+        };
+
+        let first = block_on(commit_import_with(&store, user.clone(), ImportSource::Trakt, picks())).unwrap();
+        assert_eq!((first.titles, first.skipped), (2, 0));
+        let second = block_on(commit_import_with(&store, user.clone(), ImportSource::Trakt, picks())).unwrap();
+        assert_eq!((second.titles, second.skipped), (0, 2));
+        assert_eq!(block_on(store.user_interactions(&user)).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,397 @@
+// <------- User-created lists ("Comfort shows", "Watch with family") ------->
+// A list is an ordered set of show references with a note each. It is shared
+// as a small JSON document, or the same document squeezed into one string
+// that fits in a chat message.
+
+use crate::api::search::{fetch_show, search_videos};
+use crate::model::config::RecommenderConfig;
+use crate::model::error::GlassBoxError;
+use crate::model::store::{Store, SurrealStore};
+use crate::model::svd::SVD;
+use crate::model::trending::{get_trending, is_trending_query};
+use crate::model::video::Video;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+// Tells a shared GlassBox list apart from any other JSON / text
+pub const LIST_FORMAT: &str = "glassbox-list";
+pub const LIST_VERSION: u32 = 1;
+const CODE_PREFIX: &str = "gblist1:";
+
+// Suggestions shown under "More like this list"
+const SUGGESTION_LIMIT: usize = 20;
+const TRENDING_LIMIT: usize = 40;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ListItem {
+    pub video_id: String,
+    pub video_title: String,
+    #[serde(default)]
+    pub note: String,
+}
+
+// Items are kept in the user's order
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UserList {
+    pub id: Option<Thing>,
+    pub user: Thing,
+    pub name: String,
+    pub description: String,
+    pub items: Vec<ListItem>,
+    pub created_at: String,
+    pub timestamp: String, // last change
+}
+
+impl UserList {
+    pub fn contains(&self, video_id: &str) -> bool {
+        self.items.iter().any(|i| i.video_id == video_id)
+    }
+}
+
+// <------- Editing ------->
+
+pub async fn create_list(user_id: Thing, name: String, description: String) -> Result<UserList, GlassBoxError> {
+    create_list_with(&SurrealStore, user_id, name, description).await
+}
+
+pub async fn create_list_with<S: Store>(store: &S, user_id: Thing, name: String, description: String) -> Result<UserList, GlassBoxError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(GlassBoxError::Validation("Give the list a name".to_string()));
+    }
+    if store.lists(&user_id).await?.iter().any(|l| l.name.eq_ignore_ascii_case(&name)) {
+        return Err(GlassBoxError::Validation(format!("You already have a list called '{}'", name)));
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let list = UserList {
+        id: None,
+        user: user_id,
+        name,
+        description: description.trim().to_string(),
+        items: Vec::new(),
+        created_at: now.clone(),
+        timestamp: now,
+    };
+    store.save_list(list).await
+}
+
+// Every edit (add, remove, reorder, note) saves the whole list
+pub async fn save_list(list: UserList) -> Result<UserList, GlassBoxError> {
+    save_list_with(&SurrealStore, list).await
+}
+
+pub async fn save_list_with<S: Store>(store: &S, list: UserList) -> Result<UserList, GlassBoxError> {
+    if list.name.trim().is_empty() {
+        return Err(GlassBoxError::Validation("Give the list a name".to_string()));
+    }
+    store.save_list(UserList {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        ..list
+    })
+    .await
+}
+
+pub async fn add_to_list(list: UserList, video: Video) -> Result<UserList, GlassBoxError> {
+    add_to_list_with(&SurrealStore, list, video).await
+}
+
+pub async fn add_to_list_with<S: Store>(store: &S, mut list: UserList, video: Video) -> Result<UserList, GlassBoxError> {
+    if list.contains(&video.video_id) {
+        return Ok(list);
+    }
+    // List pages read posters from the user's library
+    if !store.library(&list.user).await?.iter().any(|v| v.video_id == video.video_id) {
+        store.save_video(&list.user, video.clone()).await?;
+    }
+    list.items.push(ListItem {
+        video_id: video.video_id,
+        video_title: video.title,
+        note: String::new(),
+    });
+    save_list_with(store, list).await
+}
+
+// Moves the item at `from` one step up (-1) or down (+1)
+pub fn move_item(list: &mut UserList, from: usize, step: isize) {
+    let to = from as isize + step;
+    if from < list.items.len() && to >= 0 && (to as usize) < list.items.len() {
+        list.items.swap(from, to as usize);
+    }
+}
+
+pub async fn get_lists(user_id: Thing) -> Result<Vec<UserList>, GlassBoxError> {
+    SurrealStore.lists(&user_id).await
+}
+
+pub async fn delete_list(id: Thing) -> Result<(), GlassBoxError> {
+    SurrealStore.delete_list(&id).await
+}
+
+// <------- Sharing ------->
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SharedItem {
+    #[serde(rename = "i")]
+    pub video_id: String,
+    #[serde(rename = "t")]
+    pub title: String,
+    #[serde(rename = "n", default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+}
+
+// Short keys keep the compact code short; the JSON export uses the same layout
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SharedList {
+    pub format: String,
+    pub version: u32,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub items: Vec<SharedItem>,
+}
+
+impl SharedList {
+    pub fn from_list(list: &UserList) -> Self {
+        Self {
+            format: LIST_FORMAT.to_string(),
+            version: LIST_VERSION,
+            name: list.name.clone(),
+            description: list.description.clone(),
+            items: list
+                .items
+                .iter()
+                .map(|i| SharedItem {
+                    video_id: i.video_id.clone(),
+                    title: i.video_title.clone(),
+                    note: i.note.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, GlassBoxError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    // "gblist1:<base64url(json)>", safe to paste anywhere
+    pub fn to_code(&self) -> Result<String, GlassBoxError> {
+        let json = serde_json::to_string(self)?;
+        Ok(format!("{}{}", CODE_PREFIX, URL_SAFE_NO_PAD.encode(json)))
+    }
+
+    // Accepts either form
+    pub fn parse(text: &str) -> Result<Self, GlassBoxError> {
+        let text = text.trim();
+        let damaged = || GlassBoxError::Validation("This list code is damaged or incomplete".to_string());
+        let shared: Self = match text.strip_prefix(CODE_PREFIX) {
+            Some(code) => {
+                let bytes = URL_SAFE_NO_PAD.decode(code.trim()).map_err(|_| damaged())?;
+                // A code cut short in a chat message still decodes, to half a document
+                serde_json::from_slice(&bytes).map_err(|_| damaged())?
+            }
+            None => serde_json::from_str(text).map_err(|_| GlassBoxError::Validation("This is not a GlassBox list".to_string()))?,
+        };
+        if shared.format != LIST_FORMAT {
+            return Err(GlassBoxError::Validation("This is not a GlassBox list".to_string()));
+        }
+        if shared.version == 0 || shared.version > LIST_VERSION {
+            return Err(GlassBoxError::Validation(format!(
+                "List version {} is not supported (this GlassBox reads up to version {})",
+                shared.version, LIST_VERSION
+            )));
+        }
+        if shared.name.trim().is_empty() {
+            return Err(GlassBoxError::Validation("The list has no name".to_string()));
+        }
+        Ok(shared)
+    }
+
+    pub fn filename(&self) -> String {
+        let slug: String = self
+            .name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+            .collect();
+        format!("glassbox-list-{}.json", slug.trim_matches('-'))
+    }
+}
+
+// Creates a new list from a shared one. Shows we have never seen are fetched
+// from TVMaze so they get posters and genres; a clashing name gets " (2)".
+pub async fn import_list(user_id: Thing, shared: SharedList) -> Result<UserList, GlassBoxError> {
+    import_list_with(&SurrealStore, user_id, shared).await
+}
+
+pub async fn import_list_with<S: Store>(store: &S, user_id: Thing, shared: SharedList) -> Result<UserList, GlassBoxError> {
+    let existing = store.lists(&user_id).await?;
+    let mut name = shared.name.trim().to_string();
+    let mut n = 2;
+    while existing.iter().any(|l| l.name.eq_ignore_ascii_case(&name)) {
+        name = format!("{} ({})", shared.name.trim(), n);
+        n += 1;
+    }
+
+    let library = store.library(&user_id).await?;
+    let mut items = Vec::new();
+    for item in shared.items {
+        if items.iter().any(|i: &ListItem| i.video_id == item.video_id) {
+            continue;
+        }
+        if !library.iter().any(|v| v.video_id == item.video_id) {
+            match fetch_show(&item.video_id).await {
+                Ok(video) => {
+                    store.save_video(&user_id, video).await?;
+                }
+                Err(e) => leptos::logging::error!("LISTS: Could not fetch show {}: {:?}", item.video_id, e),
+            }
+        }
+        items.push(ListItem {
+            video_id: item.video_id,
+            video_title: item.title,
+            note: item.note,
+        });
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    store
+        .save_list(UserList {
+            id: None,
+            user: user_id,
+            name,
+            description: shared.description,
+            items,
+            created_at: now.clone(),
+            timestamp: now,
+        })
+        .await
+}
+
+// <------- "More like this list" ------->
+
+// A taste vector built from the list alone (no recency: order is the user's
+// choice, not time), then the same genre search + ranking as the feed
+pub async fn more_like_list(list: &UserList, library: &[Video], config: &RecommenderConfig) -> Result<Vec<Video>, GlassBoxError> {
+    let shows: Vec<Video> = library.iter().filter(|v| list.contains(&v.video_id)).cloned().collect();
+    if shows.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let flat = RecommenderConfig {
+        recency_multipliers: Vec::new(),
+        ..config.clone()
+    };
+    let list_vec = SVD::user_vector(&shows, &flat);
+
+    let mut candidates: Vec<Video> = Vec::new();
+    for genre in SVD::get_top_genres(&list_vec, &flat) {
+        // Same as the feed: the fallback query means the popularity list
+        let found = if is_trending_query(&genre) {
+            get_trending(TRENDING_LIMIT).await?
+        } else {
+            search_videos(&genre).await?
+        };
+        for v in found {
+            if !list.contains(&v.video_id) && !candidates.iter().any(|c| c.video_id == v.video_id) {
+                candidates.push(v);
+            }
+        }
+    }
+
+    candidates.sort_by(|a, b| {
+        SVD::predict_match(&list_vec, b)
+            .partial_cmp(&SVD::predict_match(&list_vec, a))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    candidates.truncate(SUGGESTION_LIMIT);
+    Ok(candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::store::MemoryStore;
+    use futures::executor::block_on;
+
+    fn list(items: &[&str]) -> UserList {
+        UserList {
+            id: None,
+            user: Thing::from(("user", "ann")),
+            name: "Comfort shows".to_string(),
+            description: String::new(),
+            items: items
+                .iter()
+                .map(|id| ListItem {
+                    video_id: id.to_string(),
+                    video_title: format!("Show {}", id),
+                    note: if *id == "1" { "rainy days, \"always\"".to_string() } else { String::new() },
+                })
+                .collect(),
+            created_at: String::new(),
+            timestamp: String::new(),
+        }
+    }
+
+    fn rejected(text: &str) -> String {
+        match SharedList::parse(text) {
+            Err(GlassBoxError::Validation(msg)) => msg,
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn code_and_json_round_trip() {
+        let shared = SharedList::from_list(&list(&["1", "2"]));
+        let code = shared.to_code().unwrap();
+        assert!(code.starts_with("gblist1:"));
+        assert_eq!(SharedList::parse(&format!("  {}\n", code)).unwrap(), shared);
+        assert_eq!(SharedList::parse(&shared.to_json().unwrap()).unwrap(), shared);
+        // Empty notes are left out of the code
+        assert!(!shared.to_json().unwrap().contains("\"n\": \"\""));
+    }
+
+    #[test]
+    fn damaged_codes_are_refused() {
+        let code = SharedList::from_list(&list(&["1"])).to_code().unwrap();
+        assert!(rejected(&code[..code.len() - 5]).contains("damaged"));
+        assert!(rejected("gblist1:not base64!").contains("damaged"));
+        assert!(rejected(&format!("gblist1:{}", URL_SAFE_NO_PAD.encode([0xff, 0xfe]))).contains("damaged"));
+        assert!(rejected("{\"format\": \"something-else\", \"version\": 1, \"name\": \"x\", \"items\": []}").contains("not a GlassBox list"));
+    }
+
+    #[test]
+    fn newer_versions_and_nameless_lists_are_refused() {
+        let mut shared = SharedList::from_list(&list(&["1"]));
+        shared.version = LIST_VERSION + 1;
+        assert!(rejected(&shared.to_code().unwrap()).contains("version 2"));
+        shared.version = 0;
+        assert!(rejected(&shared.to_json().unwrap()).contains("version 0"));
+
+        let mut nameless = SharedList::from_list(&list(&["1"]));
+        nameless.name = "  ".to_string();
+        assert!(rejected(&nameless.to_code().unwrap()).contains("no name"));
+    }
+
+    #[test]
+    fn items_move_one_step_within_bounds() {
+        let mut l = list(&["1", "2", "3"]);
+        move_item(&mut l, 0, 1);
+        move_item(&mut l, 0, -1);
+        move_item(&mut l, 2, 1);
+        move_item(&mut l, 7, -1);
+        assert_eq!(l.items.iter().map(|i| i.video_id.as_str()).collect::<Vec<_>>(), vec!["2", "1", "3"]);
+        assert_eq!(SharedList::from_list(&l).filename(), "glassbox-list-comfort-shows.json");
+    }
+
+    #[test]
+    fn list_names_are_unique_per_user() {
+        let store = MemoryStore::new();
+        let user = Thing::from(("user", "ann"));
+        block_on(create_list_with(&store, user.clone(), " Comfort shows ".to_string(), String::new())).unwrap();
+        assert!(block_on(create_list_with(&store, user.clone(), "comfort SHOWS".to_string(), String::new())).is_err());
+        assert!(block_on(create_list_with(&store, user.clone(), "".to_string(), String::new())).is_err());
+        assert!(block_on(create_list_with(&store, Thing::from(("user", "bob")), "Comfort shows".to_string(), String::new())).is_ok());
+    }
+}
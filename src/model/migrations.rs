@@ -0,0 +1,385 @@
+// <------- Schema + versioned migrations for the local vault ------->
+// Each migration runs once, inside a transaction, in version order. The
+// applied version lives in schema_version:current, so a browser that
+// already has data only gets the steps it is missing.
+// Never edit a shipped migration: append a new one instead.
+
+use crate::model::error::GlassBoxError;
+use crate::model::users::User;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use surrealdb::engine::any::Any;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+struct Migration {
+    version: u32,
+    name: &'static str,
+    sql: &'static str,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "core tables",
+        sql: "
+            DEFINE TABLE IF NOT EXISTS user SCHEMALESS;
+            DEFINE FIELD IF NOT EXISTS username ON TABLE user TYPE string;
+            DEFINE FIELD IF NOT EXISTS email ON TABLE user TYPE string;
+            DEFINE FIELD IF NOT EXISTS password ON TABLE user TYPE string;
+            DEFINE FIELD IF NOT EXISTS joined_at ON TABLE user TYPE string;
+
+            DEFINE TABLE IF NOT EXISTS video SCHEMALESS;
+            DEFINE FIELD IF NOT EXISTS video_id ON TABLE video TYPE string;
+            DEFINE FIELD IF NOT EXISTS title ON TABLE video TYPE string;
+            DEFINE FIELD IF NOT EXISTS rating ON TABLE video TYPE number;
+            DEFINE FIELD IF NOT EXISTS genres ON TABLE video TYPE array<string>;
+            DEFINE FIELD IF NOT EXISTS related_ids ON TABLE video TYPE array<string>;
+
+            DEFINE TABLE IF NOT EXISTS interaction SCHEMALESS;
+            DEFINE FIELD IF NOT EXISTS user ON TABLE interaction TYPE record<user>;
+            DEFINE FIELD IF NOT EXISTS video_id ON TABLE interaction TYPE string;
+            DEFINE FIELD IF NOT EXISTS interaction_type ON TABLE interaction TYPE string;
+            DEFINE FIELD IF NOT EXISTS timestamp ON TABLE interaction TYPE string;
+            DEFINE INDEX IF NOT EXISTS interaction_user ON TABLE interaction FIELDS user;
+
+            DEFINE TABLE IF NOT EXISTS search_history SCHEMALESS;
+            DEFINE FIELD IF NOT EXISTS user ON TABLE search_history TYPE record<user>;
+            DEFINE FIELD IF NOT EXISTS query ON TABLE search_history TYPE string;
+            DEFINE FIELD IF NOT EXISTS timestamp ON TABLE search_history TYPE string;
+            DEFINE INDEX IF NOT EXISTS search_history_user ON TABLE search_history FIELDS user;
+
+            DEFINE TABLE IF NOT EXISTS settings SCHEMALESS;
+            DEFINE FIELD IF NOT EXISTS user ON TABLE settings TYPE record<user>;
+
+            DEFINE TABLE IF NOT EXISTS person SCHEMALESS;
+            DEFINE FIELD IF NOT EXISTS person_id ON TABLE person TYPE string;
+            DEFINE FIELD IF NOT EXISTS name ON TABLE person TYPE string;
+
+            DEFINE TABLE IF NOT EXISTS credited TYPE RELATION IN video OUT person SCHEMALESS;
+            DEFINE FIELD IF NOT EXISTS role ON TABLE credited TYPE string;
+
+            DEFINE TABLE IF NOT EXISTS trending SCHEMALESS;
+        ",
+    },
+    Migration {
+        version: 2,
+        name: "unique usernames and emails",
+        sql: "
+            DEFINE INDEX IF NOT EXISTS user_username ON TABLE user FIELDS username UNIQUE;
+            DEFINE INDEX IF NOT EXISTS user_email ON TABLE user FIELDS email UNIQUE;
+        ",
+    },
+    Migration {
+        version: 3,
+        name: "explicit ratings",
+        sql: "
+            DEFINE TABLE IF NOT EXISTS rating SCHEMALESS;
+            DEFINE FIELD IF NOT EXISTS user ON TABLE rating TYPE record<user>;
+            DEFINE FIELD IF NOT EXISTS video_id ON TABLE rating TYPE string;
+            DEFINE FIELD IF NOT EXISTS score ON TABLE rating TYPE number ASSERT $value >= 1 AND $value <= 10;
+            DEFINE FIELD IF NOT EXISTS source ON TABLE rating TYPE string;
+            DEFINE INDEX IF NOT EXISTS rating_user ON TABLE rating FIELDS user;
+        ",
+    },
+    Migration {
+        version: 4,
+        name: "vault keys",
+        sql: "
+            DEFINE TABLE IF NOT EXISTS vault SCHEMALESS;
+            DEFINE FIELD IF NOT EXISTS user ON TABLE vault TYPE record<user>;
+            DEFINE FIELD IF NOT EXISTS salt ON TABLE vault TYPE string;
+            DEFINE FIELD IF NOT EXISTS check ON TABLE vault TYPE string;
+        ",
+    },
+    Migration {
+        version: 5,
+        name: "watch states",
+        sql: "
+            DEFINE TABLE IF NOT EXISTS watchlist SCHEMALESS;
+            DEFINE FIELD IF NOT EXISTS user ON TABLE watchlist TYPE record<user>;
+            DEFINE FIELD IF NOT EXISTS video_id ON TABLE watchlist TYPE string;
+            DEFINE FIELD IF NOT EXISTS state ON TABLE watchlist TYPE string
+                ASSERT $value IN ['want_to_watch', 'watching', 'watched', 'dropped'];
+            DEFINE FIELD IF NOT EXISTS timestamp ON TABLE watchlist TYPE string;
+            DEFINE INDEX IF NOT EXISTS watchlist_user ON TABLE watchlist FIELDS user;
+        ",
+    },
+    Migration {
+        version: 6,
+        name: "episode progress",
+        sql: "
+            DEFINE TABLE IF NOT EXISTS episode_list SCHEMALESS;
+            DEFINE FIELD IF NOT EXISTS fetched_at ON TABLE episode_list TYPE datetime;
+
+            DEFINE TABLE IF NOT EXISTS episode_progress SCHEMALESS;
+            DEFINE FIELD IF NOT EXISTS user ON TABLE episode_progress TYPE record<user>;
+            DEFINE FIELD IF NOT EXISTS video_id ON TABLE episode_progress TYPE string;
+            DEFINE FIELD IF NOT EXISTS watched ON TABLE episode_progress TYPE array<string>;
+            DEFINE FIELD IF NOT EXISTS timestamp ON TABLE episode_progress TYPE string;
+            DEFINE INDEX IF NOT EXISTS episode_progress_user ON TABLE episode_progress FIELDS user;
+        ",
+    },
+    Migration {
+        version: 7,
+        name: "user lists",
+        sql: "
+            DEFINE TABLE IF NOT EXISTS user_list SCHEMALESS;
+            DEFINE FIELD IF NOT EXISTS user ON TABLE user_list TYPE record<user>;
+            DEFINE FIELD IF NOT EXISTS name ON TABLE user_list TYPE string;
+            DEFINE FIELD IF NOT EXISTS items ON TABLE user_list TYPE array<object>;
+            DEFINE FIELD IF NOT EXISTS timestamp ON TABLE user_list TYPE string;
+            DEFINE INDEX IF NOT EXISTS user_list_user ON TABLE user_list FIELDS user;
+        ",
+    },
+    Migration {
+        version: 8,
+        name: "notes and tags",
+        sql: "
+            DEFINE TABLE IF NOT EXISTS title_note SCHEMALESS;
+            DEFINE FIELD IF NOT EXISTS user ON TABLE title_note TYPE record<user>;
+            DEFINE FIELD IF NOT EXISTS video_id ON TABLE title_note TYPE string;
+            DEFINE FIELD IF NOT EXISTS text ON TABLE title_note TYPE string;
+            DEFINE FIELD IF NOT EXISTS tags ON TABLE title_note TYPE array<string>;
+            DEFINE FIELD IF NOT EXISTS timestamp ON TABLE title_note TYPE string;
+            DEFINE INDEX IF NOT EXISTS title_note_user ON TABLE title_note FIELDS user;
+        ",
+    },
+    Migration {
+        version: 9,
+        name: "interaction runs",
+        sql: "
+            DEFINE FIELD IF NOT EXISTS count ON TABLE interaction TYPE int DEFAULT 1;
+            DEFINE FIELD IF NOT EXISTS last_at ON TABLE interaction TYPE string DEFAULT '';
+        ",
+    },
+    Migration {
+        version: 10,
+        name: "episode cache timestamps",
+        // v6 declared a datetime, but the cache stores chrono's RFC 3339 string
+        // (like the trending cache), so every write to episode_list failed
+        sql: "
+            REMOVE FIELD IF EXISTS fetched_at ON TABLE episode_list;
+            DEFINE FIELD fetched_at ON TABLE episode_list TYPE string;
+        ",
+    },
+    Migration {
+        version: 11,
+        name: "per-user libraries",
+        // The shared video table showed every account's shows and save dates.
+        // Rows move into library_item the next time their owner unlocks (vault.rs).
+        sql: "
+            DEFINE TABLE IF NOT EXISTS library_item SCHEMALESS;
+            DEFINE FIELD IF NOT EXISTS user ON TABLE library_item TYPE record<user>;
+            DEFINE FIELD IF NOT EXISTS video ON TABLE library_item TYPE string;
+            DEFINE FIELD IF NOT EXISTS saved_at ON TABLE library_item TYPE string;
+            DEFINE INDEX IF NOT EXISTS library_item_user ON TABLE library_item FIELDS user;
+
+            DEFINE FIELD IF NOT EXISTS library ON TABLE vault TYPE bool DEFAULT false;
+        ",
+    },
+];
+
+// v2's UNIQUE indexes would fail on a vault that already holds duplicate
+// usernames or emails, leaving the app unable to start. The oldest account
+// keeps its name; later ones are renamed in the same transaction.
+const UNIQUE_ACCOUNTS_VERSION: u32 = 2;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+struct AccountRename {
+    id: Thing,
+    username: String,
+    email: String,
+}
+
+// "name" -> "name-2", "me@x.org" -> "me+2@x.org"
+fn numbered(value: &str, n: usize, email: bool) -> String {
+    match value.split_once('@') {
+        Some((local, domain)) if email => format!("{}+{}@{}", local, n, domain),
+        _ => format!("{}-{}", value, n),
+    }
+}
+
+// First free numbered variant, never one another account already uses
+fn free_variant(value: &str, email: bool, taken: &HashSet<String>) -> String {
+    (2..).map(|n| numbered(value, n, email)).find(|v| !taken.contains(v)).unwrap_or_default()
+}
+
+fn dedupe_accounts(users: &[User]) -> Vec<AccountRename> {
+    let mut ordered: Vec<&User> = users.iter().filter(|u| u.id.is_some()).collect();
+    ordered.sort_by(|a, b| {
+        a.joined_at
+            .cmp(&b.joined_at)
+            .then_with(|| a.id.as_ref().map(|t| t.to_string()).cmp(&b.id.as_ref().map(|t| t.to_string())))
+    });
+
+    let mut names: HashSet<String> = users.iter().map(|u| u.username.clone()).collect();
+    let mut emails: HashSet<String> = users.iter().map(|u| u.email.clone()).collect();
+    let mut seen_names = HashSet::new();
+    let mut seen_emails = HashSet::new();
+    let mut renames = Vec::new();
+
+    for user in ordered {
+        let username = if seen_names.contains(&user.username) {
+            free_variant(&user.username, false, &names)
+        } else {
+            user.username.clone()
+        };
+        let email = if seen_emails.contains(&user.email) {
+            free_variant(&user.email, true, &emails)
+        } else {
+            user.email.clone()
+        };
+        names.insert(username.clone());
+        emails.insert(email.clone());
+        seen_names.insert(username.clone());
+        seen_emails.insert(email.clone());
+
+        if username != user.username || email != user.email {
+            if let Some(id) = user.id.clone() {
+                renames.push(AccountRename { id, username, email });
+            }
+        }
+    }
+    renames
+}
+
+// Type the newest migration gives `table.field` (tests check rows against it)
+#[cfg(test)]
+pub(crate) fn declared_type(table: &str, field: &str) -> Option<String> {
+    let needle = format!("{} ON TABLE {} TYPE ", field, table);
+    MIGRATIONS
+        .iter()
+        .rev()
+        .flat_map(|m| m.sql.lines().rev())
+        .find_map(|line| line.split_once(needle.as_str()).map(|(_, rest)| rest))
+        .and_then(|rest| rest.split([' ', ';']).next())
+        .map(|t| t.to_string())
+}
+
+// Latest version this build knows about
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct SchemaVersion {
+    version: u32,
+    applied_at: String,
+}
+
+async fn current_version(db: &Surreal<Any>) -> Result<u32, GlassBoxError> {
+    let record: Option<SchemaVersion> = db.select(("schema_version", "current")).await?;
+    Ok(record.map(|r| r.version).unwrap_or(0))
+}
+
+// Brings the vault up to latest_version(); returns the version it ended on
+pub async fn run_migrations(db: &Surreal<Any>) -> Result<u32, GlassBoxError> {
+    let mut version = current_version(db).await?;
+
+    if version > latest_version() {
+        // Data written by a newer build: refuse rather than guess
+        return Err(GlassBoxError::Database(format!(
+            "Local data is schema v{}, this version of GlassBox only understands up to v{}",
+            version,
+            latest_version()
+        )));
+    }
+
+    let applied = version;
+    for migration in MIGRATIONS.iter().filter(|m| m.version > applied) {
+        leptos::logging::log!("MIGRATIONS: Applying v{} ({})", migration.version, migration.name);
+
+        let renames = if migration.version == UNIQUE_ACCOUNTS_VERSION {
+            let users: Vec<User> = db.query("SELECT * FROM user").await?.take(0)?;
+            let renames = dedupe_accounts(&users);
+            for r in &renames {
+                leptos::logging::log!("MIGRATIONS: Duplicate account renamed to '{}' ({})", r.username, r.email);
+            }
+            renames
+        } else {
+            Vec::new()
+        };
+
+        // Schema changes and the version bump commit together or not at all
+        let sql = format!(
+            "BEGIN TRANSACTION;
+             FOR $rename IN $renames {{ UPDATE $rename.id SET username = $rename.username, email = $rename.email; }};
+             {}
+             UPSERT schema_version:current CONTENT {{ version: $version, applied_at: $applied_at }};
+             COMMIT TRANSACTION;",
+            migration.sql
+        );
+        let response = db
+            .query(sql)
+            .bind(("renames", renames))
+            .bind(("version", migration.version))
+            .bind(("applied_at", chrono::Utc::now().to_rfc3339()))
+            .await?;
+        response.check().map_err(|e| {
+            GlassBoxError::Database(format!("migration v{} ({}) failed: {}", migration.version, migration.name, e))
+        })?;
+
+        version = migration.version;
+    }
+
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: &str, username: &str, email: &str, joined_at: &str) -> User {
+        User {
+            id: Some(Thing::from(("user", id))),
+            email: email.to_string(),
+            username: username.to_string(),
+            joined_at: joined_at.to_string(),
+            password: String::new(),
+        }
+    }
+
+    #[test]
+    fn unique_accounts_are_left_alone() {
+        let users = [user("a", "ann", "ann@x.org", "2024-01-01"), user("b", "bob", "bob@x.org", "2024-01-02")];
+        assert!(dedupe_accounts(&users).is_empty());
+    }
+
+    #[test]
+    fn oldest_account_keeps_its_name() {
+        let users = [user("b", "ann", "ann2@x.org", "2024-02-01"), user("a", "ann", "ann@x.org", "2024-01-01")];
+        let renames = dedupe_accounts(&users);
+        assert_eq!(renames.len(), 1);
+        assert_eq!(renames[0].id, Thing::from(("user", "b")));
+        assert_eq!(renames[0].username, "ann-2");
+        assert_eq!(renames[0].email, "ann2@x.org");
+    }
+
+    #[test]
+    fn renames_skip_names_already_in_use() {
+        let users = [
+            user("a", "ann", "a@x.org", "2024-01-01"),
+            user("b", "ann-2", "b@x.org", "2024-01-02"),
+            user("c", "ann", "c@x.org", "2024-01-03"),
+            user("d", "ann", "d@x.org", "2024-01-04"),
+        ];
+        let names: Vec<String> = dedupe_accounts(&users).into_iter().map(|r| r.username).collect();
+        assert_eq!(names, vec!["ann-3", "ann-4"]);
+    }
+
+    #[test]
+    fn duplicate_emails_get_a_plus_address() {
+        let users = [user("a", "ann", "me@x.org", "2024-01-01"), user("b", "bob", "me@x.org", "2024-01-02")];
+        let renames = dedupe_accounts(&users);
+        assert_eq!(renames.len(), 1);
+        assert_eq!(renames[0].username, "bob");
+        assert_eq!(renames[0].email, "me+2@x.org");
+    }
+
+    #[test]
+    fn migrations_are_in_order() {
+        assert!(MIGRATIONS.windows(2).all(|w| w[0].version < w[1].version));
+        assert_eq!(latest_version(), MIGRATIONS.last().map(|m| m.version).unwrap_or(0));
+    }
+}
@@ -1,7 +1,32 @@
+pub mod archive;
+pub mod backup;
+pub mod config;
+pub mod crypto;
 pub mod db;
+pub mod download;
+pub mod episodes;
+pub mod error;
 pub mod feed_control;
+pub mod graph;
 pub mod history;
+pub mod importers;
+pub mod lists;
+pub mod migrations;
+pub mod next_item;
+pub mod notes;
+pub mod person;
+pub mod queries;
+pub mod ratings;
+pub mod search_index;
 pub mod session;
+pub mod storage;
+pub mod store;
 pub mod svd;
+pub mod text;
+pub mod trending;
+pub mod tuning;
+pub mod upload;
 pub mod users;
+pub mod vault;
 pub mod video;
+pub mod watchlist;
@@ -0,0 +1,210 @@
+// <------- Short-term "what do people open next" model ------->
+// First-order Markov chain over one user's ordered interaction log (rows are
+// sealed per account, so nobody else's clicks are readable); a long pause
+// starts a new session.
+
+use crate::model::history::InteractionEntry;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+// (first event, last event, video_id) of one run in a user's log
+type Run<'a> = (DateTime<Utc>, DateTime<Utc>, &'a str);
+
+#[derive(Debug, Default, Clone)]
+pub struct TransitionModel {
+    // from video_id -> (to video_id -> count)
+    counts: HashMap<String, HashMap<String, f64>>,
+}
+
+impl TransitionModel {
+    pub fn from_interactions(entries: &[InteractionEntry], session_gap_minutes: i64) -> Self {
+        let mut model = Self::default();
+
+        // Group per user, then walk each user's log in time order. A row is a
+        // run of repeats: the gap to the next show counts from its last event.
+        let mut per_user: HashMap<String, Vec<Run>> = HashMap::new();
+        for entry in entries {
+            let Ok(ts) = DateTime::parse_from_rfc3339(&entry.timestamp) else {
+                continue;
+            };
+            let last = DateTime::parse_from_rfc3339(entry.last_seen()).unwrap_or(ts);
+            per_user
+                .entry(entry.user.to_string())
+                .or_default()
+                .push((ts.with_timezone(&Utc), last.with_timezone(&Utc), entry.video_id.as_str()));
+        }
+
+        for events in per_user.values_mut() {
+            events.sort_by_key(|e| e.0);
+
+            for pair in events.windows(2) {
+                let (_, prev_last, prev_id) = pair[0];
+                let (next_ts, _, next_id) = pair[1];
+
+                // Re-clicking the same card is not a transition
+                if prev_id == next_id {
+                    continue;
+                }
+                if (next_ts - prev_last).num_minutes() > session_gap_minutes {
+                    continue;
+                }
+
+                *model
+                    .counts
+                    .entry(prev_id.to_string())
+                    .or_default()
+                    .entry(next_id.to_string())
+                    .or_insert(0.0) += 1.0;
+            }
+        }
+
+        model
+    }
+
+    // P(next | last), highest first
+    pub fn next_items(&self, last_video_id: &str, limit: usize) -> Vec<(String, f64)> {
+        let Some(row) = self.counts.get(last_video_id) else {
+            return Vec::new();
+        };
+
+        let total: f64 = row.values().sum();
+        if total <= 0.0 {
+            return Vec::new();
+        }
+
+        let mut probs: Vec<(String, f64)> = row
+            .iter()
+            .map(|(id, count)| (id.clone(), count / total))
+            .collect();
+        probs.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        probs.truncate(limit);
+        probs
+    }
+
+    // Shows opened right before or right after this one, by transition count
+    pub fn co_visited(&self, video_id: &str, limit: usize) -> Vec<String> {
+        let mut counts: HashMap<&str, f64> = HashMap::new();
+
+        if let Some(row) = self.counts.get(video_id) {
+            for (to, count) in row {
+                *counts.entry(to.as_str()).or_insert(0.0) += count;
+            }
+        }
+        for (from, row) in &self.counts {
+            if let Some(count) = row.get(video_id) {
+                *counts.entry(from.as_str()).or_insert(0.0) += count;
+            }
+        }
+
+        let mut ranked: Vec<(&str, f64)> = counts.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranked.into_iter().take(limit).map(|(id, _)| id.to_string()).collect()
+    }
+}
+
+// Most recent interaction of one user
+pub fn last_interaction<'a>(entries: &'a [InteractionEntry], user_id: &str) -> Option<&'a InteractionEntry> {
+    entries
+        .iter()
+        .filter(|e| e.user.to_string() == user_id)
+        .max_by(|a, b| a.last_seen().cmp(b.last_seen()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use surrealdb::sql::Thing;
+
+    // A run of `video_id` from `start` to `end` minutes after a fixed moment
+    fn run(user: &str, video_id: &str, start: i64, end: i64) -> InteractionEntry {
+        let base = DateTime::parse_from_rfc3339("2025-01-01T12:00:00+00:00").unwrap().with_timezone(&Utc);
+        InteractionEntry {
+            id: None,
+            user: Thing::from(("user", user)),
+            video_id: video_id.to_string(),
+            video_title: format!("Show {}", video_id),
+            interaction_type: "click".to_string(),
+            timestamp: (base + Duration::minutes(start)).to_rfc3339(),
+            count: 1,
+            last_at: (base + Duration::minutes(end)).to_rfc3339(),
+        }
+    }
+
+    fn click(user: &str, video_id: &str, at: i64) -> InteractionEntry {
+        run(user, video_id, at, at)
+    }
+
+    #[test]
+    fn next_items_are_ranked_by_how_often_they_followed() {
+        let log = vec![
+            click("ann", "a", 0),
+            click("ann", "b", 5),
+            click("ann", "a", 10),
+            click("ann", "b", 15),
+            click("ann", "a", 20),
+            click("ann", "c", 25),
+        ];
+        let model = TransitionModel::from_interactions(&log, 30);
+        let next = model.next_items("a", 5);
+        assert_eq!(next.iter().map(|(id, _)| id.as_str()).collect::<Vec<_>>(), vec!["b", "c"]);
+        assert!((next[0].1 - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(model.next_items("a", 1).len(), 1);
+        assert!(model.next_items("unknown", 5).is_empty());
+    }
+
+    #[test]
+    fn a_long_pause_starts_a_new_session() {
+        let log = vec![click("ann", "a", 0), click("ann", "b", 30), click("ann", "c", 61)];
+        let model = TransitionModel::from_interactions(&log, 30);
+        // Exactly the gap still counts; one minute over does not
+        assert_eq!(model.next_items("a", 5).len(), 1);
+        assert!(model.next_items("b", 5).is_empty());
+    }
+
+    #[test]
+    fn the_gap_counts_from_the_end_of_a_run() {
+        // Clicked on and off for two hours, then the next show right after
+        let log = vec![run("ann", "a", 0, 120), click("ann", "b", 125)];
+        let model = TransitionModel::from_interactions(&log, 30);
+        assert_eq!(model.next_items("a", 5)[0].0, "b");
+    }
+
+    #[test]
+    fn repeats_and_other_users_are_not_transitions() {
+        let log = vec![
+            click("ann", "a", 0),
+            click("ann", "a", 1),
+            click("bob", "b", 2),
+            InteractionEntry {
+                timestamp: "not a date".to_string(),
+                ..click("ann", "d", 3)
+            },
+        ];
+        let model = TransitionModel::from_interactions(&log, 30);
+        assert!(model.next_items("a", 5).is_empty());
+        assert!(model.next_items("b", 5).is_empty());
+        assert!(model.co_visited("d", 5).is_empty());
+    }
+
+    #[test]
+    fn co_visited_counts_both_directions() {
+        let log = vec![
+            click("ann", "x", 0),
+            click("ann", "a", 5),
+            click("ann", "y", 10),
+            click("bob", "x", 0),
+            click("bob", "a", 5),
+        ];
+        let model = TransitionModel::from_interactions(&log, 30);
+        assert_eq!(model.co_visited("a", 5), vec!["x", "y"]);
+        assert_eq!(model.co_visited("a", 1), vec!["x"]);
+    }
+
+    #[test]
+    fn last_interaction_goes_by_the_end_of_a_run() {
+        let log = vec![run("ann", "a", 0, 90), click("ann", "b", 60), click("bob", "c", 120)];
+        assert_eq!(last_interaction(&log, "user:ann").unwrap().video_id, "a");
+        assert!(last_interaction(&log, "user:carol").is_none());
+    }
+}
@@ -0,0 +1,294 @@
+// <------- Personal notes and tags on titles ------->
+// One note per (user, show): free text plus a handful of tags ("cozy",
+// "rewatch", "with mum"). Both are private, so they are sealed in the vault and
+// only ever searched in the browser (see search_index.rs).
+
+use crate::model::config::RecommenderConfig;
+use crate::model::error::GlassBoxError;
+use crate::model::store::{Store, SurrealStore};
+use crate::model::svd::SVD;
+use crate::model::video::Video;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use surrealdb::sql::Thing;
+
+// Genre similarity above which a show is said to be "like your #tag shows"
+const CLOSE_TAG: f64 = 0.8;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TitleNote {
+    pub id: Option<Thing>,
+    pub user: Thing,
+    pub video_id: String,
+    pub video_title: String,
+    pub text: String,
+    pub tags: Vec<String>, // normalised, see parse_tags
+    pub timestamp: String, // last edit
+}
+
+// "Cozy, #Rewatch ,  with  mum" -> ["cozy", "rewatch", "with mum"]
+// Commas separate tags, so a tag never contains one (the vault relies on that)
+pub fn parse_tags(raw: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for part in raw.split(',') {
+        let tag = part
+            .trim()
+            .trim_start_matches('#')
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+// `tags` is the raw tag input, see parse_tags. Saving an empty note (no text,
+// no tags) removes it.
+pub async fn save_note(user_id: Thing, video: Video, text: String, tags: String) -> Result<Option<TitleNote>, GlassBoxError> {
+    save_note_with(&SurrealStore, user_id, video, text, tags).await
+}
+
+pub async fn save_note_with<S: Store>(
+    store: &S,
+    user_id: Thing,
+    video: Video,
+    text: String,
+    tags: String,
+) -> Result<Option<TitleNote>, GlassBoxError> {
+    let text = text.trim().to_string();
+    let tags = parse_tags(&tags);
+
+    if text.is_empty() && tags.is_empty() {
+        if let Some(existing) = store.notes(&user_id).await?.into_iter().find(|n| n.video_id == video.video_id) {
+            if let Some(id) = existing.id {
+                store.delete_note(&id).await?;
+            }
+        }
+        return Ok(None);
+    }
+
+    // Search results and the taste profile read show metadata from the user's library
+    if !store.library(&user_id).await?.iter().any(|v| v.video_id == video.video_id) {
+        store.save_video(&user_id, video.clone()).await?;
+    }
+
+    let note = TitleNote {
+        id: None,
+        user: user_id,
+        video_id: video.video_id,
+        video_title: video.title,
+        text,
+        tags,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    };
+    store.save_note(note).await.map(Some)
+}
+
+// Newest edit first
+pub async fn get_notes(user_id: Thing) -> Result<Vec<TitleNote>, GlassBoxError> {
+    SurrealStore.notes(&user_id).await
+}
+
+// Every tag in use with how many shows carry it, most used first
+pub fn all_tags(notes: &[TitleNote]) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for note in notes {
+        for tag in &note.tags {
+            *counts.entry(tag.clone()).or_default() += 1;
+        }
+    }
+    let mut tags: Vec<(String, usize)> = counts.into_iter().collect();
+    tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    tags
+}
+
+// <------- Tags as taste features ------->
+
+// Each tag becomes one extra feature: how close a show's genres are to the
+// shows the user put that tag on, weighted by how much the user liked those.
+// TVMaze shows carry no personal tags, so this is how a tag reaches candidates.
+#[derive(Default)]
+pub struct TagProfile {
+    tags: Vec<TagFeature>,
+}
+
+struct TagFeature {
+    tag: String,
+    centroid: Vec<f64>, // mean genre vector of the tagged shows
+    affinity: f64,      // mean taste weight of the tagged shows
+}
+
+impl TagProfile {
+    // `weight` is the per-show taste weight the feed already uses (watch state)
+    pub fn from_notes(notes: &[TitleNote], library: &[Video], weight: impl Fn(&Video) -> f64) -> Self {
+        let mut tags = Vec::new();
+        for (tag, _) in all_tags(notes) {
+            let tagged: Vec<&Video> = notes
+                .iter()
+                .filter(|n| n.tags.contains(&tag))
+                .filter_map(|n| library.iter().find(|v| v.video_id == n.video_id))
+                .collect();
+            if tagged.is_empty() {
+                continue;
+            }
+
+            let mut centroid = Vec::new();
+            let mut affinity = 0.0;
+            for v in &tagged {
+                let vec = SVD::vectorise(v);
+                if centroid.is_empty() {
+                    centroid = vec![0.0; vec.len()];
+                }
+                for (c, x) in centroid.iter_mut().zip(vec) {
+                    *c += x;
+                }
+                affinity += weight(v);
+            }
+            let n = tagged.len() as f64;
+            centroid.iter_mut().for_each(|c| *c /= n);
+            tags.push(TagFeature {
+                tag,
+                centroid,
+                affinity: affinity / n,
+            });
+        }
+        Self { tags }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    // Affinity-weighted cosine similarity to the tag centroids, in 0..=1
+    pub fn score(&self, video: &Video) -> f64 {
+        let total: f64 = self.tags.iter().map(|t| t.affinity).sum();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        let vec = SVD::vectorise(video);
+        let weighted: f64 = self.tags.iter().map(|t| t.affinity * cosine(&vec, &t.centroid)).sum();
+        weighted / total
+    }
+
+    // The liked tag a show resembles most, for the "why is this here" line
+    pub fn closest_tag(&self, video: &Video) -> Option<&str> {
+        let vec = SVD::vectorise(video);
+        self.tags
+            .iter()
+            .filter(|t| t.affinity > 0.0)
+            .map(|t| (t, cosine(&vec, &t.centroid)))
+            .filter(|(_, sim)| *sim >= CLOSE_TAG)
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(t, _)| t.tag.as_str())
+    }
+}
+
+fn cosine(a: &[f64], b: &[f64]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = a.iter().map(|x| x * x).sum::<f64>().sqrt() * b.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        dot / norm
+    } else {
+        0.0
+    }
+}
+
+// What the feed adds to a candidate's genre match (0 = tags ignored)
+pub fn tag_boost(profile: &TagProfile, video: &Video, config: &RecommenderConfig) -> f64 {
+    if profile.is_empty() {
+        0.0
+    } else {
+        config.tag_weight * profile.score(video)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::store::MemoryStore;
+    use futures::executor::block_on;
+
+    fn show(id: &str, genres: &[&str]) -> Video {
+        Video {
+            id: None,
+            video_id: id.to_string(),
+            title: format!("Show {}", id),
+            description: String::new(),
+            thumbnail_url: String::new(),
+            rating: 7.0,
+            genres: genres.iter().map(|g| g.to_string()).collect(),
+            channel_name: String::new(),
+            related_ids: Vec::new(),
+            saved_at: chrono::Utc::now(),
+        }
+    }
+
+    fn note(id: &str, tags: &str) -> TitleNote {
+        TitleNote {
+            id: None,
+            user: Thing::from(("user", "ann")),
+            video_id: id.to_string(),
+            video_title: format!("Show {}", id),
+            text: String::new(),
+            tags: parse_tags(tags),
+            timestamp: String::new(),
+        }
+    }
+
+    #[test]
+    fn tags_are_normalised_and_deduplicated() {
+        assert_eq!(parse_tags("Cozy, #Rewatch ,  with  mum"), vec!["cozy", "rewatch", "with mum"]);
+        assert_eq!(parse_tags("cozy, COZY, #cozy, , #"), vec!["cozy"]);
+        assert!(parse_tags("  ").is_empty());
+    }
+
+    #[test]
+    fn all_tags_counts_most_used_first() {
+        let notes = vec![note("1", "rewatch, cozy"), note("2", "cozy"), note("3", "abandoned")];
+        assert_eq!(
+            all_tags(&notes),
+            vec![("cozy".to_string(), 2), ("abandoned".to_string(), 1), ("rewatch".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn a_liked_tag_pulls_similar_shows_up() {
+        let library = vec![show("1", &["Comedy"]), show("2", &["Comedy", "Family"]), show("3", &["Horror"])];
+        let notes = vec![note("1", "cozy"), note("2", "cozy"), note("3", "scary")];
+        // The horror show was dropped, so its tag carries no taste
+        let profile = TagProfile::from_notes(&notes, &library, |v| if v.video_id == "3" { 0.0 } else { 1.0 });
+
+        let comedy = show("10", &["Comedy"]);
+        let horror = show("11", &["Horror"]);
+        assert!(profile.score(&comedy) > 0.8);
+        assert_eq!(profile.score(&horror), 0.0);
+        assert_eq!(profile.closest_tag(&comedy), Some("cozy"));
+        assert_eq!(profile.closest_tag(&horror), None);
+
+        let config = RecommenderConfig { tag_weight: 0.5, ..RecommenderConfig::default() };
+        assert!((tag_boost(&profile, &comedy, &config) - 0.5 * profile.score(&comedy)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn tags_on_shows_outside_the_library_are_ignored() {
+        let profile = TagProfile::from_notes(&[note("9", "cozy")], &[show("1", &["Comedy"])], |_| 1.0);
+        assert!(profile.is_empty());
+        let config = RecommenderConfig { tag_weight: 1.0, ..RecommenderConfig::default() };
+        assert_eq!(tag_boost(&profile, &show("1", &["Comedy"]), &config), 0.0);
+    }
+
+    #[test]
+    fn an_empty_note_removes_the_old_one() {
+        let store = MemoryStore::new();
+        let user = Thing::from(("user", "ann"));
+        let saved = block_on(save_note_with(&store, user.clone(), show("1", &[]), " loved it ".to_string(), "Cozy".to_string())).unwrap();
+        assert_eq!(saved.map(|n| (n.text, n.tags)), Some(("loved it".to_string(), vec!["cozy".to_string()])));
+        assert_eq!(block_on(store.library(&user)).unwrap().len(), 1);
+
+        assert_eq!(block_on(save_note_with(&store, user.clone(), show("1", &[]), " ".to_string(), ", #".to_string())).unwrap(), None);
+        assert!(block_on(store.notes(&user)).unwrap().is_empty());
+    }
+}
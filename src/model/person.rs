@@ -0,0 +1,202 @@
+// <------- People (cast & crew) as graph nodes linked to shows ------->
+// person:<tvmaze id> records, joined to video:<id> by "credited" edges.
+
+use crate::api::credits::fetch_credits;
+use crate::model::db::db_ready;
+use crate::model::error::GlassBoxError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use surrealdb::sql::Thing;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Person {
+    pub id: Option<Thing>,
+    pub person_id: String,
+    pub name: String,
+}
+
+// One person's role on one show, flattened from a credited edge
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Credit {
+    pub person_id: String,
+    pub name: String,
+    pub role: String, // "Cast", "Creator", ...
+    pub character: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreditRow {
+    video_id: String,
+    person_id: String,
+    name: String,
+    role: String,
+    character: Option<String>,
+}
+
+pub async fn save_credits(video_id: String, credits: Vec<Credit>) -> Result<(), GlassBoxError> {
+    let db = db_ready().await?;
+    let video = Thing::from(("video", video_id.as_str()));
+
+    // Replace the show's edges so a refetch doesn't duplicate them
+    db.query("DELETE credited WHERE in = $video")
+        .bind(("video", video.clone()))
+        .await?;
+
+    for credit in credits {
+        let person = Person {
+            id: None,
+            person_id: credit.person_id.clone(),
+            name: credit.name,
+        };
+        let _: Option<Person> = db
+            .upsert(("person", credit.person_id.as_str()))
+            .content(person)
+            .await?;
+
+        let sql = "RELATE $video->credited->$person SET role = $role, character = $character";
+        db.query(sql)
+            .bind(("video", video.clone()))
+            .bind(("person", Thing::from(("person", credit.person_id.as_str()))))
+            .bind(("role", credit.role))
+            .bind(("character", credit.character))
+            .await?;
+    }
+    Ok(())
+}
+
+// Cached credits for the given shows, keyed by video_id
+pub async fn get_credits(video_ids: &[String]) -> Result<HashMap<String, Vec<Credit>>, GlassBoxError> {
+    let db = db_ready().await?;
+
+    let things: Vec<Thing> = video_ids.iter().map(|id| Thing::from(("video", id.as_str()))).collect();
+    let sql = "SELECT meta::id(in) AS video_id, out.person_id AS person_id, out.name AS name, role, character
+               FROM credited WHERE in IN $videos";
+    let mut response = db.query(sql).bind(("videos", things)).await?;
+    let rows: Vec<CreditRow> = response.take(0)?;
+
+    let mut by_video: HashMap<String, Vec<Credit>> = HashMap::new();
+    for row in rows {
+        by_video.entry(row.video_id).or_default().push(Credit {
+            person_id: row.person_id,
+            name: row.name,
+            role: row.role,
+            character: row.character,
+        });
+    }
+    Ok(by_video)
+}
+
+// Cached credits, fetching (and storing) any show we have never seen
+pub async fn credits_for(video_ids: &[String]) -> Result<HashMap<String, Vec<Credit>>, GlassBoxError> {
+    let mut by_video = get_credits(video_ids).await?;
+
+    for id in video_ids {
+        if by_video.contains_key(id) {
+            continue;
+        }
+        match fetch_credits(id).await {
+            Ok(credits) => {
+                if let Err(e) = save_credits(id.clone(), credits.clone()).await {
+                    leptos::logging::error!("PERSON: Failed to cache credits for {}: {:?}", id, e);
+                }
+                by_video.insert(id.clone(), credits);
+            }
+            Err(e) => leptos::logging::error!("PERSON: Failed to fetch credits for {}: {:?}", id, e),
+        }
+    }
+    Ok(by_video)
+}
+
+// <------- How much the user's library leans on each person ------->
+#[derive(Debug, Default, Clone)]
+pub struct PeopleProfile {
+    weights: HashMap<String, f64>,
+    names: HashMap<String, String>,
+}
+
+impl PeopleProfile {
+    pub fn from_library(credits: &HashMap<String, Vec<Credit>>) -> Self {
+        let mut profile = Self::default();
+        for show_credits in credits.values() {
+            for c in show_credits {
+                *profile.weights.entry(c.person_id.clone()).or_insert(0.0) += 1.0;
+                profile.names.insert(c.person_id.clone(), c.name.clone());
+            }
+        }
+        profile
+    }
+
+    // Score in [0, 1] plus the shared people (most familiar first) for the explanation
+    pub fn score(&self, credits: &[Credit]) -> (f64, Vec<String>) {
+        // Same person in two roles (e.g. creator and cast) counts once
+        let unique: HashMap<&str, f64> = credits
+            .iter()
+            .filter_map(|c| self.weights.get(&c.person_id).map(|w| (c.person_id.as_str(), *w)))
+            .collect();
+        if unique.is_empty() {
+            return (0.0, Vec::new());
+        }
+        let mut shared: Vec<(&str, f64)> = unique.into_iter().collect();
+        // Ties by id, so the explanation doesn't reshuffle between renders
+        shared.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.0.cmp(b.0)));
+
+        // Diminishing returns: one shared creator matters, ten shared extras less so
+        let raw: f64 = shared.iter().map(|(_, w)| w).sum();
+        let score = 1.0 - (-raw / 3.0).exp();
+
+        let names = shared
+            .iter()
+            .filter_map(|(id, _)| self.names.get(*id).cloned())
+            .collect();
+        (score, names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credit(person_id: &str, name: &str, role: &str) -> Credit {
+        Credit {
+            person_id: person_id.to_string(),
+            name: name.to_string(),
+            role: role.to_string(),
+            character: None,
+        }
+    }
+
+    // Two shows by the same creator, each with its own lead
+    fn profile() -> PeopleProfile {
+        let mut library = HashMap::new();
+        library.insert("1".to_string(), vec![credit("10", "Vince", "Creator"), credit("20", "Bryan", "Cast")]);
+        library.insert("2".to_string(), vec![credit("10", "Vince", "Creator"), credit("30", "Rhea", "Cast")]);
+        PeopleProfile::from_library(&library)
+    }
+
+    #[test]
+    fn familiar_people_come_first() {
+        let (score, names) = profile().score(&[credit("30", "Rhea", "Cast"), credit("10", "Vince", "Creator")]);
+        assert_eq!(names, vec!["Vince", "Rhea"]);
+        assert!((score - (1.0 - (-3.0f64 / 3.0).exp())).abs() < 1e-9);
+    }
+
+    #[test]
+    fn strangers_score_nothing() {
+        assert_eq!(profile().score(&[credit("99", "Nobody", "Cast")]), (0.0, Vec::new()));
+        assert_eq!(profile().score(&[]), (0.0, Vec::new()));
+    }
+
+    #[test]
+    fn two_roles_count_once_and_more_people_saturate() {
+        let profile = profile();
+        let once = profile.score(&[credit("10", "Vince", "Creator")]);
+        let twice = profile.score(&[credit("10", "Vince", "Creator"), credit("10", "Vince", "Cast")]);
+        assert_eq!(once, twice);
+        assert_eq!(twice.1, vec!["Vince"]);
+
+        let all = profile.score(&[credit("10", "Vince", "Creator"), credit("20", "Bryan", "Cast"), credit("30", "Rhea", "Cast")]);
+        assert!(all.0 > once.0 && all.0 < 1.0);
+        // Ties are broken by id
+        assert_eq!(all.1, vec!["Vince", "Bryan", "Rhea"]);
+    }
+}
@@ -0,0 +1,252 @@
+// <------- What the user searches for ------->
+// Everything here is computed from search_history in the browser: suggestions
+// for the search box, the "frequent searches" on the profile page, and the
+// query terms the feed uses as a weak interest signal.
+
+use crate::model::config::RecommenderConfig;
+use crate::model::history::SearchEntry;
+use crate::model::search_index::tokenize;
+use crate::model::video::Video;
+use std::collections::{HashMap, HashSet};
+
+// Words that say nothing about taste
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "with", "from", "show", "shows", "series", "season", "episode", "tv", "new", "best", "like",
+];
+const MIN_TERM_LEN: usize = 3;
+// Only the most searched terms take part in ranking
+const PROFILE_TERMS: usize = 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryStat {
+    pub query: String, // as last typed
+    pub count: usize,
+    pub last_at: String,
+}
+
+// Searches are compared trimmed and case-insensitively
+fn key(query: &str) -> String {
+    query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+// Earlier searches for the search box, newest first. Blank input lists the most
+// recent ones; otherwise those starting with (then containing) what is typed.
+pub fn suggest_queries(searches: &[SearchEntry], typed: &str, limit: usize) -> Vec<String> {
+    let typed = key(typed);
+    let mut seen = HashSet::new();
+    let mut starts = Vec::new();
+    let mut contains = Vec::new();
+    // searches() is newest first
+    for entry in searches {
+        let k = key(&entry.query);
+        if k.is_empty() || k == typed || !seen.insert(k.clone()) {
+            continue;
+        }
+        if k.starts_with(&typed) {
+            starts.push(entry.query.trim().to_string());
+        } else if k.contains(&typed) {
+            contains.push(entry.query.trim().to_string());
+        }
+    }
+    starts.extend(contains);
+    starts.truncate(limit);
+    starts
+}
+
+// Each distinct search with how often it was run, most frequent first
+pub fn frequent_queries(searches: &[SearchEntry]) -> Vec<QueryStat> {
+    let mut stats: HashMap<String, QueryStat> = HashMap::new();
+    for entry in searches {
+        let k = key(&entry.query);
+        if k.is_empty() {
+            continue;
+        }
+        let stat = stats.entry(k).or_insert_with(|| QueryStat {
+            query: entry.query.trim().to_string(),
+            count: 0,
+            last_at: entry.timestamp.clone(),
+        });
+        stat.count += 1;
+        if entry.timestamp > stat.last_at {
+            stat.last_at = entry.timestamp.clone();
+            stat.query = entry.query.trim().to_string();
+        }
+    }
+    let mut stats: Vec<QueryStat> = stats.into_values().collect();
+    stats.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| b.last_at.cmp(&a.last_at)));
+    stats
+}
+
+// Words worth keeping from a query
+fn terms(query: &str) -> HashSet<String> {
+    tokenize(query)
+        .into_iter()
+        .filter(|t| t.chars().count() >= MIN_TERM_LEN && !STOP_WORDS.contains(&t.as_str()))
+        .collect()
+}
+
+// Every search term with how many searches used it, most used first
+pub fn top_terms(searches: &[SearchEntry]) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for entry in searches {
+        for term in terms(&entry.query) {
+            *counts.entry(term).or_default() += 1;
+        }
+    }
+    let mut ranked: Vec<(String, usize)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked
+}
+
+// <------- Query terms as an interest signal ------->
+
+// A show scores by the most searched term found in its title, genres or
+// description. It is weak on purpose: a search is curiosity, not a like.
+#[derive(Default)]
+pub struct QueryProfile {
+    terms: Vec<(String, f64)>, // term, share of the top term's count (0..=1)
+}
+
+impl QueryProfile {
+    pub fn from_searches(searches: &[SearchEntry]) -> Self {
+        let top = top_terms(searches);
+        let Some(max) = top.first().map(|(_, n)| *n as f64) else {
+            return Self::default();
+        };
+        let terms = top
+            .into_iter()
+            .take(PROFILE_TERMS)
+            .map(|(term, n)| (term, n as f64 / max))
+            .collect();
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    // The best matching term and its weight
+    fn best(&self, video: &Video) -> Option<(&str, f64)> {
+        let words: HashSet<String> = tokenize(&format!("{} {} {}", video.title, video.genres.join(" "), video.description))
+            .into_iter()
+            .collect();
+        self.terms
+            .iter()
+            .filter(|(term, _)| words.contains(term))
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(term, w)| (term.as_str(), *w))
+    }
+
+    // 0..=1
+    pub fn score(&self, video: &Video) -> f64 {
+        self.best(video).map(|(_, w)| w).unwrap_or(0.0)
+    }
+
+    // For the "why is this here" line
+    pub fn matched_term(&self, video: &Video) -> Option<&str> {
+        self.best(video).map(|(term, _)| term)
+    }
+}
+
+// What the feed adds to a candidate's genre match (0 = searches ignored)
+pub fn query_boost(profile: &QueryProfile, video: &Video, config: &RecommenderConfig) -> f64 {
+    if profile.is_empty() {
+        0.0
+    } else {
+        config.query_weight * profile.score(video)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use surrealdb::sql::Thing;
+
+    // Newest first, like searches()
+    fn log(queries: &[(&str, &str)]) -> Vec<SearchEntry> {
+        queries
+            .iter()
+            .map(|(query, day)| SearchEntry {
+                id: None,
+                user: Thing::from(("user", "ann")),
+                query: query.to_string(),
+                timestamp: format!("2025-01-{}T12:00:00+00:00", day),
+            })
+            .collect()
+    }
+
+    fn show(title: &str, genres: &[&str]) -> Video {
+        Video {
+            id: None,
+            video_id: title.to_lowercase(),
+            title: title.to_string(),
+            description: String::new(),
+            thumbnail_url: String::new(),
+            rating: 7.0,
+            genres: genres.iter().map(|g| g.to_string()).collect(),
+            channel_name: String::new(),
+            related_ids: Vec::new(),
+            saved_at: chrono::Utc::now(),
+        }
+    }
+
+    fn searches() -> Vec<SearchEntry> {
+        log(&[
+            ("Korean drama", "09"),
+            ("the office", "08"),
+            ("korean  DRAMA ", "07"),
+            ("heist thriller", "06"),
+            ("The Office", "05"),
+            ("korean drama", "04"),
+            ("  ", "03"),
+        ])
+    }
+
+    #[test]
+    fn suggestions_start_with_what_is_typed_then_contain_it() {
+        let searches = log(&[("drama korea", "09"), ("korean drama", "08"), ("Korean comedy", "07"), ("k-drama", "06")]);
+        assert_eq!(suggest_queries(&searches, "kor", 5), vec!["korean drama", "Korean comedy", "drama korea"]);
+        assert_eq!(suggest_queries(&searches, " DRAMA", 5), vec!["drama korea", "korean drama", "k-drama"]);
+        assert_eq!(suggest_queries(&searches, "kor", 1), vec!["korean drama"]);
+    }
+
+    #[test]
+    fn blank_input_lists_recent_distinct_searches_but_not_the_typed_one() {
+        assert_eq!(suggest_queries(&searches(), "", 10), vec!["Korean drama", "the office", "heist thriller"]);
+        assert_eq!(suggest_queries(&searches(), "The  Office", 10), Vec::<String>::new());
+    }
+
+    #[test]
+    fn frequent_queries_keep_the_latest_spelling() {
+        let stats = frequent_queries(&searches());
+        let summary: Vec<(&str, usize, &str)> = stats.iter().map(|s| (s.query.as_str(), s.count, &s.last_at[..10])).collect();
+        assert_eq!(
+            summary,
+            vec![("Korean drama", 3, "2025-01-09"), ("the office", 2, "2025-01-08"), ("heist thriller", 1, "2025-01-06")]
+        );
+    }
+
+    #[test]
+    fn stop_words_and_short_words_are_not_terms() {
+        let searches = log(&[("the best new tv show", "09"), ("a heist", "08"), ("Heist series", "07"), ("spy heist", "06")]);
+        assert_eq!(top_terms(&searches), vec![("heist".to_string(), 3), ("spy".to_string(), 1)]);
+    }
+
+    #[test]
+    fn shows_score_by_their_most_searched_term() {
+        let profile = QueryProfile::from_searches(&searches());
+        let korean = show("Korean Odyssey", &["Fantasy"]);
+        let drama = show("Mr. Sunshine", &["Drama"]);
+        let heist = show("Money Heist", &["Crime"]);
+        let other = show("Bluey", &["Children"]);
+
+        assert_eq!(profile.score(&korean), 1.0);
+        assert_eq!(profile.matched_term(&drama), Some("drama"));
+        assert!((profile.score(&heist) - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!((profile.score(&other), profile.matched_term(&other)), (0.0, None));
+
+        let config = RecommenderConfig { query_weight: 0.2, ..RecommenderConfig::default() };
+        assert!((query_boost(&profile, &korean, &config) - 0.2).abs() < 1e-9);
+        assert_eq!(query_boost(&QueryProfile::from_searches(&[]), &korean, &config), 0.0);
+    }
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+use crate::model::error::GlassBoxError;
+use crate::model::store::{Store, SurrealStore};
+use crate::model::video::Video;
+
+// An explicit score the user gave a show (clicks are only implicit signals)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Rating {
+    pub id: Option<Thing>,
+    pub user: Thing,
+    pub video_id: String,
+    pub video_title: String,
+    pub score: f64,     // 1.0 - 10.0, same scale as Video.rating
+    pub source: String, // "glassbox", "letterboxd", "imdb", "trakt"
+    pub timestamp: String,
+}
+
+pub async fn get_ratings(user_id: Thing) -> Result<Vec<Rating>, GlassBoxError> {
+    SurrealStore.ratings(&user_id).await
+}
+
+// The user's own score beats TVMaze's average when weighting the taste vector
+pub fn with_user_ratings(library: Vec<Video>, ratings: &[Rating]) -> Vec<Video> {
+    library
+        .into_iter()
+        .map(|v| match ratings.iter().find(|r| r.video_id == v.video_id) {
+            Some(r) => Video { rating: r.score, ..v },
+            None => v,
+        })
+        .collect()
+}
@@ -0,0 +1,375 @@
+// <------- Local full-text search ------->
+// An in-memory inverted index over what the user already has: library titles
+// and descriptions, their notes and tags, and past queries. It is rebuilt from
+// IndexedDB for each search (a library is hundreds of shows, not millions), so
+// it never goes stale and works with no network at all.
+
+use crate::model::error::GlassBoxError;
+use crate::model::history::{get_searches, get_user_library, SearchEntry};
+use crate::model::notes::{get_notes, TitleNote};
+use crate::model::text::edit_distance;
+use crate::model::video::{get_library, Video};
+use std::collections::HashMap;
+use surrealdb::sql::Thing;
+
+// How much a match in each field is worth
+const TITLE_WEIGHT: f64 = 3.0;
+const TAG_WEIGHT: f64 = 2.5;
+const NOTE_WEIGHT: f64 = 2.0;
+const DESCRIPTION_WEIGHT: f64 = 1.0;
+const QUERY_WEIGHT: f64 = 1.0;
+
+// How good a term match is
+const EXACT: f64 = 1.0;
+const PREFIX: f64 = 0.8;
+const FUZZY: f64 = 0.6;
+const FUZZY_PREFIX: f64 = 0.5;
+
+// Shorter words are too easy to "fuzz" into something unrelated
+const MIN_PREFIX_LEN: usize = 2;
+const MIN_FUZZY_LEN: usize = 4;
+
+const SHOW_LIMIT: usize = 10;
+const QUERY_LIMIT: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Title,
+    Tag,
+    Note,
+    Description,
+    Query,
+}
+
+impl Field {
+    fn weight(self) -> f64 {
+        match self {
+            Field::Title => TITLE_WEIGHT,
+            Field::Tag => TAG_WEIGHT,
+            Field::Note => NOTE_WEIGHT,
+            Field::Description => DESCRIPTION_WEIGHT,
+            Field::Query => QUERY_WEIGHT,
+        }
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
+enum Doc {
+    Show { video: Video, note: Option<TitleNote> },
+    Query(String),
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum LocalHit {
+    // `note` is the user's note and tags, shown under the description
+    Show { video: Video, note: Option<String> },
+    Query(String),
+}
+
+#[derive(Default)]
+pub struct SearchIndex {
+    docs: Vec<Doc>,
+    // term -> (doc, field) it appears in
+    postings: HashMap<String, Vec<(usize, Field)>>,
+}
+
+// Lowercase words; anything that isn't a letter or digit separates them
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_string())
+        .collect()
+}
+
+impl SearchIndex {
+    pub fn build(library: &[Video], notes: &[TitleNote], searches: &[SearchEntry]) -> Self {
+        let mut index = Self::default();
+
+        for video in library {
+            let note = notes.iter().find(|n| n.video_id == video.video_id).cloned();
+            let doc = index.docs.len();
+            index.add(doc, &video.title, Field::Title);
+            index.add(doc, &video.description, Field::Description);
+            if let Some(n) = &note {
+                index.add(doc, &n.text, Field::Note);
+                index.add(doc, &n.tags.join(" "), Field::Tag);
+            }
+            index.docs.push(Doc::Show { video: video.clone(), note });
+        }
+
+        // Same query typed twice is one suggestion
+        let mut seen: Vec<String> = Vec::new();
+        for entry in searches {
+            let key = entry.query.trim().to_lowercase();
+            if key.is_empty() || seen.contains(&key) {
+                continue;
+            }
+            seen.push(key);
+            let doc = index.docs.len();
+            index.add(doc, &entry.query, Field::Query);
+            index.docs.push(Doc::Query(entry.query.trim().to_string()));
+        }
+
+        index
+    }
+
+    fn add(&mut self, doc: usize, text: &str, field: Field) {
+        for term in tokenize(text) {
+            let postings = self.postings.entry(term).or_default();
+            if !postings.contains(&(doc, field)) {
+                postings.push((doc, field));
+            }
+        }
+    }
+
+    // Every word of the query has to match (exactly, as a prefix, or within a
+    // typo or two); "#word" only matches a tag. Shows come first, then past
+    // queries, each best first.
+    pub fn search(&self, query: &str) -> Vec<LocalHit> {
+        let words: Vec<&str> = query.split_whitespace().collect();
+        if words.is_empty() {
+            return Vec::new();
+        }
+
+        // doc -> summed score, and how many query words it matched
+        let mut scores: HashMap<usize, (f64, usize)> = HashMap::new();
+        let mut needed = 0;
+        for word in &words {
+            let (tag_only, word) = match word.strip_prefix('#') {
+                Some(rest) => (true, rest),
+                None => (false, *word),
+            };
+            let Some(term) = tokenize(word).into_iter().next() else {
+                continue;
+            };
+            needed += 1;
+
+            // Best score this word earns in each doc
+            let mut best: HashMap<usize, f64> = HashMap::new();
+            for (candidate, postings) in &self.postings {
+                let quality = if tag_only {
+                    if candidate == &term { EXACT } else { 0.0 }
+                } else {
+                    match_quality(&term, candidate)
+                };
+                if quality <= 0.0 {
+                    continue;
+                }
+                for (doc, field) in postings {
+                    if tag_only && *field != Field::Tag {
+                        continue;
+                    }
+                    let score = quality * field.weight();
+                    let entry = best.entry(*doc).or_insert(0.0);
+                    if score > *entry {
+                        *entry = score;
+                    }
+                }
+            }
+            for (doc, score) in best {
+                let entry = scores.entry(doc).or_insert((0.0, 0));
+                entry.0 += score;
+                entry.1 += 1;
+            }
+        }
+
+        let mut ranked: Vec<(usize, f64)> = scores
+            .into_iter()
+            .filter(|(_, (_, matched))| *matched == needed)
+            .map(|(doc, (score, _))| (doc, score))
+            .collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then(a.0.cmp(&b.0)));
+
+        let typed = query.trim().to_lowercase();
+        let mut shows = Vec::new();
+        let mut queries = Vec::new();
+        for (doc, _) in ranked {
+            match &self.docs[doc] {
+                Doc::Show { video, note } if shows.len() < SHOW_LIMIT => shows.push(LocalHit::Show {
+                    video: video.clone(),
+                    note: note.as_ref().map(note_line),
+                }),
+                // The query being run is not a suggestion for itself
+                Doc::Query(q) if queries.len() < QUERY_LIMIT && q.to_lowercase() != typed => queries.push(LocalHit::Query(q.clone())),
+                _ => {}
+            }
+        }
+        shows.extend(queries);
+        shows
+    }
+}
+
+fn note_line(note: &TitleNote) -> String {
+    let tags: Vec<String> = note.tags.iter().map(|t| format!("#{}", t)).collect();
+    format!("{} {}", note.text, tags.join(" ")).trim().to_string()
+}
+
+fn match_quality(word: &str, term: &str) -> f64 {
+    if word == term {
+        return EXACT;
+    }
+    let len = word.chars().count();
+    if len >= MIN_PREFIX_LEN && term.starts_with(word) {
+        return PREFIX;
+    }
+    if len < MIN_FUZZY_LEN {
+        return 0.0;
+    }
+    // One typo per four letters, at most two
+    let allowed = (len / MIN_FUZZY_LEN).min(2);
+    if edit_distance(word, term) <= allowed {
+        return FUZZY;
+    }
+    // Still typing, with a typo: compare against the start of the term
+    let head: String = term.chars().take(len).collect();
+    if term.chars().count() > len && edit_distance(word, &head) <= allowed {
+        return FUZZY_PREFIX;
+    }
+    0.0
+}
+
+// Signed in: this user's library, the shows they wrote notes on, and their
+// searches. Signed out there is nothing local to search.
+pub async fn build_index(user: Option<Thing>) -> Result<SearchIndex, GlassBoxError> {
+    let Some(user) = user else {
+        return Ok(SearchIndex::build(&[], &[], &[]));
+    };
+
+    let mut library = get_user_library(user.clone()).await?;
+    let notes = get_notes(user.clone()).await?;
+    let missing: Vec<&str> = notes
+        .iter()
+        .filter(|n| !library.iter().any(|v| v.video_id == n.video_id))
+        .map(|n| n.video_id.as_str())
+        .collect();
+    if !missing.is_empty() {
+        let all = get_library(user.clone()).await?;
+        library.extend(all.into_iter().filter(|v| missing.contains(&v.video_id.as_str())));
+    }
+    let searches = get_searches(user).await?;
+
+    Ok(SearchIndex::build(&library, &notes, &searches))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn show(id: &str, title: &str, description: &str) -> Video {
+        Video {
+            id: None,
+            video_id: id.to_string(),
+            title: title.to_string(),
+            description: description.to_string(),
+            thumbnail_url: String::new(),
+            rating: 7.0,
+            genres: Vec::new(),
+            channel_name: String::new(),
+            related_ids: Vec::new(),
+            saved_at: chrono::Utc::now(),
+        }
+    }
+
+    fn note(id: &str, text: &str, tags: &[&str]) -> TitleNote {
+        TitleNote {
+            id: None,
+            user: Thing::from(("user", "ann")),
+            video_id: id.to_string(),
+            video_title: String::new(),
+            text: text.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            timestamp: String::new(),
+        }
+    }
+
+    fn searched(query: &str) -> SearchEntry {
+        SearchEntry {
+            id: None,
+            user: Thing::from(("user", "ann")),
+            query: query.to_string(),
+            timestamp: String::new(),
+        }
+    }
+
+    fn index() -> SearchIndex {
+        let library = vec![
+            show("1", "Severance", "Office workers split their memories."),
+            show("2", "The Office", "A paper company documentary."),
+            show("3", "Cozy Kitchen", "Baking at home."),
+            show("4", "Bake Off", "Amateur bakers in a tent."),
+        ];
+        let notes = vec![note("4", "watch with mum", &["cozy", "rewatch"])];
+        let searches = vec![searched("office comedy"), searched("Office Comedy "), searched("severance season 2")];
+        SearchIndex::build(&library, &notes, &searches)
+    }
+
+    // Titles of the shows, then the suggested queries
+    fn hits(query: &str) -> Vec<String> {
+        index()
+            .search(query)
+            .into_iter()
+            .map(|hit| match hit {
+                LocalHit::Show { video, .. } => video.title,
+                LocalHit::Query(q) => format!("?{}", q),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn words_are_lowercased_and_split_on_punctuation() {
+        assert_eq!(tokenize("Mr. Robot's S01-E02"), vec!["mr", "robot", "s", "s01", "e02"]);
+        assert!(tokenize(" -- ").is_empty());
+    }
+
+    #[test]
+    fn exact_prefix_and_typo_matches_rank_in_that_order() {
+        assert_eq!(match_quality("severance", "severance"), EXACT);
+        assert_eq!(match_quality("sev", "severance"), PREFIX);
+        assert_eq!(match_quality("severence", "severance"), FUZZY);
+        assert_eq!(match_quality("sevr", "severance"), FUZZY_PREFIX);
+        // Two typos only from eight letters on
+        assert_eq!(match_quality("sevarence", "severance"), FUZZY);
+        assert_eq!(match_quality("bakr", "bake"), FUZZY);
+        assert_eq!(match_quality("bkr", "bake"), 0.0);
+    }
+
+    #[test]
+    fn short_words_need_to_be_exact_or_a_prefix() {
+        assert_eq!(match_quality("s", "severance"), 0.0);
+        assert_eq!(match_quality("teh", "the"), 0.0);
+        assert_eq!(match_quality("of", "office"), PREFIX);
+    }
+
+    #[test]
+    fn a_title_match_beats_a_description_match() {
+        assert_eq!(hits("office"), vec!["The Office", "Severance", "?office comedy"]);
+    }
+
+    #[test]
+    fn every_word_has_to_match_and_typos_are_forgiven() {
+        assert_eq!(hits("severence"), vec!["Severance", "?severance season 2"]);
+        assert_eq!(hits("office paper"), vec!["The Office"]);
+        assert!(hits("office tent").is_empty());
+        assert!(hits("   ").is_empty());
+    }
+
+    #[test]
+    fn a_hash_only_matches_tags() {
+        // A title counts more than a tag
+        assert_eq!(hits("cozy"), vec!["Cozy Kitchen", "Bake Off"]);
+        assert_eq!(hits("#cozy"), vec!["Bake Off"]);
+        assert!(hits("#coz").is_empty());
+        match &index().search("#rewatch")[0] {
+            LocalHit::Show { note, .. } => assert_eq!(note.as_deref(), Some("watch with mum #cozy #rewatch")),
+            other => panic!("expected a show, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn past_queries_are_deduplicated_and_never_suggest_themselves() {
+        assert_eq!(hits("comedy"), vec!["?office comedy"]);
+        assert!(hits("office comedy").is_empty());
+    }
+}
@@ -1,10 +1,26 @@
 use leptos::prelude::*;
 use crate::model::users::User;
+use crate::model::vault;
+use surrealdb::sql::Thing;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Session {
     pub user_id: Option<String>, // Serialized SurrealDB Thing
     pub username: Option<String>,
+    // False after a reload: the session is remembered, the vault key is not
+    pub unlocked: bool,
+}
+
+impl Session {
+    // The signed-in user as a record id, if the stored one parses
+    pub fn user_thing(&self) -> Option<Thing> {
+        self.user_id.as_deref().and_then(|uid| surrealdb::sql::thing(uid).ok())
+    }
+
+    // Signed in, but nothing sealed can be read until the password is entered again
+    pub fn is_locked(&self) -> bool {
+        self.user_id.is_some() && !self.unlocked
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -15,6 +31,7 @@ impl SessionState {
         let (get, set) = signal(Session {
             user_id: None,
             username: None,
+            unlocked: false,
         });
 
         // Try to load from LocalStorage on startup
@@ -24,7 +41,7 @@ impl SessionState {
                  let uid = storage.get_item("glassbox_userid").unwrap_or(None);
                  
                  if let (Some(username), Some(userid)) = (u, uid) {
-                     set.set(Session { user_id: Some(userid), username: Some(username) });
+                     set.set(Session { user_id: Some(userid), username: Some(username), unlocked: false });
                  }
              }
         }
@@ -32,13 +49,23 @@ impl SessionState {
         Self(set, get)
     }
 
+    pub fn user_thing(&self) -> Option<Thing> {
+        self.1.get().user_thing()
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.1.get().is_locked()
+    }
+
     pub fn login(&self, user: User) {
         // Extract ID. If None, we can't track history, but allow login?
         // SurrealDB users should always have ID.
         let uid_str = user.id.map(|t| t.to_string());
 
         // Save to Signal
+        // Called once vault::unlock succeeded, so the key is in memory
         (self.0).set(Session {
+            unlocked: uid_str.as_deref().is_some_and(vault::is_unlocked),
             user_id: uid_str.clone(),
             username: Some(user.username.clone()),
         });
@@ -55,22 +82,42 @@ impl SessionState {
     }
 
     pub fn logout(&self) {
+        self.clear();
+
+        if let Some(window) = web_sys::window() {
+            let _ = window.location().set_href("/");
+        }
+    }
+
+    // Signed out without leaving the page (account deletion shows its report first)
+    pub fn clear(&self) {
+        // Drop the encryption keys before anything else
+        vault::lock();
+
         // Clear Signal
         (self.0).set(Session {
             user_id: None,
             username: None,
+            unlocked: false,
         });
 
-        // Clear LocalStorage
-        if let Some(window) = web_sys::window() {
-            if let Ok(Some(storage)) = window.local_storage() {
-                let _ = storage.remove_item("glassbox_username");
-                let _ = storage.remove_item("glassbox_userid");
-            }
-        }
-        
-        if let Some(window) = web_sys::window() {
-            let _ = window.location().set_href("/");
+        clear_stored_session();
+    }
+}
+
+// Forget the remembered sign-in (also used by a backup restore, which may
+// remove the account it points at)
+pub fn clear_stored_session() {
+    if let Some(window) = web_sys::window() {
+        if let Ok(Some(storage)) = window.local_storage() {
+            let _ = storage.remove_item("glassbox_username");
+            let _ = storage.remove_item("glassbox_userid");
         }
     }
 }
+
+impl Default for SessionState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
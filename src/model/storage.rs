@@ -0,0 +1,130 @@
+// <------- Browser storage: persistence and quota ------->
+// Without persistent storage the browser may evict IndexedDB under storage
+// pressure, taking every account with it. We ask for persistence once the vault
+// opens and report usage against the quota, so the user can export in time.
+
+use crate::model::error::GlassBoxError;
+use js_sys::Reflect;
+use leptos::prelude::*;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::StorageManager;
+
+// Share of the quota above which the user is asked to export
+pub const NEAR_QUOTA: f64 = 0.8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StorageStatus {
+    pub persisted: bool,
+    pub usage: f64, // bytes
+    pub quota: f64, // bytes
+}
+
+impl StorageStatus {
+    // 0..=1 (0 when the browser reports no quota)
+    pub fn fraction(&self) -> f64 {
+        if self.quota > 0.0 {
+            (self.usage / self.quota).min(1.0)
+        } else {
+            0.0
+        }
+    }
+
+    pub fn near_quota(&self) -> bool {
+        self.fraction() >= NEAR_QUOTA
+    }
+}
+
+fn manager() -> Result<StorageManager, GlassBoxError> {
+    let window = web_sys::window().ok_or(GlassBoxError::Browser("no window".to_string()))?;
+    Ok(window.navigator().storage())
+}
+
+async fn persisted(manager: &StorageManager) -> Result<bool, GlassBoxError> {
+    Ok(JsFuture::from(manager.persisted()?).await?.as_bool().unwrap_or(false))
+}
+
+// Set once the startup persistence request has been answered, so nothing
+// reports `persisted` before the browser has decided
+#[derive(Clone, Copy, Debug)]
+pub struct PersistenceAsked(pub RwSignal<bool>);
+
+// True if storage is (now) persistent. Browsers may ask the user, or decide on
+// their own from how the site is used; false means eviction is still possible.
+pub async fn request_persistence() -> Result<bool, GlassBoxError> {
+    let manager = manager()?;
+    if persisted(&manager).await? {
+        return Ok(true);
+    }
+    let granted = JsFuture::from(manager.persist()?).await?.as_bool().unwrap_or(false);
+    if !granted {
+        leptos::logging::log!("STORAGE: Persistence denied, the browser may evict the vault");
+    }
+    Ok(granted)
+}
+
+pub async fn storage_status() -> Result<StorageStatus, GlassBoxError> {
+    let manager = manager()?;
+    let estimate = JsFuture::from(manager.estimate()?).await?;
+    let field = |name: &str| Reflect::get(&estimate, &JsValue::from_str(name)).ok().and_then(|v| v.as_f64()).unwrap_or(0.0);
+    Ok(StorageStatus {
+        persisted: persisted(&manager).await?,
+        usage: field("usage"),
+        quota: field("quota"),
+    })
+}
+
+// 1536 -> "1.5 KB"
+pub fn format_bytes(bytes: f64) -> String {
+    let units = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes.max(0.0);
+    let mut unit = 0;
+    // Just under a unit rounds up into it rather than printing "1024.0 KB"
+    while unit < units.len() - 1 && (value >= 1024.0 || (unit > 0 && value >= 1023.95)) {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", value as u64, units[unit])
+    } else {
+        format!("{:.1} {}", value, units[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(usage: f64, quota: f64) -> StorageStatus {
+        StorageStatus {
+            persisted: false,
+            usage,
+            quota,
+        }
+    }
+
+    #[test]
+    fn near_quota_from_eighty_percent() {
+        assert!(!status(79.0, 100.0).near_quota());
+        assert!(status(80.0, 100.0).near_quota());
+        // Over the quota still reads as full, and no quota as empty
+        assert_eq!(status(150.0, 100.0).fraction(), 1.0);
+        assert!(!status(5000.0, 0.0).near_quota());
+    }
+
+    #[test]
+    fn bytes_are_shown_in_the_largest_whole_unit() {
+        assert_eq!(format_bytes(0.0), "0 B");
+        assert_eq!(format_bytes(1023.0), "1023 B");
+        assert_eq!(format_bytes(1536.0), "1.5 KB");
+        assert_eq!(format_bytes(5.0 * 1024.0 * 1024.0 * 1024.0), "5.0 GB");
+        assert_eq!(format_bytes(1024.0_f64.powi(5)), "1024.0 TB");
+    }
+
+    #[test]
+    fn odd_inputs_format_sanely() {
+        assert_eq!(format_bytes(-12.0), "0 B");
+        assert_eq!(format_bytes(f64::NAN), "0 B");
+        assert_eq!(format_bytes(1024.0 * 1024.0 - 1.0), "1.0 MB");
+    }
+}
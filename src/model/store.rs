@@ -0,0 +1,1103 @@
+// <------- Storage abstraction ------->
+// Every table the app owns goes through `Store`. SurrealStore is the real
+// IndexedDB-backed vault; MemoryStore keeps everything in RAM so model logic
+// (login, signup, history, feed) can run natively without a browser.
+
+use crate::model::config::UserSettings;
+use crate::model::db::{db_ready, SafeSurreal};
+use crate::model::episodes::EpisodeProgress;
+use crate::model::error::GlassBoxError;
+use crate::model::history::{InteractionEntry, SearchEntry};
+use crate::model::lists::UserList;
+use crate::model::notes::TitleNote;
+use crate::model::ratings::Rating;
+use crate::model::users::{DeletionReport, User};
+use crate::model::vault::{
+    new_record_id, open_interactions, open_library, open_lists, open_notes, open_progress, open_ratings, open_searches, open_watchlist,
+    row_id, seal_interaction, seal_library_item, seal_list, seal_note, seal_progress, seal_rating, seal_search, seal_watch,
+};
+use crate::model::video::{LibraryItem, Video};
+use crate::model::watchlist::WatchEntry;
+use std::cell::RefCell;
+use surrealdb::sql::Thing;
+
+// WASM is single-threaded, so none of these futures need to be Send
+#[allow(async_fn_in_trait)]
+pub trait Store {
+    // --- users ---
+    async fn find_user_by_username(&self, username: &str) -> Result<Option<User>, GlassBoxError>;
+    async fn find_user_by_email(&self, email: &str) -> Result<Option<User>, GlassBoxError>;
+    async fn find_user(&self, id: &Thing) -> Result<Option<User>, GlassBoxError>;
+    async fn create_user(&self, user: User) -> Result<User, GlassBoxError>;
+    async fn delete_user(&self, id: &Thing) -> Result<(), GlassBoxError>;
+
+    // --- videos (each account's own library) ---
+    // Newest first
+    async fn library(&self, user: &Thing) -> Result<Vec<Video>, GlassBoxError>;
+    // One per (user, show): replaces the saved copy
+    async fn save_video(&self, user: &Thing, video: Video) -> Result<Video, GlassBoxError>;
+    async fn delete_library(&self, user: &Thing) -> Result<(), GlassBoxError>;
+
+    // --- interactions ---
+    async fn add_interaction(&self, entry: InteractionEntry) -> Result<(), GlassBoxError>;
+    // Rewrites an existing row (a run that grew, see history.rs)
+    async fn update_interaction(&self, entry: InteractionEntry) -> Result<(), GlassBoxError>;
+    // Oldest first, every local account
+    async fn interactions(&self) -> Result<Vec<InteractionEntry>, GlassBoxError>;
+    // Oldest first, only this user's rows (nobody else's are read or opened)
+    async fn user_interactions(&self, user: &Thing) -> Result<Vec<InteractionEntry>, GlassBoxError>;
+    // This user's newest run, without reading the rest of the log
+    async fn latest_interaction(&self, user: &Thing) -> Result<Option<InteractionEntry>, GlassBoxError>;
+    async fn delete_interactions(&self, user: &Thing) -> Result<(), GlassBoxError>;
+    async fn delete_interaction(&self, id: &Thing) -> Result<(), GlassBoxError>;
+    // All-or-nothing cleanup: every interaction or search in `delete` goes and
+    // every interaction in `update` is rewritten (compaction, retention)
+    async fn prune_history(&self, delete: Vec<Thing>, update: Vec<InteractionEntry>) -> Result<(), GlassBoxError>;
+
+    // --- searches ---
+    async fn add_search(&self, entry: SearchEntry) -> Result<(), GlassBoxError>;
+    async fn searches(&self, user: &Thing) -> Result<Vec<SearchEntry>, GlassBoxError>;
+    async fn delete_searches(&self, user: &Thing) -> Result<(), GlassBoxError>;
+    async fn delete_search(&self, id: &Thing) -> Result<(), GlassBoxError>;
+
+    // --- settings ---
+    async fn load_settings(&self, user: &Thing) -> Result<Option<UserSettings>, GlassBoxError>;
+    async fn save_settings(&self, settings: UserSettings) -> Result<UserSettings, GlassBoxError>;
+    async fn delete_settings(&self, user: &Thing) -> Result<(), GlassBoxError>;
+
+    // --- ratings ---
+    async fn save_rating(&self, rating: Rating) -> Result<Rating, GlassBoxError>;
+    // Newest first
+    async fn ratings(&self, user: &Thing) -> Result<Vec<Rating>, GlassBoxError>;
+    async fn delete_ratings(&self, user: &Thing) -> Result<(), GlassBoxError>;
+
+    // --- watch states ---
+    async fn save_watch(&self, entry: WatchEntry) -> Result<WatchEntry, GlassBoxError>;
+    // Newest first
+    async fn watchlist(&self, user: &Thing) -> Result<Vec<WatchEntry>, GlassBoxError>;
+    async fn delete_watch(&self, id: &Thing) -> Result<(), GlassBoxError>;
+    async fn delete_watchlist(&self, user: &Thing) -> Result<(), GlassBoxError>;
+
+    // --- episode progress ---
+    async fn save_progress(&self, progress: EpisodeProgress) -> Result<EpisodeProgress, GlassBoxError>;
+    // Newest first
+    async fn progress(&self, user: &Thing) -> Result<Vec<EpisodeProgress>, GlassBoxError>;
+    async fn delete_progress(&self, user: &Thing) -> Result<(), GlassBoxError>;
+
+    // --- lists ---
+    // Creates the list when it has no id yet
+    async fn save_list(&self, list: UserList) -> Result<UserList, GlassBoxError>;
+    // Oldest first (the order they were created in)
+    async fn lists(&self, user: &Thing) -> Result<Vec<UserList>, GlassBoxError>;
+    async fn delete_list(&self, id: &Thing) -> Result<(), GlassBoxError>;
+    async fn delete_lists(&self, user: &Thing) -> Result<(), GlassBoxError>;
+
+    // --- notes and tags ---
+    // One per (user, show): replaces the existing note
+    async fn save_note(&self, note: TitleNote) -> Result<TitleNote, GlassBoxError>;
+    // Newest first
+    async fn notes(&self, user: &Thing) -> Result<Vec<TitleNote>, GlassBoxError>;
+    async fn delete_note(&self, id: &Thing) -> Result<(), GlassBoxError>;
+    async fn delete_notes(&self, user: &Thing) -> Result<(), GlassBoxError>;
+
+    // --- whole history ---
+    // Swaps everything `user` owns for `history` in one go: either all of it
+    // lands or the old rows stay (archive import in Replace mode)
+    async fn replace_history(&self, user: &Thing, history: UserHistory) -> Result<(), GlassBoxError>;
+    // Adds `history` to what `user` already owns in one go, rows keyed by show
+    // overwriting the local ones (archive import in Merge mode)
+    async fn merge_history(&self, user: &Thing, history: UserHistory) -> Result<(), GlassBoxError>;
+    // Removes the account and every row it owns in one go; the report counts
+    // the rows that were actually removed (username left blank)
+    async fn delete_account(&self, user: &Thing) -> Result<DeletionReport, GlassBoxError>;
+}
+
+// Every row one account owns apart from the user record itself
+#[derive(Debug, Clone, Default)]
+pub struct UserHistory {
+    pub interactions: Vec<InteractionEntry>,
+    pub searches: Vec<SearchEntry>,
+    pub ratings: Vec<Rating>,
+    pub watchlist: Vec<WatchEntry>,
+    pub progress: Vec<EpisodeProgress>,
+    pub lists: Vec<UserList>,
+    pub notes: Vec<TitleNote>,
+    pub library: Vec<Video>,
+    pub settings: Option<UserSettings>,
+}
+
+// <------- IndexedDB (SurrealDB) backend ------->
+// History, searches, ratings, watch states, episode progress, lists, notes and library items are sealed on the way in and
+// opened on the way out (see vault.rs), so callers only ever see plaintext.
+pub struct SurrealStore;
+
+impl SurrealStore {
+    // Waits for init_db instead of failing if called during startup
+    async fn db() -> Result<&'static SafeSurreal, GlassBoxError> {
+        db_ready().await
+    }
+
+    // This user's library rows, opened, newest first
+    async fn library_rows(&self, user: &Thing) -> Result<Vec<(Thing, Video)>, GlassBoxError> {
+        let db = Self::db().await?;
+        let sql = "SELECT * FROM library_item WHERE user = $user ORDER BY saved_at DESC";
+        let mut response = db.query(sql).bind(("user", user.clone())).await?;
+        let mut rows = Vec::new();
+        for item in open_library(response.take(0)?).await {
+            match (item.id.clone(), item.to_video()) {
+                (Some(id), Ok(video)) => rows.push((id, video)),
+                (None, _) => leptos::logging::error!("STORE: Library item without id"),
+                (_, Err(e)) => leptos::logging::error!("STORE: Unreadable library item: {:?}", e),
+            }
+        }
+        Ok(rows)
+    }
+
+    // One transaction for replace_history / merge_history; `replace` first
+    // deletes every row the user owns
+    async fn write_history(&self, user: &Thing, history: UserHistory, replace: bool) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+
+        // Sealed up front: a locked vault or a row that won't seal fails with nothing deleted
+        let mut interactions = Vec::with_capacity(history.interactions.len());
+        for entry in history.interactions {
+            interactions.push(seal_interaction(InteractionEntry { id: Some(new_record_id("interaction")?), ..entry }).await?);
+        }
+        let mut searches = Vec::with_capacity(history.searches.len());
+        for entry in history.searches {
+            searches.push(seal_search(SearchEntry { id: Some(new_record_id("search_history")?), ..entry }).await?);
+        }
+        let mut ratings = Vec::with_capacity(history.ratings.len());
+        for rating in history.ratings {
+            let id = row_id("rating", user, &rating.video_id).await?;
+            ratings.push(seal_rating(Rating { id: Some(id), ..rating }).await?);
+        }
+        let mut watchlist = Vec::with_capacity(history.watchlist.len());
+        for entry in history.watchlist {
+            let id = row_id("watchlist", user, &entry.video_id).await?;
+            watchlist.push(seal_watch(WatchEntry { id: Some(id), ..entry }).await?);
+        }
+        let mut progress = Vec::with_capacity(history.progress.len());
+        for row in history.progress {
+            let id = row_id("episode_progress", user, &row.video_id).await?;
+            progress.push(seal_progress(EpisodeProgress { id: Some(id), ..row }).await?);
+        }
+        let mut lists = Vec::with_capacity(history.lists.len());
+        for list in history.lists {
+            lists.push(seal_list(UserList { id: Some(new_record_id("user_list")?), ..list }).await?);
+        }
+        let mut notes = Vec::with_capacity(history.notes.len());
+        for note in history.notes {
+            let id = row_id("title_note", user, &note.video_id).await?;
+            notes.push(seal_note(TitleNote { id: Some(id), ..note }).await?);
+        }
+        let mut library = Vec::with_capacity(history.library.len());
+        for video in &history.library {
+            let id = row_id("library_item", user, &video.video_id).await?;
+            library.push(seal_library_item(LibraryItem::new(id, user.clone(), video)?).await?);
+        }
+        let settings_sql = match (history.settings.is_some(), replace) {
+            (true, true) => "DELETE settings WHERE user = $user; UPSERT $settings_id CONTENT $settings;",
+            (true, false) => "UPSERT $settings_id CONTENT $settings;",
+            (false, _) => "",
+        };
+        let delete_sql = if replace {
+            "DELETE interaction WHERE user = $user; DELETE search_history WHERE user = $user;
+             DELETE rating WHERE user = $user; DELETE watchlist WHERE user = $user;
+             DELETE episode_progress WHERE user = $user; DELETE user_list WHERE user = $user;
+             DELETE title_note WHERE user = $user; DELETE library_item WHERE user = $user;"
+        } else {
+            ""
+        };
+
+        // Rows keyed by show are upserted: a show listed twice lands on one row
+        let sql = format!(
+            "BEGIN TRANSACTION;
+             {}
+             FOR $row IN $interactions {{ CREATE $row.id CONTENT $row; }};
+             FOR $row IN $searches {{ CREATE $row.id CONTENT $row; }};
+             FOR $row IN $ratings {{ UPSERT $row.id CONTENT $row; }};
+             FOR $row IN $watchlist {{ UPSERT $row.id CONTENT $row; }};
+             FOR $row IN $progress {{ UPSERT $row.id CONTENT $row; }};
+             FOR $row IN $lists {{ CREATE $row.id CONTENT $row; }};
+             FOR $row IN $notes {{ UPSERT $row.id CONTENT $row; }};
+             FOR $row IN $library {{ UPSERT $row.id CONTENT $row; }};
+             {}
+             COMMIT TRANSACTION;",
+            delete_sql, settings_sql
+        );
+        db.query(sql)
+            .bind(("user", user.clone()))
+            .bind(("interactions", interactions))
+            .bind(("searches", searches))
+            .bind(("ratings", ratings))
+            .bind(("watchlist", watchlist))
+            .bind(("progress", progress))
+            .bind(("lists", lists))
+            .bind(("notes", notes))
+            .bind(("library", library))
+            .bind(("settings_id", Thing::from(("settings", user.id.to_raw().as_str()))))
+            .bind(("settings", history.settings))
+            .await?
+            .check()?;
+        Ok(())
+    }
+}
+
+impl Store for SurrealStore {
+    async fn find_user_by_username(&self, username: &str) -> Result<Option<User>, GlassBoxError> {
+        let db = Self::db().await?;
+        let sql = "SELECT * FROM user WHERE username = $username";
+        let mut response = db.query(sql).bind(("username", username.to_string())).await?;
+
+        // SELECT returns a list, so we deserialize into Vec<User>
+        let users: Vec<User> = response.take(0)?;
+        Ok(users.into_iter().next())
+    }
+
+    async fn find_user_by_email(&self, email: &str) -> Result<Option<User>, GlassBoxError> {
+        let db = Self::db().await?;
+        let sql = "SELECT * FROM user WHERE email = $email";
+        let mut response = db.query(sql).bind(("email", email.to_string())).await?;
+
+        let users: Vec<User> = response.take(0)?;
+        Ok(users.into_iter().next())
+    }
+
+    async fn find_user(&self, id: &Thing) -> Result<Option<User>, GlassBoxError> {
+        let db = Self::db().await?;
+        Ok(db.select(("user", id.id.to_raw())).await?)
+    }
+
+    async fn create_user(&self, user: User) -> Result<User, GlassBoxError> {
+        let db = Self::db().await?;
+        // We use "create" to ensure we get a return value.
+        let created: Option<User> = db.create("user").content(user).await.map_err(|e| {
+            // user_username / user_email UNIQUE indexes (see migrations.rs)
+            if e.to_string().contains("already contains") {
+                GlassBoxError::Validation("Username or email is already taken".to_string())
+            } else {
+                GlassBoxError::from(e)
+            }
+        })?;
+        created.ok_or(GlassBoxError::Database("create returned no user".to_string()))
+    }
+
+    async fn delete_user(&self, id: &Thing) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        db.query("DELETE $id").bind(("id", id.clone())).await?.check()?;
+        Ok(())
+    }
+
+    async fn library(&self, user: &Thing) -> Result<Vec<Video>, GlassBoxError> {
+        Ok(self.library_rows(user).await?.into_iter().map(|(_, video)| video).collect())
+    }
+
+    async fn save_video(&self, user: &Thing, video: Video) -> Result<Video, GlassBoxError> {
+        let db = Self::db().await?;
+        // Derived from the show, so the saved copy (if any) is overwritten in place
+        let id = row_id("library_item", user, &video.video_id).await?;
+        let video = Video { id: None, ..video };
+        let sealed = seal_library_item(LibraryItem::new(id.clone(), user.clone(), &video)?).await?;
+        let _: Option<LibraryItem> = db.upsert(("library_item", id.id.to_raw())).content(sealed).await?;
+        Ok(video)
+    }
+
+    async fn delete_library(&self, user: &Thing) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        db.query("DELETE library_item WHERE user = $user").bind(("user", user.clone())).await?.check()?;
+        Ok(())
+    }
+
+    async fn add_interaction(&self, entry: InteractionEntry) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        let entry = seal_interaction(entry).await?;
+        let _: Option<InteractionEntry> = db.create("interaction").content(entry).await?;
+        Ok(())
+    }
+
+    async fn update_interaction(&self, entry: InteractionEntry) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        let id = entry.id.clone().ok_or(GlassBoxError::Database("interaction without id".to_string()))?;
+        let sealed = seal_interaction(entry).await?;
+        let _: Option<InteractionEntry> = db.upsert(("interaction", id.id.to_raw())).content(sealed).await?;
+        Ok(())
+    }
+
+    async fn interactions(&self) -> Result<Vec<InteractionEntry>, GlassBoxError> {
+        let db = Self::db().await?;
+        let sql = "SELECT * FROM interaction ORDER BY timestamp ASC";
+        let mut response = db.query(sql).await?;
+        Ok(open_interactions(response.take(0)?).await)
+    }
+
+    async fn user_interactions(&self, user: &Thing) -> Result<Vec<InteractionEntry>, GlassBoxError> {
+        let db = Self::db().await?;
+        // Served by the interaction_user index
+        let sql = "SELECT * FROM interaction WHERE user = $user ORDER BY timestamp ASC";
+        let mut response = db.query(sql).bind(("user", user.clone())).await?;
+        Ok(open_interactions(response.take(0)?).await)
+    }
+
+    async fn latest_interaction(&self, user: &Thing) -> Result<Option<InteractionEntry>, GlassBoxError> {
+        let db = Self::db().await?;
+        // Served by the interaction_user index
+        let sql = "SELECT * FROM interaction WHERE user = $user ORDER BY timestamp DESC LIMIT 1";
+        let mut response = db.query(sql).bind(("user", user.clone())).await?;
+        Ok(open_interactions(response.take(0)?).await.into_iter().next())
+    }
+
+    async fn delete_interactions(&self, user: &Thing) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        db.query("DELETE interaction WHERE user = $user").bind(("user", user.clone())).await?.check()?;
+        Ok(())
+    }
+
+    async fn delete_interaction(&self, id: &Thing) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        db.query("DELETE $id").bind(("id", id.clone())).await?.check()?;
+        Ok(())
+    }
+
+    async fn prune_history(&self, delete: Vec<Thing>, update: Vec<InteractionEntry>) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        let mut sealed = Vec::with_capacity(update.len());
+        for entry in update {
+            if entry.id.is_none() {
+                return Err(GlassBoxError::Database("interaction without id".to_string()));
+            }
+            sealed.push(seal_interaction(entry).await?);
+        }
+        db.query(
+            "BEGIN TRANSACTION;
+             FOR $id IN $delete { DELETE $id; };
+             FOR $row IN $update { UPSERT $row.id CONTENT $row; };
+             COMMIT TRANSACTION;",
+        )
+        .bind(("delete", delete))
+        .bind(("update", sealed))
+        .await?
+        .check()?;
+        Ok(())
+    }
+
+    async fn add_search(&self, entry: SearchEntry) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        let entry = seal_search(entry).await?;
+        let _: Option<SearchEntry> = db.create("search_history").content(entry).await?;
+        Ok(())
+    }
+
+    async fn searches(&self, user: &Thing) -> Result<Vec<SearchEntry>, GlassBoxError> {
+        let db = Self::db().await?;
+        let sql = "SELECT * FROM search_history WHERE user = $user ORDER BY timestamp DESC";
+        let mut response = db.query(sql).bind(("user", user.clone())).await?;
+        Ok(open_searches(response.take(0)?).await)
+    }
+
+    async fn delete_searches(&self, user: &Thing) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        db.query("DELETE search_history WHERE user = $user").bind(("user", user.clone())).await?.check()?;
+        Ok(())
+    }
+
+    async fn delete_search(&self, id: &Thing) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        db.query("DELETE $id").bind(("id", id.clone())).await?.check()?;
+        Ok(())
+    }
+
+    async fn load_settings(&self, user: &Thing) -> Result<Option<UserSettings>, GlassBoxError> {
+        let db = Self::db().await?;
+        Ok(db.select(("settings", user.id.to_raw())).await?)
+    }
+
+    async fn save_settings(&self, settings: UserSettings) -> Result<UserSettings, GlassBoxError> {
+        let db = Self::db().await?;
+        let key = settings.user.id.to_raw();
+        let saved: Option<UserSettings> = db.upsert(("settings", key)).content(settings).await?;
+        saved.ok_or(GlassBoxError::Database("upsert returned no settings".to_string()))
+    }
+
+    async fn delete_settings(&self, user: &Thing) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        db.query("DELETE settings WHERE user = $user").bind(("user", user.clone())).await?.check()?;
+        Ok(())
+    }
+
+    async fn save_rating(&self, rating: Rating) -> Result<Rating, GlassBoxError> {
+        let db = Self::db().await?;
+        // Sealed video IDs differ on every write, but the record ID is derived from the show
+        let id = row_id("rating", &rating.user, &rating.video_id).await?;
+        let plain = rating.clone();
+        let sealed = seal_rating(Rating { id: Some(id.clone()), ..rating }).await?;
+        let _: Option<Rating> = db.upsert(("rating", id.id.to_raw())).content(sealed).await?;
+        Ok(Rating { id: Some(id), ..plain })
+    }
+
+    async fn ratings(&self, user: &Thing) -> Result<Vec<Rating>, GlassBoxError> {
+        let db = Self::db().await?;
+        let sql = "SELECT * FROM rating WHERE user = $user ORDER BY timestamp DESC";
+        let mut response = db.query(sql).bind(("user", user.clone())).await?;
+        Ok(open_ratings(response.take(0)?).await)
+    }
+
+    async fn delete_ratings(&self, user: &Thing) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        db.query("DELETE rating WHERE user = $user").bind(("user", user.clone())).await?.check()?;
+        Ok(())
+    }
+
+    async fn save_watch(&self, entry: WatchEntry) -> Result<WatchEntry, GlassBoxError> {
+        let db = Self::db().await?;
+        let id = row_id("watchlist", &entry.user, &entry.video_id).await?;
+        let plain = entry.clone();
+        let sealed = seal_watch(WatchEntry { id: Some(id.clone()), ..entry }).await?;
+        let _: Option<WatchEntry> = db.upsert(("watchlist", id.id.to_raw())).content(sealed).await?;
+        Ok(WatchEntry { id: Some(id), ..plain })
+    }
+
+    async fn watchlist(&self, user: &Thing) -> Result<Vec<WatchEntry>, GlassBoxError> {
+        let db = Self::db().await?;
+        let sql = "SELECT * FROM watchlist WHERE user = $user ORDER BY timestamp DESC";
+        let mut response = db.query(sql).bind(("user", user.clone())).await?;
+        Ok(open_watchlist(response.take(0)?).await)
+    }
+
+    async fn delete_watch(&self, id: &Thing) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        db.query("DELETE $id").bind(("id", id.clone())).await?.check()?;
+        Ok(())
+    }
+
+    async fn delete_watchlist(&self, user: &Thing) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        db.query("DELETE watchlist WHERE user = $user").bind(("user", user.clone())).await?.check()?;
+        Ok(())
+    }
+
+    async fn save_progress(&self, progress: EpisodeProgress) -> Result<EpisodeProgress, GlassBoxError> {
+        let db = Self::db().await?;
+        let id = row_id("episode_progress", &progress.user, &progress.video_id).await?;
+        let plain = progress.clone();
+        let sealed = seal_progress(EpisodeProgress { id: Some(id.clone()), ..progress }).await?;
+        let _: Option<EpisodeProgress> = db.upsert(("episode_progress", id.id.to_raw())).content(sealed).await?;
+        Ok(EpisodeProgress { id: Some(id), ..plain })
+    }
+
+    async fn progress(&self, user: &Thing) -> Result<Vec<EpisodeProgress>, GlassBoxError> {
+        let db = Self::db().await?;
+        let sql = "SELECT * FROM episode_progress WHERE user = $user ORDER BY timestamp DESC";
+        let mut response = db.query(sql).bind(("user", user.clone())).await?;
+        Ok(open_progress(response.take(0)?).await)
+    }
+
+    async fn delete_progress(&self, user: &Thing) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        db.query("DELETE episode_progress WHERE user = $user").bind(("user", user.clone())).await?.check()?;
+        Ok(())
+    }
+
+    async fn save_list(&self, list: UserList) -> Result<UserList, GlassBoxError> {
+        let db = Self::db().await?;
+        let id = match &list.id {
+            Some(id) => id.clone(),
+            None => new_record_id("user_list")?,
+        };
+        let plain = list.clone();
+        let sealed = seal_list(UserList { id: Some(id.clone()), ..list }).await?;
+        let _: Option<UserList> = db.upsert(("user_list", id.id.to_raw())).content(sealed).await?;
+        Ok(UserList { id: Some(id), ..plain })
+    }
+
+    async fn lists(&self, user: &Thing) -> Result<Vec<UserList>, GlassBoxError> {
+        let db = Self::db().await?;
+        let sql = "SELECT * FROM user_list WHERE user = $user ORDER BY created_at ASC";
+        let mut response = db.query(sql).bind(("user", user.clone())).await?;
+        Ok(open_lists(response.take(0)?).await)
+    }
+
+    async fn delete_list(&self, id: &Thing) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        db.query("DELETE $id").bind(("id", id.clone())).await?.check()?;
+        Ok(())
+    }
+
+    async fn delete_lists(&self, user: &Thing) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        db.query("DELETE user_list WHERE user = $user").bind(("user", user.clone())).await?.check()?;
+        Ok(())
+    }
+
+    async fn save_note(&self, note: TitleNote) -> Result<TitleNote, GlassBoxError> {
+        let db = Self::db().await?;
+        let id = row_id("title_note", &note.user, &note.video_id).await?;
+        let plain = note.clone();
+        let sealed = seal_note(TitleNote { id: Some(id.clone()), ..note }).await?;
+        let _: Option<TitleNote> = db.upsert(("title_note", id.id.to_raw())).content(sealed).await?;
+        Ok(TitleNote { id: Some(id), ..plain })
+    }
+
+    async fn notes(&self, user: &Thing) -> Result<Vec<TitleNote>, GlassBoxError> {
+        let db = Self::db().await?;
+        let sql = "SELECT * FROM title_note WHERE user = $user ORDER BY timestamp DESC";
+        let mut response = db.query(sql).bind(("user", user.clone())).await?;
+        Ok(open_notes(response.take(0)?).await)
+    }
+
+    async fn delete_note(&self, id: &Thing) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        db.query("DELETE $id").bind(("id", id.clone())).await?.check()?;
+        Ok(())
+    }
+
+    async fn delete_notes(&self, user: &Thing) -> Result<(), GlassBoxError> {
+        let db = Self::db().await?;
+        db.query("DELETE title_note WHERE user = $user").bind(("user", user.clone())).await?.check()?;
+        Ok(())
+    }
+
+    async fn replace_history(&self, user: &Thing, history: UserHistory) -> Result<(), GlassBoxError> {
+        self.write_history(user, history, true).await
+    }
+
+    async fn merge_history(&self, user: &Thing, history: UserHistory) -> Result<(), GlassBoxError> {
+        self.write_history(user, history, false).await
+    }
+
+    async fn delete_account(&self, user: &Thing) -> Result<DeletionReport, GlassBoxError> {
+        let db = Self::db().await?;
+        // Legacy shared video rows (see vault.rs) go too, unless an account that
+        // hasn't moved its library yet still points at them. Sealed rows can't
+        // be matched, so nothing is pruned while such an account has a vault.
+        let sql = "BEGIN TRANSACTION;
+             LET $interactions = (DELETE interaction WHERE user = $user RETURN BEFORE);
+             LET $searches = (DELETE search_history WHERE user = $user RETURN BEFORE);
+             LET $ratings = (DELETE rating WHERE user = $user RETURN BEFORE);
+             LET $watchlist = (DELETE watchlist WHERE user = $user RETURN BEFORE);
+             LET $progress = (DELETE episode_progress WHERE user = $user RETURN BEFORE);
+             LET $lists = (DELETE user_list WHERE user = $user RETURN BEFORE);
+             LET $notes = (DELETE title_note WHERE user = $user RETURN BEFORE);
+             LET $library = (DELETE library_item WHERE user = $user RETURN BEFORE);
+             LET $settings = (DELETE settings WHERE user = $user RETURN BEFORE);
+             DELETE $vault;
+             DELETE $user;
+
+             LET $waiting = (SELECT VALUE id FROM user WHERE id NOTINSIDE (SELECT VALUE user FROM vault WHERE library = true));
+             IF array::len((SELECT id FROM vault WHERE library = false)) = 0 {
+                 LET $referenced = array::flatten([
+                     (SELECT VALUE video_id FROM interaction WHERE user INSIDE $waiting),
+                     (SELECT VALUE video_id FROM rating WHERE user INSIDE $waiting),
+                     (SELECT VALUE video_id FROM watchlist WHERE user INSIDE $waiting),
+                     (SELECT VALUE video_id FROM episode_progress WHERE user INSIDE $waiting),
+                     (SELECT VALUE video_id FROM title_note WHERE user INSIDE $waiting),
+                     array::flatten((SELECT VALUE items.video_id FROM user_list WHERE user INSIDE $waiting))
+                 ]);
+                 DELETE video WHERE video_id NOTINSIDE $referenced;
+             };
+
+             RETURN {
+                 username: '',
+                 library: array::len($library),
+                 interactions: array::len($interactions),
+                 searches: array::len($searches),
+                 ratings: array::len($ratings),
+                 watchlist: array::len($watchlist),
+                 progress: array::len($progress),
+                 lists: array::len($lists),
+                 notes: array::len($notes),
+                 settings: array::len($settings) > 0
+             };
+             COMMIT TRANSACTION;";
+        // RETURN leaves the transaction with that one result
+        let mut response = db
+            .query(sql)
+            .bind(("user", user.clone()))
+            .bind(("vault", Thing::from(("vault", user.id.to_raw().as_str()))))
+            .await?
+            .check()?;
+        let report: Option<DeletionReport> = response.take(0)?;
+        report.ok_or(GlassBoxError::Database("account deletion returned no report".to_string()))
+    }
+}
+
+// <------- In-memory backend (native runs, no browser) ------->
+#[derive(Default)]
+pub struct MemoryStore {
+    users: RefCell<Vec<User>>,
+    videos: RefCell<Vec<(Thing, Video)>>,
+    interactions: RefCell<Vec<InteractionEntry>>,
+    searches: RefCell<Vec<SearchEntry>>,
+    settings: RefCell<Vec<UserSettings>>,
+    ratings: RefCell<Vec<Rating>>,
+    watchlist: RefCell<Vec<WatchEntry>>,
+    progress: RefCell<Vec<EpisodeProgress>>,
+    lists: RefCell<Vec<UserList>>,
+    notes: RefCell<Vec<TitleNote>>,
+    next_id: RefCell<u64>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Mimics SurrealDB's generated record IDs
+    fn new_id(&self, table: &str) -> Thing {
+        let mut next = self.next_id.borrow_mut();
+        *next += 1;
+        Thing::from((table, next.to_string().as_str()))
+    }
+}
+
+impl Store for MemoryStore {
+    async fn find_user_by_username(&self, username: &str) -> Result<Option<User>, GlassBoxError> {
+        Ok(self.users.borrow().iter().find(|u| u.username == username).cloned())
+    }
+
+    async fn find_user_by_email(&self, email: &str) -> Result<Option<User>, GlassBoxError> {
+        Ok(self.users.borrow().iter().find(|u| u.email == email).cloned())
+    }
+
+    async fn find_user(&self, id: &Thing) -> Result<Option<User>, GlassBoxError> {
+        Ok(self.users.borrow().iter().find(|u| u.id.as_ref() == Some(id)).cloned())
+    }
+
+    async fn create_user(&self, mut user: User) -> Result<User, GlassBoxError> {
+        user.id = Some(self.new_id("user"));
+        self.users.borrow_mut().push(user.clone());
+        Ok(user)
+    }
+
+    async fn delete_user(&self, id: &Thing) -> Result<(), GlassBoxError> {
+        self.users.borrow_mut().retain(|u| u.id.as_ref() != Some(id));
+        Ok(())
+    }
+
+    async fn library(&self, user: &Thing) -> Result<Vec<Video>, GlassBoxError> {
+        let mut videos: Vec<Video> = self.videos.borrow().iter().filter(|(u, _)| u == user).map(|(_, v)| v.clone()).collect();
+        videos.sort_by_key(|v| std::cmp::Reverse(v.saved_at));
+        Ok(videos)
+    }
+
+    async fn save_video(&self, user: &Thing, video: Video) -> Result<Video, GlassBoxError> {
+        let video = Video { id: None, ..video };
+        let mut videos = self.videos.borrow_mut();
+        match videos.iter_mut().find(|(u, v)| u == user && v.video_id == video.video_id) {
+            Some((_, existing)) => *existing = video.clone(),
+            None => videos.push((user.clone(), video.clone())),
+        }
+        Ok(video)
+    }
+
+    async fn delete_library(&self, user: &Thing) -> Result<(), GlassBoxError> {
+        self.videos.borrow_mut().retain(|(u, _)| u != user);
+        Ok(())
+    }
+
+    async fn add_interaction(&self, mut entry: InteractionEntry) -> Result<(), GlassBoxError> {
+        entry.id = Some(self.new_id("interaction"));
+        self.interactions.borrow_mut().push(entry);
+        Ok(())
+    }
+
+    async fn update_interaction(&self, entry: InteractionEntry) -> Result<(), GlassBoxError> {
+        let mut all = self.interactions.borrow_mut();
+        match all.iter_mut().find(|i| i.id.is_some() && i.id == entry.id) {
+            Some(existing) => *existing = entry,
+            None => return Err(GlassBoxError::NotFound("Interaction".to_string())),
+        }
+        Ok(())
+    }
+
+    async fn interactions(&self) -> Result<Vec<InteractionEntry>, GlassBoxError> {
+        let mut entries = self.interactions.borrow().clone();
+        entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        Ok(entries)
+    }
+
+    async fn user_interactions(&self, user: &Thing) -> Result<Vec<InteractionEntry>, GlassBoxError> {
+        let mut entries: Vec<InteractionEntry> = self.interactions.borrow().iter().filter(|i| &i.user == user).cloned().collect();
+        entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        Ok(entries)
+    }
+
+    async fn latest_interaction(&self, user: &Thing) -> Result<Option<InteractionEntry>, GlassBoxError> {
+        let all = self.interactions.borrow();
+        Ok(all.iter().filter(|i| &i.user == user).max_by(|a, b| a.timestamp.cmp(&b.timestamp)).cloned())
+    }
+
+    async fn delete_interactions(&self, user: &Thing) -> Result<(), GlassBoxError> {
+        self.interactions.borrow_mut().retain(|i| &i.user != user);
+        Ok(())
+    }
+
+    async fn delete_interaction(&self, id: &Thing) -> Result<(), GlassBoxError> {
+        self.interactions.borrow_mut().retain(|i| i.id.as_ref() != Some(id));
+        Ok(())
+    }
+
+    async fn prune_history(&self, delete: Vec<Thing>, update: Vec<InteractionEntry>) -> Result<(), GlassBoxError> {
+        let mut interactions = self.interactions.borrow_mut();
+        if update.iter().any(|u| u.id.is_none() || !interactions.iter().any(|i| i.id == u.id)) {
+            return Err(GlassBoxError::NotFound("Interaction".to_string()));
+        }
+        interactions.retain(|i| !i.id.as_ref().is_some_and(|id| delete.contains(id)));
+        for entry in update {
+            if let Some(existing) = interactions.iter_mut().find(|i| i.id == entry.id) {
+                *existing = entry;
+            }
+        }
+        self.searches.borrow_mut().retain(|s| !s.id.as_ref().is_some_and(|id| delete.contains(id)));
+        Ok(())
+    }
+
+    async fn add_search(&self, mut entry: SearchEntry) -> Result<(), GlassBoxError> {
+        entry.id = Some(self.new_id("search_history"));
+        self.searches.borrow_mut().push(entry);
+        Ok(())
+    }
+
+    async fn searches(&self, user: &Thing) -> Result<Vec<SearchEntry>, GlassBoxError> {
+        let mut entries: Vec<SearchEntry> = self.searches.borrow().iter().filter(|s| &s.user == user).cloned().collect();
+        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(entries)
+    }
+
+    async fn delete_searches(&self, user: &Thing) -> Result<(), GlassBoxError> {
+        self.searches.borrow_mut().retain(|s| &s.user != user);
+        Ok(())
+    }
+
+    async fn delete_search(&self, id: &Thing) -> Result<(), GlassBoxError> {
+        self.searches.borrow_mut().retain(|s| s.id.as_ref() != Some(id));
+        Ok(())
+    }
+
+    async fn load_settings(&self, user: &Thing) -> Result<Option<UserSettings>, GlassBoxError> {
+        Ok(self.settings.borrow().iter().find(|s| &s.user == user).cloned())
+    }
+
+    async fn save_settings(&self, mut settings: UserSettings) -> Result<UserSettings, GlassBoxError> {
+        settings.id = Some(Thing::from(("settings", settings.user.id.to_raw().as_str())));
+        let mut all = self.settings.borrow_mut();
+        match all.iter_mut().find(|s| s.user == settings.user) {
+            Some(existing) => *existing = settings.clone(),
+            None => all.push(settings.clone()),
+        }
+        Ok(settings)
+    }
+
+    async fn delete_settings(&self, user: &Thing) -> Result<(), GlassBoxError> {
+        self.settings.borrow_mut().retain(|s| &s.user != user);
+        Ok(())
+    }
+
+    async fn save_rating(&self, mut rating: Rating) -> Result<Rating, GlassBoxError> {
+        let key = format!("{}_{}", rating.user.id.to_raw(), rating.video_id);
+        rating.id = Some(Thing::from(("rating", key.as_str())));
+        let mut all = self.ratings.borrow_mut();
+        match all.iter_mut().find(|r| r.id == rating.id) {
+            Some(existing) => *existing = rating.clone(),
+            None => all.push(rating.clone()),
+        }
+        Ok(rating)
+    }
+
+    async fn ratings(&self, user: &Thing) -> Result<Vec<Rating>, GlassBoxError> {
+        let mut entries: Vec<Rating> = self.ratings.borrow().iter().filter(|r| &r.user == user).cloned().collect();
+        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(entries)
+    }
+
+    async fn delete_ratings(&self, user: &Thing) -> Result<(), GlassBoxError> {
+        self.ratings.borrow_mut().retain(|r| &r.user != user);
+        Ok(())
+    }
+
+    async fn save_watch(&self, mut entry: WatchEntry) -> Result<WatchEntry, GlassBoxError> {
+        let key = format!("{}_{}", entry.user.id.to_raw(), entry.video_id);
+        entry.id = Some(Thing::from(("watchlist", key.as_str())));
+        let mut all = self.watchlist.borrow_mut();
+        match all.iter_mut().find(|e| e.id == entry.id) {
+            Some(existing) => *existing = entry.clone(),
+            None => all.push(entry.clone()),
+        }
+        Ok(entry)
+    }
+
+    async fn watchlist(&self, user: &Thing) -> Result<Vec<WatchEntry>, GlassBoxError> {
+        let mut entries: Vec<WatchEntry> = self.watchlist.borrow().iter().filter(|e| &e.user == user).cloned().collect();
+        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(entries)
+    }
+
+    async fn delete_watch(&self, id: &Thing) -> Result<(), GlassBoxError> {
+        self.watchlist.borrow_mut().retain(|e| e.id.as_ref() != Some(id));
+        Ok(())
+    }
+
+    async fn delete_watchlist(&self, user: &Thing) -> Result<(), GlassBoxError> {
+        self.watchlist.borrow_mut().retain(|e| &e.user != user);
+        Ok(())
+    }
+
+    async fn save_progress(&self, mut progress: EpisodeProgress) -> Result<EpisodeProgress, GlassBoxError> {
+        let key = format!("{}_{}", progress.user.id.to_raw(), progress.video_id);
+        progress.id = Some(Thing::from(("episode_progress", key.as_str())));
+        let mut all = self.progress.borrow_mut();
+        match all.iter_mut().find(|p| p.id == progress.id) {
+            Some(existing) => *existing = progress.clone(),
+            None => all.push(progress.clone()),
+        }
+        Ok(progress)
+    }
+
+    async fn progress(&self, user: &Thing) -> Result<Vec<EpisodeProgress>, GlassBoxError> {
+        let mut entries: Vec<EpisodeProgress> = self.progress.borrow().iter().filter(|p| &p.user == user).cloned().collect();
+        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(entries)
+    }
+
+    async fn delete_progress(&self, user: &Thing) -> Result<(), GlassBoxError> {
+        self.progress.borrow_mut().retain(|p| &p.user != user);
+        Ok(())
+    }
+
+    async fn save_list(&self, mut list: UserList) -> Result<UserList, GlassBoxError> {
+        if list.id.is_none() {
+            list.id = Some(self.new_id("user_list"));
+        }
+        let mut all = self.lists.borrow_mut();
+        match all.iter_mut().find(|l| l.id == list.id) {
+            Some(existing) => *existing = list.clone(),
+            None => all.push(list.clone()),
+        }
+        Ok(list)
+    }
+
+    async fn lists(&self, user: &Thing) -> Result<Vec<UserList>, GlassBoxError> {
+        let mut lists: Vec<UserList> = self.lists.borrow().iter().filter(|l| &l.user == user).cloned().collect();
+        lists.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(lists)
+    }
+
+    async fn delete_list(&self, id: &Thing) -> Result<(), GlassBoxError> {
+        self.lists.borrow_mut().retain(|l| l.id.as_ref() != Some(id));
+        Ok(())
+    }
+
+    async fn delete_lists(&self, user: &Thing) -> Result<(), GlassBoxError> {
+        self.lists.borrow_mut().retain(|l| &l.user != user);
+        Ok(())
+    }
+
+    async fn save_note(&self, mut note: TitleNote) -> Result<TitleNote, GlassBoxError> {
+        let key = format!("{}_{}", note.user.id.to_raw(), note.video_id);
+        note.id = Some(Thing::from(("title_note", key.as_str())));
+        let mut all = self.notes.borrow_mut();
+        match all.iter_mut().find(|n| n.id == note.id) {
+            Some(existing) => *existing = note.clone(),
+            None => all.push(note.clone()),
+        }
+        Ok(note)
+    }
+
+    async fn notes(&self, user: &Thing) -> Result<Vec<TitleNote>, GlassBoxError> {
+        let mut notes: Vec<TitleNote> = self.notes.borrow().iter().filter(|n| &n.user == user).cloned().collect();
+        notes.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(notes)
+    }
+
+    async fn delete_note(&self, id: &Thing) -> Result<(), GlassBoxError> {
+        self.notes.borrow_mut().retain(|n| n.id.as_ref() != Some(id));
+        Ok(())
+    }
+
+    async fn delete_notes(&self, user: &Thing) -> Result<(), GlassBoxError> {
+        self.notes.borrow_mut().retain(|n| &n.user != user);
+        Ok(())
+    }
+
+    async fn replace_history(&self, user: &Thing, history: UserHistory) -> Result<(), GlassBoxError> {
+        self.delete_interactions(user).await?;
+        self.delete_searches(user).await?;
+        self.delete_ratings(user).await?;
+        self.delete_watchlist(user).await?;
+        self.delete_progress(user).await?;
+        self.delete_lists(user).await?;
+        self.delete_notes(user).await?;
+        self.delete_library(user).await?;
+        self.merge_history(user, history).await
+    }
+
+    async fn merge_history(&self, user: &Thing, history: UserHistory) -> Result<(), GlassBoxError> {
+        for entry in history.interactions {
+            self.add_interaction(entry).await?;
+        }
+        for entry in history.searches {
+            self.add_search(entry).await?;
+        }
+        for rating in history.ratings {
+            self.save_rating(rating).await?;
+        }
+        for entry in history.watchlist {
+            self.save_watch(entry).await?;
+        }
+        for row in history.progress {
+            self.save_progress(row).await?;
+        }
+        for list in history.lists {
+            self.save_list(list).await?;
+        }
+        for note in history.notes {
+            self.save_note(note).await?;
+        }
+        for video in history.library {
+            self.save_video(user, video).await?;
+        }
+        if let Some(settings) = history.settings {
+            self.save_settings(settings).await?;
+        }
+        Ok(())
+    }
+
+    async fn delete_account(&self, user: &Thing) -> Result<DeletionReport, GlassBoxError> {
+        let report = DeletionReport {
+            username: String::new(),
+            library: self.videos.borrow().iter().filter(|(u, _)| u == user).count(),
+            interactions: self.interactions.borrow().iter().filter(|i| &i.user == user).count(),
+            searches: self.searches.borrow().iter().filter(|s| &s.user == user).count(),
+            ratings: self.ratings.borrow().iter().filter(|r| &r.user == user).count(),
+            watchlist: self.watchlist.borrow().iter().filter(|e| &e.user == user).count(),
+            progress: self.progress.borrow().iter().filter(|p| &p.user == user).count(),
+            lists: self.lists.borrow().iter().filter(|l| &l.user == user).count(),
+            notes: self.notes.borrow().iter().filter(|n| &n.user == user).count(),
+            settings: self.settings.borrow().iter().any(|s| &s.user == user),
+        };
+        self.delete_interactions(user).await?;
+        self.delete_searches(user).await?;
+        self.delete_ratings(user).await?;
+        self.delete_watchlist(user).await?;
+        self.delete_progress(user).await?;
+        self.delete_lists(user).await?;
+        self.delete_notes(user).await?;
+        self.delete_library(user).await?;
+        self.delete_settings(user).await?;
+        self.delete_user(user).await?;
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::config::RecommenderConfig;
+    use crate::model::history::{get_user_interactions_with, get_user_library_with, save_interaction_with};
+    use crate::model::next_item::TransitionModel;
+    use crate::model::svd::SVD;
+    use crate::model::users::{create_new_user_with, is_username_taken_with, login_user_with};
+    use crate::model::video::save_video_with;
+    use futures::executor::block_on;
+
+    fn show(id: &str, title: &str, genres: &[&str]) -> Video {
+        Video {
+            id: None,
+            video_id: id.to_string(),
+            title: title.to_string(),
+            description: String::new(),
+            thumbnail_url: String::new(),
+            rating: 8.0,
+            genres: genres.iter().map(|g| g.to_string()).collect(),
+            channel_name: String::new(),
+            related_ids: Vec::new(),
+            saved_at: chrono::Utc::now(),
+        }
+    }
+
+    fn signed_up(store: &MemoryStore, username: &str) -> Thing {
+        let user = block_on(create_new_user_with(store, username.to_string(), format!("{}@example.org", username), "hunter2".to_string())).unwrap();
+        user.id.unwrap()
+    }
+
+    #[test]
+    fn signup_then_login() {
+        let store = MemoryStore::new();
+        let id = signed_up(&store, "ann");
+
+        assert!(block_on(is_username_taken_with(&store, "ann".to_string())).unwrap());
+        let user = block_on(login_user_with(&store, "ann".to_string(), "hunter2".to_string())).unwrap();
+        assert_eq!(user.id, Some(id));
+        assert_ne!(user.password, "hunter2");
+    }
+
+    #[test]
+    fn login_rejects_wrong_password_and_unknown_user() {
+        let store = MemoryStore::new();
+        signed_up(&store, "ann");
+
+        let wrong = block_on(login_user_with(&store, "ann".to_string(), "nope".to_string()));
+        assert_eq!(wrong.unwrap_err(), GlassBoxError::AuthFailed);
+        let unknown = block_on(login_user_with(&store, "bob".to_string(), "hunter2".to_string()));
+        assert_eq!(unknown.unwrap_err(), GlassBoxError::AuthFailed);
+    }
+
+    #[test]
+    fn repeated_clicks_extend_one_run() {
+        let store = MemoryStore::new();
+        let user = Thing::from(("user", "ann"));
+        let video = block_on(save_video_with(&store, user.clone(), show("1", "Severance", &["Drama"]))).unwrap();
+
+        block_on(save_interaction_with(&store, user.clone(), video.clone(), "click".to_string())).unwrap();
+        let run = block_on(save_interaction_with(&store, user.clone(), video, "click".to_string())).unwrap();
+
+        assert_eq!(run.count, 2);
+        assert_eq!(block_on(get_user_interactions_with(&store, user)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn library_only_holds_the_users_own_shows() {
+        let store = MemoryStore::new();
+        let ann = Thing::from(("user", "ann"));
+        let bob = Thing::from(("user", "bob"));
+        let mine = block_on(save_video_with(&store, ann.clone(), show("1", "Severance", &["Drama"]))).unwrap();
+        let theirs = block_on(save_video_with(&store, bob.clone(), show("2", "Bluey", &["Children"]))).unwrap();
+        // Saved for a list or a note only: cached, but not part of the taste profile
+        block_on(save_video_with(&store, ann.clone(), show("3", "Dark", &["Drama"]))).unwrap();
+
+        block_on(save_interaction_with(&store, ann.clone(), mine, "click".to_string())).unwrap();
+        block_on(save_interaction_with(&store, bob.clone(), theirs, "click".to_string())).unwrap();
+
+        let library = block_on(get_user_library_with(&store, ann.clone())).unwrap();
+        assert_eq!(library.iter().map(|v| v.video_id.as_str()).collect::<Vec<_>>(), vec!["1"]);
+        let saved = block_on(store.library(&ann)).unwrap();
+        assert!(saved.iter().all(|v| v.video_id != "2"));
+        let library = block_on(get_user_library_with(&store, bob)).unwrap();
+        assert_eq!(library.iter().map(|v| v.video_id.as_str()).collect::<Vec<_>>(), vec!["2"]);
+        let log = block_on(store.user_interactions(&ann)).unwrap();
+        assert_eq!(log.iter().map(|i| i.video_id.as_str()).collect::<Vec<_>>(), vec!["1"]);
+    }
+
+    #[test]
+    fn feed_ranks_by_taste_and_click_order() {
+        let store = MemoryStore::new();
+        let user = Thing::from(("user", "ann"));
+        let drama = block_on(save_video_with(&store, user.clone(), show("1", "Severance", &["Drama", "Thriller"]))).unwrap();
+        let next = block_on(save_video_with(&store, user.clone(), show("2", "Dark", &["Drama", "Mystery"]))).unwrap();
+
+        block_on(save_interaction_with(&store, user.clone(), drama, "click".to_string())).unwrap();
+        block_on(save_interaction_with(&store, user.clone(), next, "click".to_string())).unwrap();
+
+        // Taste vector: another drama beats a comedy
+        let library = block_on(get_user_library_with(&store, user)).unwrap();
+        let taste = SVD::user_vector(&library, &RecommenderConfig::default());
+        let candidate = show("3", "Succession", &["Drama"]);
+        let other = show("4", "Ted Lasso", &["Comedy"]);
+        assert!(SVD::predict_match(&taste, &candidate) > SVD::predict_match(&taste, &other));
+
+        // Next-item row: Dark followed Severance
+        let log = block_on(store.interactions()).unwrap();
+        let model = TransitionModel::from_interactions(&log, RecommenderConfig::default().session_gap_minutes);
+        assert_eq!(model.next_items("1", 5).first().map(|(id, _)| id.as_str()), Some("2"));
+    }
+}
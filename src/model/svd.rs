@@ -1,5 +1,6 @@
 // <------- This is the file where I am gonna write the main recommendation model engine for user feed ------->
 
+use crate::model::config::RecommenderConfig;
 use crate::model::video::Video;
 
 const ALL_GENRES: &[&str] = &[
@@ -24,6 +25,7 @@ const ALL_GENRES: &[&str] = &[
     "Western",
 ];
 
+#[allow(clippy::upper_case_acronyms)]
 pub struct SVD;
 
 impl SVD {
@@ -44,7 +46,12 @@ impl SVD {
     }
 
     // <------- Calculate the vector base on user watch history ------->
-    pub fn user_vector(history: &[Video]) -> Vec<f64> {
+    pub fn user_vector(history: &[Video], config: &RecommenderConfig) -> Vec<f64> {
+        Self::user_vector_weighted(history, config, |_| 1.0)
+    }
+
+    // Same as user_vector, with an extra per-show multiplier (watch state)
+    pub fn user_vector_weighted(history: &[Video], config: &RecommenderConfig, state_weight: impl Fn(&Video) -> f64) -> Vec<f64> {
         let mut user_vec = vec![0.0; ALL_GENRES.len()];
 
         if history.is_empty() {
@@ -53,31 +60,26 @@ impl SVD {
 
         // Create a list of references and sort by date (newest first)
         let mut sorted_history: Vec<&Video> = history.iter().collect();
-        sorted_history.sort_by(|a, b| b.saved_at.cmp(&a.saved_at));
+        sorted_history.sort_by_key(|v| std::cmp::Reverse(v.saved_at));
 
         let mut total_weight = 0.0;
 
         for (idx, video) in sorted_history.iter().enumerate() {
             let vec_vdo = Self::vectorise(video);
 
-            // Base weight from rating (default 5.0)
+            // Base weight from rating (default from config)
             let mut weight = if video.rating > 0.0 {
                 video.rating
             } else {
-                5.0
+                config.default_weight
             };
 
             // RECENCY BOOST:
-            // 1st item (newest): 5x multiplier
-            // 2nd item: 3x multiplier
-            // 3rd item: 2x multiplier
-            if idx == 0 {
-                weight *= 5.0;
-            } else if idx == 1 {
-                weight *= 3.0;
-            } else if idx == 2 {
-                weight *= 2.0;
-            }
+            // newest items get the configured multipliers (default 5x, 3x, 2x)
+            weight *= config.recency_multiplier(idx);
+
+            // WATCH STATE: "Watched" outweighs a curious click, "Dropped" counts little
+            weight *= state_weight(video);
 
             total_weight += weight;
 
@@ -88,8 +90,8 @@ impl SVD {
 
         // Normalize the vector
         if total_weight > 0.0 {
-            for i in 0..user_vec.len() {
-                user_vec[i] /= total_weight; // Normalize by sum of weights, not count
+            for value in user_vec.iter_mut() {
+                *value /= total_weight; // Normalize by sum of weights, not count
             }
         }
 
@@ -111,25 +113,30 @@ impl SVD {
         score
     }
 
-    pub fn get_top_genre(user_vector: &[f64]) -> String {
+    // <------- Top N genres used as candidate queries ------->
+    pub fn get_top_genres(user_vector: &[f64], config: &RecommenderConfig) -> Vec<String> {
         if user_vector.len() != ALL_GENRES.len() {
-            return "Trending".to_string();
-        }
-
-        let mut max_score = -1.0;
-        let mut best_idx = 0;
-
-        for (i, score) in user_vector.iter().enumerate() {
-            if *score > max_score {
-                max_score = *score;
-                best_idx = i;
-            }
+            return vec![config.fallback_query.clone()];
         }
 
-        if max_score <= 0.0 {
-            "Trending".to_string() // Fallback
+        let mut ranked: Vec<(usize, f64)> = user_vector
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let genres: Vec<String> = ranked
+            .into_iter()
+            .take(config.candidate_genres.max(1))
+            .map(|(i, _)| ALL_GENRES[i].to_string())
+            .collect();
+
+        if genres.is_empty() {
+            vec![config.fallback_query.clone()]
         } else {
-            ALL_GENRES[best_idx].to_string()
+            genres
         }
     }
 }
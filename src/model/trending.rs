@@ -0,0 +1,160 @@
+// <------- Popularity baseline: what everyone is watching ------->
+// Used for the cold-start feed (nothing in the library yet) and as the
+// baseline the personalised engine has to beat.
+
+use crate::api::trending::{fetch_popular_shows, PopularShow};
+use crate::model::db::db_ready;
+use crate::model::error::GlassBoxError;
+use crate::model::video::Video;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+// The fallback query that means "use the popularity source" instead of a search
+pub const TRENDING_QUERY: &str = "Trending";
+
+// Schedules change daily; half a day keeps the list fresh without hammering TVMaze
+const CACHE_HOURS: i64 = 12;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrendingEntry {
+    pub video: Video,
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct TrendingCache {
+    id: Option<Thing>,
+    fetched_at: DateTime<Utc>,
+    entries: Vec<TrendingEntry>,
+}
+
+pub fn is_trending_query(query: &str) -> bool {
+    query.trim().eq_ignore_ascii_case(TRENDING_QUERY)
+}
+
+// 50% rating, 30% TVMaze weight, 20% recency (on air today, or how long ago it premiered)
+pub fn popularity_score(show: &PopularShow, now: DateTime<Utc>) -> f64 {
+    let rating = (show.video.rating / 10.0).clamp(0.0, 1.0);
+    let weight = (show.weight as f64 / 100.0).clamp(0.0, 1.0);
+
+    let recency = if show.airing {
+        1.0
+    } else {
+        show.premiered
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+            .map(|d| {
+                let years = (now.date_naive() - d).num_days().max(0) as f64 / 365.0;
+                (-years / 10.0).exp()
+            })
+            .unwrap_or(0.0)
+    };
+
+    0.5 * rating + 0.3 * weight + 0.2 * recency
+}
+
+// Rating-only popularity for shows we have no TVMaze weight for (e.g. offline evaluation)
+pub fn rating_popularity(video: &Video) -> f64 {
+    (video.rating / 10.0).clamp(0.0, 1.0)
+}
+
+// Cached list if it is fresh enough, otherwise refetch and re-cache
+pub async fn get_trending(limit: usize) -> Result<Vec<Video>, GlassBoxError> {
+    let db = db_ready().await?;
+    let now = Utc::now();
+
+    let cached: Option<TrendingCache> = db.select(("trending", "cache")).await?;
+    if let Some(cache) = cached {
+        if (now - cache.fetched_at).num_hours() < CACHE_HOURS && !cache.entries.is_empty() {
+            return Ok(cache.entries.into_iter().take(limit).map(|e| e.video).collect());
+        }
+    }
+
+    let shows = match fetch_popular_shows().await {
+        Ok(shows) => shows,
+        Err(e) => {
+            // Offline: a stale list is better than an empty feed
+            let stale: Option<TrendingCache> = db.select(("trending", "cache")).await?;
+            return match stale {
+                Some(cache) => Ok(cache.entries.into_iter().take(limit).map(|e| e.video).collect()),
+                None => Err(e),
+            };
+        }
+    };
+
+    let mut entries: Vec<TrendingEntry> = shows
+        .iter()
+        .map(|s| TrendingEntry {
+            video: s.video.clone(),
+            score: popularity_score(s, now),
+        })
+        .collect();
+    entries.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+
+    let cache = TrendingCache {
+        id: None,
+        fetched_at: now,
+        entries: entries.clone(),
+    };
+    let _: Option<TrendingCache> = db.upsert(("trending", "cache")).content(cache).await?;
+
+    Ok(entries.into_iter().take(limit).map(|e| e.video).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn popular(rating: f64, weight: u32, premiered: Option<&str>, airing: bool) -> PopularShow {
+        PopularShow {
+            video: Video {
+                id: None,
+                video_id: "1".to_string(),
+                title: "Show".to_string(),
+                description: String::new(),
+                thumbnail_url: String::new(),
+                rating,
+                genres: Vec::new(),
+                channel_name: String::new(),
+                related_ids: Vec::new(),
+                saved_at: Utc::now(),
+            },
+            weight,
+            premiered: premiered.map(str::to_string),
+            airing,
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2025-06-01T00:00:00+00:00").unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn a_perfect_show_on_air_scores_one() {
+        assert!((popularity_score(&popular(10.0, 100, None, true), now()) - 1.0).abs() < 1e-9);
+        assert_eq!(popularity_score(&popular(0.0, 0, None, false), now()), 0.0);
+    }
+
+    #[test]
+    fn older_premieres_fade_and_bad_dates_count_as_unknown() {
+        let fresh = popularity_score(&popular(8.0, 50, Some("2025-06-01"), false), now());
+        let decade = popularity_score(&popular(8.0, 50, Some("2015-06-01"), false), now());
+        let unknown = popularity_score(&popular(8.0, 50, Some("June 2015"), false), now());
+        assert!((fresh - (0.5 * 0.8 + 0.3 * 0.5 + 0.2)).abs() < 1e-9);
+        // Ten years is one e-fold of the recency term
+        assert!((fresh - decade - 0.2 * (1.0 - (-3653.0f64 / 365.0 / 10.0).exp())).abs() < 1e-9);
+        assert!(unknown < decade);
+        // A premiere in the future counts as today
+        assert_eq!(popularity_score(&popular(8.0, 50, Some("2026-01-01"), false), now()), fresh);
+    }
+
+    #[test]
+    fn out_of_range_inputs_are_clamped() {
+        let wild = popularity_score(&popular(42.0, 500, None, true), now());
+        assert!((wild - 1.0).abs() < 1e-9);
+        assert_eq!(rating_popularity(&popular(-3.0, 0, None, false).video), 0.0);
+        assert!(is_trending_query("  trending "));
+        assert!(!is_trending_query("Trending shows"));
+    }
+}
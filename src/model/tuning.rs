@@ -0,0 +1,509 @@
+// <------- Offline hyperparameter search for RecommenderConfig ------->
+// Replays the library on a temporal split: older items build the profile,
+// newer items are what the engine should have ranked at the top.
+//
+// Scoring follows the feed's ranking step (taste vector with watch-state
+// weights, tag and search boosts), so these fields are tuned:
+// recency_multipliers, default_weight, the five watch-state weights,
+// tag_weight and query_weight. The rest need TVMaze (people, graph,
+// candidate genres, episode lists for abandon_days) or a live click sequence
+// (session_*), and are kept from the base config.
+
+use crate::model::config::RecommenderConfig;
+use crate::model::history::SearchEntry;
+use crate::model::notes::{tag_boost, TagProfile, TitleNote};
+use crate::model::queries::{query_boost, QueryProfile};
+use crate::model::svd::SVD;
+use crate::model::trending::rating_popularity;
+use crate::model::video::Video;
+use crate::model::watchlist::{state_weights, with_watch_times, WatchEntry};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum Metric {
+    HitRate,
+    Mrr,
+    // Rewards putting a held-out title high, not just somewhere in the top K
+    #[default]
+    Ndcg,
+}
+
+impl Metric {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Metric::HitRate => "HitRate@K",
+            Metric::Mrr => "MRR",
+            Metric::Ndcg => "NDCG@K",
+        }
+    }
+}
+
+// Values tried for each tunable field. An empty list keeps the base config's
+// value, so a space file only has to name the fields it wants searched.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchSpace {
+    #[serde(default)]
+    pub recency_multipliers: Vec<Vec<f64>>,
+    #[serde(default)]
+    pub default_weight: Vec<f64>,
+    #[serde(default)]
+    pub click_weight: Vec<f64>,
+    #[serde(default)]
+    pub want_weight: Vec<f64>,
+    #[serde(default)]
+    pub watching_weight: Vec<f64>,
+    #[serde(default)]
+    pub watched_weight: Vec<f64>,
+    #[serde(default)]
+    pub dropped_weight: Vec<f64>,
+    #[serde(default)]
+    pub tag_weight: Vec<f64>,
+    #[serde(default)]
+    pub query_weight: Vec<f64>,
+}
+
+impl Default for SearchSpace {
+    fn default() -> Self {
+        Self {
+            recency_multipliers: vec![
+                vec![],
+                vec![2.0],
+                vec![3.0, 2.0],
+                vec![5.0, 3.0, 2.0],
+                vec![8.0, 4.0, 2.0, 1.5],
+            ],
+            default_weight: vec![2.5, 5.0, 7.5],
+            click_weight: vec![0.3, 1.0],
+            want_weight: vec![0.6],
+            watching_weight: vec![1.0],
+            watched_weight: vec![1.0, 1.5, 2.0],
+            dropped_weight: vec![0.0],
+            tag_weight: vec![0.0, 0.3, 0.6],
+            query_weight: vec![0.0, 0.1, 0.3],
+        }
+    }
+}
+
+type Setter = fn(&mut RecommenderConfig, f64);
+
+impl SearchSpace {
+    // Every scalar field with the values to try and how to set it
+    fn scalars(&self) -> [(&[f64], Setter); 8] {
+        [
+            (&self.default_weight, |c, v| c.default_weight = v),
+            (&self.click_weight, |c, v| c.click_weight = v),
+            (&self.want_weight, |c, v| c.want_weight = v),
+            (&self.watching_weight, |c, v| c.watching_weight = v),
+            (&self.watched_weight, |c, v| c.watched_weight = v),
+            (&self.dropped_weight, |c, v| c.dropped_weight = v),
+            (&self.tag_weight, |c, v| c.tag_weight = v),
+            (&self.query_weight, |c, v| c.query_weight = v),
+        ]
+    }
+
+    // How many configs grid_search tries
+    pub fn grid_size(&self) -> usize {
+        self.recency_multipliers.len().max(1) * self.scalars().iter().map(|(values, _)| values.len().max(1)).product::<usize>()
+    }
+}
+
+// The tuned fields of a config, one line (evaluator output, Settings)
+pub fn describe(config: &RecommenderConfig) -> String {
+    format!(
+        "recency {:?}  default {}  click {}  want {}  watching {}  watched {}  dropped {}  tags {}  searches {}",
+        config.recency_multipliers,
+        config.default_weight,
+        config.click_weight,
+        config.want_weight,
+        config.watching_weight,
+        config.watched_weight,
+        config.dropped_weight,
+        config.tag_weight,
+        config.query_weight
+    )
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrialResult {
+    pub config: RecommenderConfig,
+    pub score: f64,
+}
+
+// What the feed reads besides the library (episode progress needs TVMaze, so
+// it is left out)
+#[derive(Debug, Clone, Default)]
+pub struct Signals {
+    pub watchlist: Vec<WatchEntry>,
+    pub notes: Vec<TitleNote>,
+    pub searches: Vec<SearchEntry>,
+}
+
+pub struct TemporalSplit {
+    pub train: Vec<Video>,
+    pub test: Vec<Video>,
+    // Only what was known before the held-out titles (see with_signals)
+    pub signals: Signals,
+}
+
+// Oldest items train, the newest `holdout` fraction (at least one) is held out
+pub fn temporal_split(history: &[Video], holdout: f64) -> Option<TemporalSplit> {
+    if history.len() < 2 {
+        return None;
+    }
+
+    let mut sorted = history.to_vec();
+    sorted.sort_by_key(|v| v.saved_at);
+
+    let test_len = ((sorted.len() as f64) * holdout.clamp(0.0, 1.0)).round() as usize;
+    let test_len = test_len.clamp(1, sorted.len() - 1);
+    let test = sorted.split_off(sorted.len() - test_len);
+
+    Some(TemporalSplit {
+        train: sorted,
+        test,
+        signals: Signals::default(),
+    })
+}
+
+impl TemporalSplit {
+    // Keeps watch states and notes of training titles and searches made before
+    // the first held-out title, so nothing about the answers leaks in
+    pub fn with_signals(self, signals: Signals) -> Self {
+        let cutoff = self.test.iter().map(|v| v.saved_at).min();
+        let trained = |id: &str| self.train.iter().any(|v| v.video_id == id);
+        let before = |ts: &str| match (cutoff, DateTime::parse_from_rfc3339(ts)) {
+            (Some(cutoff), Ok(t)) => t.with_timezone(&Utc) < cutoff,
+            _ => false,
+        };
+        let signals = Signals {
+            watchlist: signals.watchlist.into_iter().filter(|e| trained(&e.video_id)).collect(),
+            notes: signals.notes.into_iter().filter(|n| trained(&n.video_id)).collect(),
+            searches: signals.searches.into_iter().filter(|s| before(&s.timestamp)).collect(),
+        };
+        Self { signals, ..self }
+    }
+}
+
+// Score one config the way the feed ranks candidates, and see where the
+// held-out items landed
+pub fn evaluate(config: &RecommenderConfig, split: &TemporalSplit, pool: &[Video], metric: Metric, k: usize) -> f64 {
+    let weights = state_weights(&split.signals.watchlist, config);
+    let weight = |v: &Video| weights.get(&v.video_id).copied().unwrap_or(config.click_weight);
+    let train = with_watch_times(split.train.clone(), &split.signals.watchlist);
+
+    let user_vec = SVD::user_vector_weighted(&train, config, weight);
+    let tags = TagProfile::from_notes(&split.signals.notes, &train, weight);
+    let searched = QueryProfile::from_searches(&split.signals.searches);
+    evaluate_with(split, pool, metric, k, |v| {
+        SVD::predict_match(&user_vec, v) + tag_boost(&tags, v, config) + query_boost(&searched, v, config)
+    })
+}
+
+// The non-personalised baseline: rank the pool by popularity alone
+pub fn evaluate_popularity(split: &TemporalSplit, pool: &[Video], metric: Metric, k: usize) -> f64 {
+    evaluate_with(split, pool, metric, k, rating_popularity)
+}
+
+fn evaluate_with(split: &TemporalSplit, pool: &[Video], metric: Metric, k: usize, score: impl Fn(&Video) -> f64) -> f64 {
+    if split.test.is_empty() {
+        return 0.0;
+    }
+
+    // Never rank things the profile was built from
+    let mut ranked: Vec<&Video> = pool
+        .iter()
+        .filter(|v| !split.train.iter().any(|t| t.video_id == v.video_id))
+        .collect();
+    ranked.sort_by(|a, b| {
+        let score_a = score(a);
+        let score_b = score(b);
+        score_b.partial_cmp(&score_a).unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let mut total = 0.0;
+    for target in &split.test {
+        let Some(rank) = ranked.iter().position(|v| v.video_id == target.video_id) else {
+            continue;
+        };
+        total += match metric {
+            Metric::HitRate => {
+                if rank < k {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Metric::Mrr => 1.0 / (rank as f64 + 1.0),
+            Metric::Ndcg => {
+                if rank < k {
+                    1.0 / (rank as f64 + 2.0).log2()
+                } else {
+                    0.0
+                }
+            }
+        };
+    }
+
+    total / split.test.len() as f64
+}
+
+pub fn grid_search(
+    base: &RecommenderConfig,
+    space: &SearchSpace,
+    split: &TemporalSplit,
+    pool: &[Video],
+    metric: Metric,
+    k: usize,
+) -> Vec<TrialResult> {
+    let mut configs = if space.recency_multipliers.is_empty() {
+        vec![base.clone()]
+    } else {
+        space
+            .recency_multipliers
+            .iter()
+            .map(|m| RecommenderConfig {
+                recency_multipliers: m.clone(),
+                ..base.clone()
+            })
+            .collect()
+    };
+    for (values, set) in space.scalars() {
+        if values.is_empty() {
+            continue;
+        }
+        configs = configs
+            .into_iter()
+            .flat_map(|config| {
+                values.iter().map(move |v| {
+                    let mut config = config.clone();
+                    set(&mut config, *v);
+                    config
+                })
+            })
+            .collect();
+    }
+
+    let results = configs
+        .into_iter()
+        .map(|config| {
+            let score = evaluate(&config, split, pool, metric, k);
+            TrialResult { config, score }
+        })
+        .collect();
+    rank_results(results)
+}
+
+// Samples each field uniformly between the smallest and largest grid value
+#[allow(clippy::too_many_arguments)]
+pub fn random_search(
+    base: &RecommenderConfig,
+    space: &SearchSpace,
+    split: &TemporalSplit,
+    pool: &[Video],
+    metric: Metric,
+    k: usize,
+    trials: usize,
+    seed: u64,
+) -> Vec<TrialResult> {
+    let mut rng = XorShift::new(seed);
+
+    let max_len = space.recency_multipliers.iter().map(|m| m.len()).max().unwrap_or(0);
+    let max_boost = space
+        .recency_multipliers
+        .iter()
+        .flatten()
+        .copied()
+        .fold(1.0, f64::max);
+    let ranges: Vec<(Option<(f64, f64)>, Setter)> = space
+        .scalars()
+        .into_iter()
+        .map(|(values, set)| {
+            let range = values.iter().fold(None, |range: Option<(f64, f64)>, v| match range {
+                Some((lo, hi)) => Some((lo.min(*v), hi.max(*v))),
+                None => Some((*v, *v)),
+            });
+            (range, set)
+        })
+        .collect();
+
+    let mut results = Vec::new();
+    for _ in 0..trials {
+        let mut config = base.clone();
+
+        // Multipliers stay non-increasing so "newer" never counts less than "older"
+        if !space.recency_multipliers.is_empty() {
+            let len = rng.below(max_len + 1);
+            let mut ceiling = max_boost;
+            let mut multipliers = Vec::with_capacity(len);
+            for _ in 0..len {
+                let m = 1.0 + rng.next_f64() * (ceiling - 1.0);
+                multipliers.push((m * 100.0).round() / 100.0);
+                ceiling = m;
+            }
+            config.recency_multipliers = multipliers;
+        }
+
+        for (range, set) in &ranges {
+            if let Some((lo, hi)) = range {
+                let v = lo + rng.next_f64() * (hi - lo);
+                set(&mut config, (v * 100.0).round() / 100.0);
+            }
+        }
+
+        let score = evaluate(&config, split, pool, metric, k);
+        results.push(TrialResult { config, score });
+    }
+
+    rank_results(results)
+}
+
+fn rank_results(mut results: Vec<TrialResult>) -> Vec<TrialResult> {
+    results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    results
+}
+
+// Tiny deterministic RNG so a seed reproduces the same trials
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        if n == 0 {
+            0
+        } else {
+            (self.next_u64() % n as u64) as usize
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::watchlist::WatchState;
+    use chrono::{Duration, Utc};
+    use surrealdb::sql::Thing;
+
+    fn show(id: usize, genre: &str, days_ago: i64) -> Video {
+        Video {
+            id: None,
+            video_id: id.to_string(),
+            title: format!("Show {}", id),
+            description: String::new(),
+            thumbnail_url: String::new(),
+            rating: 7.0,
+            genres: vec![genre.to_string()],
+            channel_name: String::new(),
+            related_ids: Vec::new(),
+            saved_at: Utc::now() - Duration::days(days_ago),
+        }
+    }
+
+    // A drama fan whose newest title is a drama, among comedies
+    fn fixture() -> (TemporalSplit, Vec<Video>) {
+        let history: Vec<Video> = (0..5).map(|i| show(i, "Drama", 10 - i as i64)).collect();
+        let split = temporal_split(&history, 0.2).unwrap();
+        let mut pool = split.test.clone();
+        pool.extend((10..20).map(|i| show(i, "Comedy", 1)));
+        (split, pool)
+    }
+
+    #[test]
+    fn split_holds_out_the_newest() {
+        let (split, _) = fixture();
+        assert_eq!(split.train.len(), 4);
+        assert_eq!(split.test.iter().map(|v| v.video_id.as_str()).collect::<Vec<_>>(), vec!["4"]);
+    }
+
+    #[test]
+    fn grid_tries_every_combination_best_first() {
+        let (split, pool) = fixture();
+        let space = SearchSpace::default();
+        let results = grid_search(&RecommenderConfig::default(), &space, &split, &pool, Metric::HitRate, 1);
+        assert_eq!(results.len(), space.grid_size());
+        assert_eq!(results.len(), 5 * 3 * 2 * 3 * 3 * 3);
+        assert!(results.windows(2).all(|w| w[0].score >= w[1].score));
+        assert_eq!(results[0].score, 1.0);
+    }
+
+    fn watched(video: &Video, state: WatchState) -> WatchEntry {
+        WatchEntry {
+            id: None,
+            user: Thing::from(("user", "test")),
+            video_id: video.video_id.clone(),
+            video_title: video.title.clone(),
+            state,
+            added_at: video.saved_at.to_rfc3339(),
+            timestamp: video.saved_at.to_rfc3339(),
+        }
+    }
+
+    fn searched(query: &str, days_ago: i64) -> SearchEntry {
+        SearchEntry {
+            id: None,
+            user: Thing::from(("user", "test")),
+            query: query.to_string(),
+            timestamp: (Utc::now() - Duration::days(days_ago)).to_rfc3339(),
+        }
+    }
+
+    #[test]
+    fn watch_states_move_the_ranking_like_the_feed() {
+        // Mostly dramas saved, but they were dropped; the one comedy was finished
+        let mut history: Vec<Video> = (0..3).map(|i| show(i, "Drama", 10 - i as i64)).collect();
+        history.push(show(3, "Comedy", 7));
+        history.push(show(4, "Comedy", 1));
+        let mut states: Vec<WatchEntry> = history[..3].iter().map(|v| watched(v, WatchState::Dropped)).collect();
+        states.push(watched(&history[3], WatchState::Watched));
+
+        let plain = temporal_split(&history, 0.2).unwrap();
+        let mut pool = plain.test.clone();
+        pool.extend((10..20).map(|i| show(i, "Drama", 1)));
+        let informed = temporal_split(&history, 0.2).unwrap().with_signals(Signals {
+            watchlist: states,
+            ..Signals::default()
+        });
+
+        let config = RecommenderConfig::default();
+        assert_eq!(evaluate(&config, &plain, &pool, Metric::HitRate, 1), 0.0);
+        assert_eq!(evaluate(&config, &informed, &pool, Metric::HitRate, 1), 1.0);
+    }
+
+    #[test]
+    fn signals_about_held_out_titles_are_dropped() {
+        let (split, _) = fixture();
+        let watchlist = vec![watched(&split.train[0], WatchState::Watched), watched(&split.test[0], WatchState::Watched)];
+        let split = split.with_signals(Signals {
+            watchlist,
+            notes: Vec::new(),
+            searches: vec![searched("drama", 9), searched("comedy", 0)],
+        });
+        assert_eq!(split.signals.watchlist.len(), 1);
+        assert_eq!(split.signals.watchlist[0].video_id, "0");
+        assert_eq!(split.signals.searches.iter().map(|s| s.query.as_str()).collect::<Vec<_>>(), vec!["drama"]);
+    }
+
+    #[test]
+    fn random_search_is_reproducible_from_its_seed() {
+        let (split, pool) = fixture();
+        let run = |seed| random_search(&RecommenderConfig::default(), &SearchSpace::default(), &split, &pool, Metric::Mrr, 5, 8, seed);
+        let configs = |results: Vec<TrialResult>| results.into_iter().map(|t| t.config).collect::<Vec<_>>();
+        assert_eq!(configs(run(7)), configs(run(7)));
+    }
+}
@@ -0,0 +1,25 @@
+// <------- Read a file the user picked ------->
+// The counterpart of download.rs: <input type="file"> -> text, no server involved.
+
+use crate::model::error::GlassBoxError;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlInputElement;
+
+// Text of the first file chosen in an <input type="file">, None if nothing was picked
+pub async fn read_selected_file(ev: &web_sys::Event) -> Result<Option<(String, String)>, GlassBoxError> {
+    let input: HtmlInputElement = ev
+        .target()
+        .and_then(|t| t.dyn_into().ok())
+        .ok_or(GlassBoxError::Browser("event did not come from an <input>".to_string()))?;
+
+    let Some(file) = input.files().and_then(|files| files.get(0)) else {
+        return Ok(None);
+    };
+
+    let text = JsFuture::from(file.text()).await?;
+    let text = text
+        .as_string()
+        .ok_or(GlassBoxError::Parse(format!("{} is not a text file", file.name())))?;
+    Ok(Some((file.name(), text)))
+}
@@ -1,27 +1,24 @@
 use serde::{Deserialize, Serialize};
 use surrealdb::sql::Thing;
-use crate::model::db::DB; 
+use crate::model::error::GlassBoxError;
+use crate::model::store::{Store, SurrealStore};
+use crate::model::vault::{forget, rekey};
 use bcrypt::{hash, DEFAULT_COST, verify}; 
 
-pub async fn is_email_taken(email:String) -> Result<bool, String> {
-    let db = DB.get().ok_or("Database not initialized")?;
-
-    let sql = "SELECT * FROM user WHERE email = $email";
-    let mut response = db.query(sql).bind(("email", email)).await.map_err(|e| e.to_string())?;
-
-    // SELECT returns a list, so we deserialize into Vec<User>
-    let users: Vec<User> = response.take(0).map_err(|e| e.to_string())?; 
-    Ok(!users.is_empty())
+pub async fn is_email_taken(email: String) -> Result<bool, GlassBoxError> {
+    is_email_taken_with(&SurrealStore, email).await
 }
 
-pub async fn is_username_taken(username: String) -> Result<bool, String> {
-    let db = DB.get().ok_or("Database not initialized")?;
+pub async fn is_email_taken_with<S: Store>(store: &S, email: String) -> Result<bool, GlassBoxError> {
+    Ok(store.find_user_by_email(&email).await?.is_some())
+}
 
-    let sql = "SELECT * FROM user WHERE username = $username";
-    let mut response = db.query(sql).bind(("username", username)).await.map_err(|e| e.to_string())?;
+pub async fn is_username_taken(username: String) -> Result<bool, GlassBoxError> {
+    is_username_taken_with(&SurrealStore, username).await
+}
 
-    let users: Vec<User> = response.take(0).map_err(|e| e.to_string())?;
-    Ok(!users.is_empty())
+pub async fn is_username_taken_with<S: Store>(store: &S, username: String) -> Result<bool, GlassBoxError> {
+    Ok(store.find_user_by_username(&username).await?.is_some())
 }
 
 
@@ -35,11 +32,24 @@ pub struct User {
 }
 
 // Function to create a new user in the local database. wirh secure HASHED password.
-pub async fn create_new_user(username: String, email: String, raw_password: String) -> Result<User, String> {
-    let db = DB.get().ok_or("Database not initialized")?;
+pub async fn create_new_user(username: String, email: String, raw_password: String) -> Result<User, GlassBoxError> {
+    create_new_user_with(&SurrealStore, username, email, raw_password).await
+}
+
+pub async fn create_new_user_with<S: Store>(store: &S, username: String, email: String, raw_password: String) -> Result<User, GlassBoxError> {
+    // The form checks these as you type; this catches a race or a skipped check
+    if username.trim().is_empty() || email.trim().is_empty() || raw_password.is_empty() {
+        return Err(GlassBoxError::Validation("Username, email and password are all required".to_string()));
+    }
+    if is_username_taken_with(store, username.clone()).await? {
+        return Err(GlassBoxError::Validation("This username already exists.".to_string()));
+    }
+    if is_email_taken_with(store, email.clone()).await? {
+        return Err(GlassBoxError::Validation("This email already exists.".to_string()));
+    }
 
     // Hash password
-    let hashed_password = hash(raw_password, DEFAULT_COST).map_err(|e| e.to_string())?;
+    let hashed_password = hash(raw_password, DEFAULT_COST)?;
     
     let new_user = User {
         id: None, // SurrealDB generates ID
@@ -49,32 +59,26 @@ pub async fn create_new_user(username: String, email: String, raw_password: Stri
         password: hashed_password
     };
 
-    // Create user in DB
-    // We use "create" to ensure we get a return value. 
     // Table is "user". Content is the struct.
-    let created: Option<User> = db.create("user").content(new_user).await.map_err(|e| e.to_string())?;
-    
-    created.ok_or("Failed to create user".to_string())
+    store.create_user(new_user).await
+}
+
+pub async fn login_user(username: String, password: String) -> Result<User, GlassBoxError> {
+    login_user_with(&SurrealStore, username, password).await
 }
 
-pub async fn login_user(username: String, password: String) -> Result<User, String> {
+pub async fn login_user_with<S: Store>(store: &S, username: String, password: String) -> Result<User, GlassBoxError> {
     leptos::logging::log!("LOGIN ATTEMPT: Starting for user '{}'", username);
-    
-    let db = DB.get().ok_or("Database not initialized")?;
-    leptos::logging::log!("DB ACQUIRED");
 
-    let sql = "SELECT * FROM user WHERE username = $username";
     leptos::logging::log!("QUERYING USER...");
-    let mut response = db.query(sql).bind(("username", username)).await.map_err(|e| e.to_string())?;
-
-    let users: Vec<User> = response.take(0).map_err(|e| e.to_string())?;
-    leptos::logging::log!("QUERY COMPLETE. Users found: {}", users.len());
+    let found = store.find_user_by_username(&username).await?;
+    leptos::logging::log!("QUERY COMPLETE. User found: {}", found.is_some());
     
-    if let Some(user) = users.first() {
+    if let Some(user) = found {
         leptos::logging::log!("VERIFYING PASSWORD...");
-        if verify(&password, &user.password).map_err(|e| e.to_string())? {
+        if verify(&password, &user.password)? {
             leptos::logging::log!("LOGIN SUCCESS");
-            return Ok(user.clone());
+            return Ok(user);
         } else {
              leptos::logging::log!("PASSWORD MISMATCH");
         }
@@ -82,5 +86,148 @@ pub async fn login_user(username: String, password: String) -> Result<User, Stri
          leptos::logging::log!("USER NOT FOUND");
     }
     
-    Err("Invalid username or password".to_string())
+    Err(GlassBoxError::AuthFailed)
+}
+
+// Vault-backed only: the history is re-encrypted under the new password
+pub async fn change_password(user_id: Thing, old_password: String, new_password: String) -> Result<(), GlassBoxError> {
+    let user = SurrealStore
+        .find_user(&user_id)
+        .await?
+        .ok_or(GlassBoxError::NotFound("Your account".to_string()))?;
+
+    if !verify(&old_password, &user.password)? {
+        return Err(GlassBoxError::AuthFailed);
+    }
+    if new_password.is_empty() {
+        return Err(GlassBoxError::Validation("The new password can't be empty".to_string()));
+    }
+
+    let new_hash = hash(&new_password, DEFAULT_COST)?;
+    rekey(&user, &new_password, new_hash).await?;
+    leptos::logging::log!("PASSWORD: Changed and vault re-encrypted for '{}'", user.username);
+    Ok(())
+}
+
+// What delete_account removed, shown to the user afterwards
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct DeletionReport {
+    pub username: String,
+    pub library: usize,
+    pub interactions: usize,
+    pub searches: usize,
+    pub ratings: usize,
+    pub watchlist: usize,
+    pub progress: usize,
+    pub lists: usize,
+    pub notes: usize,
+    pub settings: bool,
+}
+
+pub async fn delete_account(user_id: Thing, password: String) -> Result<DeletionReport, GlassBoxError> {
+    let report = delete_account_with(&SurrealStore, user_id.clone(), password).await?;
+    // Committed: nothing after this may fail and hide the report
+    forget(&user_id);
+    Ok(report)
+}
+
+// Checks the password, then removes the user and everything keyed to them,
+// their library included, in one transaction (Store::delete_account)
+pub async fn delete_account_with<S: Store>(store: &S, user_id: Thing, password: String) -> Result<DeletionReport, GlassBoxError> {
+    let user = store
+        .find_user(&user_id)
+        .await?
+        .ok_or(GlassBoxError::NotFound("Your account".to_string()))?;
+    if !verify(&password, &user.password)? {
+        return Err(GlassBoxError::AuthFailed);
+    }
+
+    let report = DeletionReport {
+        username: user.username.clone(),
+        ..store.delete_account(&user_id).await?
+    };
+    leptos::logging::log!("ACCOUNT: Deleted '{}' ({:?})", user.username, report);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::store::MemoryStore;
+    use futures::executor::block_on;
+
+    fn signup(store: &MemoryStore, username: &str, email: &str, password: &str) -> Result<User, GlassBoxError> {
+        block_on(create_new_user_with(store, username.to_string(), email.to_string(), password.to_string()))
+    }
+
+    #[test]
+    fn signup_rejects_taken_names_and_emails() {
+        let store = MemoryStore::new();
+        signup(&store, "ann", "ann@example.org", "hunter2").unwrap();
+
+        let same_name = signup(&store, "ann", "other@example.org", "hunter2");
+        assert!(matches!(same_name, Err(GlassBoxError::Validation(_))));
+        let same_email = signup(&store, "bob", "ann@example.org", "hunter2");
+        assert!(matches!(same_email, Err(GlassBoxError::Validation(_))));
+    }
+
+    #[test]
+    fn signup_requires_every_field() {
+        let store = MemoryStore::new();
+        assert!(matches!(signup(&store, "ann", "ann@example.org", ""), Err(GlassBoxError::Validation(_))));
+        assert!(matches!(signup(&store, " ", "ann@example.org", "hunter2"), Err(GlassBoxError::Validation(_))));
+    }
+
+    #[test]
+    fn login_with_a_corrupt_hash_is_not_a_wrong_password() {
+        let store = MemoryStore::new();
+        block_on(store.create_user(User {
+            id: None,
+            email: "ann@example.org".to_string(),
+            username: "ann".to_string(),
+            joined_at: chrono::Utc::now().to_rfc3339(),
+            password: "not a bcrypt hash".to_string(),
+        }))
+        .unwrap();
+
+        let result = block_on(login_user_with(&store, "ann".to_string(), "hunter2".to_string()));
+        assert!(matches!(result, Err(GlassBoxError::Crypto(_))));
+    }
+
+    #[test]
+    fn deleting_an_account_counts_what_it_removed() {
+        use crate::model::history::save_interaction_with;
+        use crate::model::video::{save_video_with, Video};
+
+        let store = MemoryStore::new();
+        let ann = signup(&store, "ann", "ann@example.org", "hunter2").unwrap().id.unwrap();
+        let bob = signup(&store, "bob", "bob@example.org", "hunter2").unwrap().id.unwrap();
+        let show = Video {
+            id: None,
+            video_id: "1".to_string(),
+            title: "Severance".to_string(),
+            description: String::new(),
+            thumbnail_url: String::new(),
+            rating: 8.0,
+            genres: vec!["Drama".to_string()],
+            channel_name: String::new(),
+            related_ids: Vec::new(),
+            saved_at: chrono::Utc::now(),
+        };
+        for user in [&ann, &bob] {
+            block_on(save_video_with(&store, user.clone(), show.clone())).unwrap();
+            block_on(save_interaction_with(&store, user.clone(), show.clone(), "click".to_string())).unwrap();
+        }
+
+        let wrong = block_on(delete_account_with(&store, ann.clone(), "nope".to_string()));
+        assert_eq!(wrong.unwrap_err(), GlassBoxError::AuthFailed);
+        assert_eq!(block_on(store.library(&ann)).unwrap().len(), 1);
+
+        let report = block_on(delete_account_with(&store, ann.clone(), "hunter2".to_string())).unwrap();
+        assert_eq!((report.username.as_str(), report.library, report.interactions), ("ann", 1, 1));
+        assert!(block_on(store.find_user(&ann)).unwrap().is_none());
+        assert!(block_on(store.library(&ann)).unwrap().is_empty());
+        assert_eq!(block_on(store.library(&bob)).unwrap().len(), 1);
+        assert_eq!(block_on(store.interactions()).unwrap().len(), 1);
+    }
 }
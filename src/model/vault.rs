@@ -0,0 +1,866 @@
+// <------- Per-user encryption of the vault ------->
+// Each account gets a random salt; PBKDF2(password, salt) gives the AES key,
+// which is only ever held in this tab's memory (KEYS) between login and logout.
+//
+// Sealed: interaction video_id/video_title/interaction_type, search queries,
+// rating, watchlist and episode_progress video_id/video_title, and the ticked
+// episode IDs (sealed together as one comma-joined value), list names,
+// descriptions and every item of a user_list, title_note video_id/video_title,
+// text and tags (one comma-joined value), and each library_item's whole show (as
+// JSON, related_ids and co-visits included). Left readable: the user/timestamp/saved_at columns the
+// queries filter and sort on, the watch state itself, and the shared TVMaze caches (person,
+// trending, episode_list tables), which are public catalogue data rather than anyone's history.
+//
+// Once an account has a vault record, every row it owns is sealed: a plaintext
+// row under its name was not written by GlassBox and is refused when opened.
+//
+// Tables with one row per (user, show) (rating, watchlist, episode_progress,
+// title_note, library_item) take their record ID from an HMAC of the show under
+// a per-account secret, so a save can go straight to its row without opening
+// the others, and the ID still doesn't reveal the show.
+
+use crate::model::crypto::{
+    decode_bytes, decrypt, derive_key, encode_bytes, encrypt, hmac_hex, import_hmac_key, is_sealed, random_bytes, to_hex, ROW_SECRET_LEN,
+    SALT_LEN,
+};
+use crate::model::db::db_ready;
+use crate::model::store::{Store, SurrealStore};
+use crate::model::episodes::EpisodeProgress;
+use crate::model::error::GlassBoxError;
+use crate::model::history::{InteractionEntry, SearchEntry};
+use crate::model::lists::{ListItem, UserList};
+use crate::model::notes::TitleNote;
+use crate::model::ratings::Rating;
+use crate::model::users::User;
+use crate::model::video::{LibraryItem, Video};
+use crate::model::watchlist::WatchEntry;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use surrealdb::sql::Thing;
+use web_sys::CryptoKey;
+
+// Decrypting this proves the derived key is the right one
+const CHECK_PLAINTEXT: &str = "glassbox-vault";
+
+// pub(crate) only so backup.rs can copy the rows as they are
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct VaultMeta {
+    id: Option<Thing>,
+    user: Thing,
+    salt: String,  // base64
+    check: String, // CHECK_PLAINTEXT sealed with the current key
+    // True once this account's shows moved out of the shared video table
+    #[serde(default)]
+    library: bool,
+    // Random HMAC secret for row IDs, sealed with the key (None before it existed)
+    #[serde(default)]
+    row_secret: Option<String>,
+}
+
+// Every sealed row one user owns, as read from or written to IndexedDB
+struct UserRows {
+    interactions: Vec<InteractionEntry>,
+    searches: Vec<SearchEntry>,
+    ratings: Vec<Rating>,
+    watchlist: Vec<WatchEntry>,
+    progress: Vec<EpisodeProgress>,
+    lists: Vec<UserList>,
+    notes: Vec<TitleNote>,
+    library: Vec<LibraryItem>,
+}
+
+thread_local! {
+    // user Thing (as string) -> AES key for this session
+    static KEYS: RefCell<HashMap<String, CryptoKey>> = RefCell::new(HashMap::new());
+    // user Thing (as string) -> HMAC key that derives its row IDs
+    static ROW_KEYS: RefCell<HashMap<String, CryptoKey>> = RefCell::new(HashMap::new());
+    // Accounts with a vault record (user Thing as string), read on first use
+    static SEALED: RefCell<Option<HashSet<String>>> = const { RefCell::new(None) };
+}
+
+fn key_for(user: &Thing) -> Option<CryptoKey> {
+    KEYS.with(|k| k.borrow().get(&user.to_string()).cloned())
+}
+
+fn require_key(user: &Thing) -> Result<CryptoKey, GlassBoxError> {
+    key_for(user).ok_or(GlassBoxError::VaultLocked)
+}
+
+fn require_row_key(user: &Thing) -> Result<CryptoKey, GlassBoxError> {
+    ROW_KEYS
+        .with(|k| k.borrow().get(&user.to_string()).cloned())
+        .ok_or(GlassBoxError::VaultLocked)
+}
+
+pub fn is_unlocked(user_id: &str) -> bool {
+    KEYS.with(|k| k.borrow().contains_key(user_id))
+}
+
+// Forget every key (logout, and after a restore replaced the vault table)
+pub fn lock() {
+    KEYS.with(|k| k.borrow_mut().clear());
+    ROW_KEYS.with(|k| k.borrow_mut().clear());
+    SEALED.with(|s| *s.borrow_mut() = None);
+}
+
+// Account deletion: drop the in-memory keys (Store::delete_account already
+// removed the vault record in its transaction)
+pub fn forget(user_id: &Thing) {
+    KEYS.with(|k| k.borrow_mut().remove(&user_id.to_string()));
+    ROW_KEYS.with(|k| k.borrow_mut().remove(&user_id.to_string()));
+    SEALED.with(|s| *s.borrow_mut() = None);
+}
+
+fn mark_sealed(user_id: &Thing) {
+    SEALED.with(|s| {
+        if let Some(sealed) = s.borrow_mut().as_mut() {
+            sealed.insert(user_id.to_string());
+        }
+    });
+}
+
+// Plaintext is only expected from accounts the unlock migration hasn't reached
+// yet. If the vault table can't be read, nothing unsealed gets through.
+async fn accepts_plaintext(user: &Thing) -> bool {
+    if key_for(user).is_some() {
+        return false;
+    }
+    if let Some(sealed) = SEALED.with(|s| s.borrow().as_ref().map(|set| set.contains(&user.to_string()))) {
+        return !sealed;
+    }
+    let loaded: Result<Vec<Thing>, GlassBoxError> = async {
+        let db = db_ready().await?;
+        Ok(db.query("SELECT VALUE user FROM vault").await?.take(0)?)
+    }
+    .await;
+    match loaded {
+        Ok(users) => {
+            let sealed: HashSet<String> = users.iter().map(|u| u.to_string()).collect();
+            let accepted = !sealed.contains(&user.to_string());
+            SEALED.with(|s| *s.borrow_mut() = Some(sealed));
+            accepted
+        }
+        Err(e) => {
+            leptos::logging::error!("VAULT: Could not read the vault table: {:?}", e);
+            false
+        }
+    }
+}
+
+async fn plaintext_allowed(kind: &str, user: &Thing) -> bool {
+    let allowed = accepts_plaintext(user).await;
+    if !allowed {
+        leptos::logging::error!("VAULT: Refused an unsealed {} stored under sealed account {}", kind, user);
+    }
+    allowed
+}
+
+fn user_thing(user: &User) -> Result<Thing, GlassBoxError> {
+    user.id.clone().ok_or(GlassBoxError::NotFound("Your account".to_string()))
+}
+
+// Called right after the password was verified. The first unlock of an
+// account also seals everything it wrote before encryption existed.
+pub async fn unlock(user: &User, password: &str) -> Result<(), GlassBoxError> {
+    let db = db_ready().await?;
+    let user_id = user_thing(user)?;
+
+    let meta: Option<VaultMeta> = db.select(("vault", user_id.id.to_raw())).await?;
+    match meta {
+        Some(meta) => {
+            let key = derive_key(password, &decode_bytes(&meta.salt)?).await?;
+            match decrypt(&key, &meta.check).await {
+                Ok(check) if check == CHECK_PLAINTEXT => {}
+                _ => return Err(GlassBoxError::AuthFailed),
+            }
+            let meta = match &meta.row_secret {
+                Some(sealed) => {
+                    let row_key = import_hmac_key(&decode_bytes(&decrypt(&key, sealed).await?)?).await?;
+                    ROW_KEYS.with(|k| k.borrow_mut().insert(user_id.to_string(), row_key));
+                    meta
+                }
+                None => add_row_secret(&user_id, &key, meta).await?,
+            };
+            KEYS.with(|k| k.borrow_mut().insert(user_id.to_string(), key.clone()));
+            if !meta.library {
+                move_library(&user_id, &key, meta).await;
+            }
+        }
+        None => {
+            let salt = random_bytes(SALT_LEN)?;
+            let key = derive_key(password, &salt).await?;
+            let secret = random_bytes(ROW_SECRET_LEN)?;
+            let row_key = import_hmac_key(&secret).await?;
+            let meta = VaultMeta {
+                id: None,
+                user: user_id.clone(),
+                salt: encode_bytes(&salt),
+                check: encrypt(&key, CHECK_PLAINTEXT).await?,
+                library: false,
+                row_secret: Some(encrypt(&key, &encode_bytes(&secret)).await?),
+            };
+
+            let rows = reseal(None, &key, &row_key, load_rows(&user_id).await?).await?;
+            write_sealed(&user_id, rows, meta.clone(), None).await?;
+
+            KEYS.with(|k| k.borrow_mut().insert(user_id.to_string(), key.clone()));
+            ROW_KEYS.with(|k| k.borrow_mut().insert(user_id.to_string(), row_key));
+            mark_sealed(&user_id);
+            leptos::logging::log!("VAULT: Sealed existing history for {}", user.username);
+            move_library(&user_id, &key, meta).await;
+        }
+    }
+    Ok(())
+}
+
+// Vaults sealed before row IDs were derived: give the account its secret and
+// move every keyed row to its derived ID, in one transaction
+async fn add_row_secret(user_id: &Thing, key: &CryptoKey, meta: VaultMeta) -> Result<VaultMeta, GlassBoxError> {
+    let secret = random_bytes(ROW_SECRET_LEN)?;
+    let row_key = import_hmac_key(&secret).await?;
+    let meta = VaultMeta {
+        row_secret: Some(encrypt(key, &encode_bytes(&secret)).await?),
+        ..meta
+    };
+
+    let rows = reseal(Some(key), key, &row_key, load_rows(user_id).await?).await?;
+    write_sealed(user_id, rows, meta.clone(), None).await?;
+
+    ROW_KEYS.with(|k| k.borrow_mut().insert(user_id.to_string(), row_key));
+    Ok(meta)
+}
+
+// Password change: every sealed row is re-encrypted under a key from the new
+// password and a fresh salt, in the same transaction as the new bcrypt hash.
+pub async fn rekey(user: &User, new_password: &str, new_hash: String) -> Result<(), GlassBoxError> {
+    let user_id = user_thing(user)?;
+    let old_key = require_key(&user_id)?;
+
+    let row_key = require_row_key(&user_id)?;
+
+    // The row secret stays the same (so do the row IDs); only its seal changes
+    let db = db_ready().await?;
+    let old_meta: Option<VaultMeta> = db.select(("vault", user_id.id.to_raw())).await?;
+    let secret = match old_meta.and_then(|m| m.row_secret) {
+        Some(sealed) => decrypt(&old_key, &sealed).await?,
+        None => return Err(GlassBoxError::VaultLocked),
+    };
+
+    let salt = random_bytes(SALT_LEN)?;
+    let new_key = derive_key(new_password, &salt).await?;
+    let meta = VaultMeta {
+        id: None,
+        user: user_id.clone(),
+        salt: encode_bytes(&salt),
+        check: encrypt(&new_key, CHECK_PLAINTEXT).await?,
+        library: true,
+        row_secret: Some(encrypt(&new_key, &secret).await?),
+    };
+
+    let rows = reseal(Some(&old_key), &new_key, &row_key, load_rows(&user_id).await?).await?;
+    write_sealed(&user_id, rows, meta, Some(new_hash)).await?;
+
+    KEYS.with(|k| k.borrow_mut().insert(user_id.to_string(), new_key));
+    Ok(())
+}
+
+// Raw rows straight from IndexedDB, sealed or not
+async fn load_rows(user_id: &Thing) -> Result<UserRows, GlassBoxError> {
+    let db = db_ready().await?;
+    let mut response = db
+        .query("SELECT * FROM interaction WHERE user = $user")
+        .query("SELECT * FROM search_history WHERE user = $user")
+        .query("SELECT * FROM rating WHERE user = $user")
+        .query("SELECT * FROM watchlist WHERE user = $user")
+        .query("SELECT * FROM episode_progress WHERE user = $user")
+        .query("SELECT * FROM user_list WHERE user = $user")
+        .query("SELECT * FROM title_note WHERE user = $user")
+        .query("SELECT * FROM library_item WHERE user = $user")
+        .bind(("user", user_id.clone()))
+        .await?;
+    Ok(UserRows {
+        interactions: response.take(0)?,
+        searches: response.take(1)?,
+        ratings: response.take(2)?,
+        watchlist: response.take(3)?,
+        progress: response.take(4)?,
+        lists: response.take(5)?,
+        notes: response.take(6)?,
+        library: response.take(7)?,
+    })
+}
+
+async fn reseal(old_key: Option<&CryptoKey>, new_key: &CryptoKey, row_key: &CryptoKey, rows: UserRows) -> Result<UserRows, GlassBoxError> {
+    let mut out_i = Vec::with_capacity(rows.interactions.len());
+    for entry in rows.interactions {
+        let entry = match old_key {
+            Some(k) => open_interaction(k, entry).await?,
+            None => entry,
+        };
+        out_i.push(seal_interaction_with(new_key, entry).await?);
+    }
+
+    let mut out_s = Vec::with_capacity(rows.searches.len());
+    for entry in rows.searches {
+        let entry = match old_key {
+            Some(k) => open_search(k, entry).await?,
+            None => entry,
+        };
+        out_s.push(seal_search_with(new_key, entry).await?);
+    }
+
+    let mut out_r = Vec::with_capacity(rows.ratings.len());
+    for rating in rows.ratings {
+        let rating = match old_key {
+            Some(k) => open_rating(k, rating).await?,
+            None => rating,
+        };
+        // Pre-encryption rating IDs were "<user>_<video_id>": every keyed row gets its derived one
+        let rating = Rating { id: Some(row_id_with(row_key, "rating", &rating.video_id).await?), ..rating };
+        out_r.push(seal_rating_with(new_key, rating).await?);
+    }
+
+    let mut out_w = Vec::with_capacity(rows.watchlist.len());
+    for entry in rows.watchlist {
+        let entry = match old_key {
+            Some(k) => open_watch(k, entry).await?,
+            None => entry,
+        };
+        let entry = WatchEntry { id: Some(row_id_with(row_key, "watchlist", &entry.video_id).await?), ..entry };
+        out_w.push(seal_watch_with(new_key, entry).await?);
+    }
+
+    let mut out_p = Vec::with_capacity(rows.progress.len());
+    for progress in rows.progress {
+        let progress = match old_key {
+            Some(k) => open_one_progress(k, progress).await?,
+            None => progress,
+        };
+        let progress = EpisodeProgress { id: Some(row_id_with(row_key, "episode_progress", &progress.video_id).await?), ..progress };
+        out_p.push(seal_progress_with(new_key, progress).await?);
+    }
+
+    let mut out_l = Vec::with_capacity(rows.lists.len());
+    for list in rows.lists {
+        let list = match old_key {
+            Some(k) => open_list(k, list).await?,
+            None => list,
+        };
+        out_l.push(seal_list_with(new_key, list).await?);
+    }
+
+    let mut out_n = Vec::with_capacity(rows.notes.len());
+    for note in rows.notes {
+        let note = match old_key {
+            Some(k) => open_note(k, note).await?,
+            None => note,
+        };
+        let note = TitleNote { id: Some(row_id_with(row_key, "title_note", &note.video_id).await?), ..note };
+        out_n.push(seal_note_with(new_key, note).await?);
+    }
+
+    let mut out_v = Vec::with_capacity(rows.library.len());
+    for item in rows.library {
+        let item = match old_key {
+            Some(k) => open_library_item(k, item).await?,
+            None => item,
+        };
+        let video_id = item.to_video()?.video_id;
+        let item = LibraryItem { id: Some(row_id_with(row_key, "library_item", &video_id).await?), ..item };
+        out_v.push(seal_library_item_with(new_key, item).await?);
+    }
+
+    Ok(UserRows {
+        interactions: out_i,
+        searches: out_s,
+        ratings: out_r,
+        watchlist: out_w,
+        progress: out_p,
+        lists: out_l,
+        notes: out_n,
+        library: out_v,
+    })
+}
+
+// All-or-nothing: a half re-encrypted vault would be unreadable with either password
+async fn write_sealed(user_id: &Thing, rows: UserRows, meta: VaultMeta, password_hash: Option<String>) -> Result<(), GlassBoxError> {
+    let db = db_ready().await?;
+    let password_sql = if password_hash.is_some() {
+        "UPDATE $user SET password = $password;"
+    } else {
+        ""
+    };
+    let sql = format!(
+        "BEGIN TRANSACTION;
+         FOR $row IN $interactions {{ UPSERT $row.id CONTENT $row; }};
+         FOR $row IN $searches {{ UPSERT $row.id CONTENT $row; }};
+         DELETE rating WHERE user = $user; DELETE watchlist WHERE user = $user;
+         DELETE episode_progress WHERE user = $user; DELETE title_note WHERE user = $user;
+         DELETE library_item WHERE user = $user;
+         FOR $row IN $ratings {{ UPSERT $row.id CONTENT $row; }};
+         FOR $row IN $watchlist {{ UPSERT $row.id CONTENT $row; }};
+         FOR $row IN $progress {{ UPSERT $row.id CONTENT $row; }};
+         FOR $row IN $lists {{ UPSERT $row.id CONTENT $row; }};
+         FOR $row IN $notes {{ UPSERT $row.id CONTENT $row; }};
+         FOR $row IN $library {{ UPSERT $row.id CONTENT $row; }};
+         UPSERT $vault CONTENT $meta;
+         {}
+         COMMIT TRANSACTION;",
+        password_sql
+    );
+
+    db.query(sql)
+        .bind(("user", user_id.clone()))
+        .bind(("interactions", rows.interactions))
+        .bind(("searches", rows.searches))
+        .bind(("ratings", rows.ratings))
+        .bind(("watchlist", rows.watchlist))
+        .bind(("progress", rows.progress))
+        .bind(("lists", rows.lists))
+        .bind(("notes", rows.notes))
+        .bind(("library", rows.library))
+        .bind(("vault", Thing::from(("vault", user_id.id.to_raw().as_str()))))
+        .bind(("meta", meta))
+        .bind(("password", password_hash.unwrap_or_default()))
+        .await?
+        .check()?;
+    Ok(())
+}
+
+// <------- Moving a library out of the shared video table ------->
+// Before v11 every account's shows sat in one plaintext `video` table. On unlock
+// the shows this account's rows point at are sealed into its own library_item
+// rows; the shared table is dropped once no account is left to move. A failure
+// is only logged: the flag stays unset and the next unlock tries again.
+async fn move_library(user_id: &Thing, key: &CryptoKey, meta: VaultMeta) {
+    match move_library_rows(user_id, key, meta).await {
+        Ok(0) => {}
+        Ok(moved) => leptos::logging::log!("VAULT: Moved {} shows into {}'s own library", moved, user_id),
+        Err(e) => leptos::logging::error!("VAULT: Could not move the library of {}: {:?}", user_id, e),
+    }
+}
+
+async fn move_library_rows(user_id: &Thing, key: &CryptoKey, meta: VaultMeta) -> Result<usize, GlassBoxError> {
+    let db = db_ready().await?;
+    let store = SurrealStore;
+    let row_key = require_row_key(user_id)?;
+
+    let mut mine: HashSet<String> = store.user_interactions(user_id).await?.into_iter().map(|i| i.video_id).collect();
+    mine.extend(store.ratings(user_id).await?.into_iter().map(|r| r.video_id));
+    mine.extend(store.watchlist(user_id).await?.into_iter().map(|e| e.video_id));
+    mine.extend(store.progress(user_id).await?.into_iter().map(|p| p.video_id));
+    mine.extend(store.lists(user_id).await?.into_iter().flat_map(|l| l.items.into_iter().map(|i| i.video_id)));
+    mine.extend(store.notes(user_id).await?.into_iter().map(|n| n.video_id));
+    let saved: HashSet<String> = store.library(user_id).await?.into_iter().map(|v| v.video_id).collect();
+
+    let legacy: Vec<Video> = db.select("video").await?;
+    let mut items = Vec::new();
+    for video in legacy.iter().filter(|v| mine.contains(&v.video_id) && !saved.contains(&v.video_id)) {
+        let id = row_id_with(&row_key, "library_item", &video.video_id).await?;
+        let item = LibraryItem::new(id, user_id.clone(), video)?;
+        items.push(seal_library_item_with(key, item).await?);
+    }
+    let moved = items.len();
+
+    db.query(
+        "BEGIN TRANSACTION;
+         FOR $row IN $items { UPSERT $row.id CONTENT $row; };
+         UPSERT $vault CONTENT $meta;
+         LET $moved = (SELECT VALUE user FROM vault WHERE library = true);
+         IF array::len((SELECT VALUE id FROM user WHERE id NOTINSIDE $moved)) = 0 { DELETE video; };
+         COMMIT TRANSACTION;",
+    )
+    .bind(("items", items))
+    .bind(("vault", Thing::from(("vault", user_id.id.to_raw().as_str()))))
+    .bind(("meta", VaultMeta { library: true, ..meta }))
+    .await?
+    .check()?;
+    Ok(moved)
+}
+
+// Random ID for rows that aren't keyed by a show (interactions, searches, lists)
+pub fn new_record_id(table: &str) -> Result<Thing, GlassBoxError> {
+    Ok(Thing::from((table, to_hex(&random_bytes(10)?).as_str())))
+}
+
+// The record ID of this user's row for a show in a (user, show) table: the
+// same show always lands on the same row, and only the account can tell which
+pub async fn row_id(table: &str, user: &Thing, video_id: &str) -> Result<Thing, GlassBoxError> {
+    row_id_with(&require_row_key(user)?, table, video_id).await
+}
+
+async fn row_id_with(row_key: &CryptoKey, table: &str, video_id: &str) -> Result<Thing, GlassBoxError> {
+    let mac = hmac_hex(row_key, &format!("{}:{}", table, video_id)).await?;
+    Ok(Thing::from((table, &mac[..20])))
+}
+
+// <------- Row sealing (used by SurrealStore) ------->
+
+pub async fn seal_interaction(entry: InteractionEntry) -> Result<InteractionEntry, GlassBoxError> {
+    let key = require_key(&entry.user)?;
+    seal_interaction_with(&key, entry).await
+}
+
+async fn seal_interaction_with(key: &CryptoKey, entry: InteractionEntry) -> Result<InteractionEntry, GlassBoxError> {
+    Ok(InteractionEntry {
+        video_id: encrypt(key, &entry.video_id).await?,
+        video_title: encrypt(key, &entry.video_title).await?,
+        interaction_type: encrypt(key, &entry.interaction_type).await?,
+        ..entry
+    })
+}
+
+async fn open_interaction(key: &CryptoKey, entry: InteractionEntry) -> Result<InteractionEntry, GlassBoxError> {
+    Ok(InteractionEntry {
+        video_id: decrypt(key, &entry.video_id).await?,
+        video_title: decrypt(key, &entry.video_title).await?,
+        interaction_type: decrypt(key, &entry.interaction_type).await?,
+        ..entry
+    })
+}
+
+// Rows of locked accounts are skipped; plaintext only passes for accounts not sealed yet
+pub async fn open_interactions(entries: Vec<InteractionEntry>) -> Vec<InteractionEntry> {
+    let mut out = Vec::with_capacity(entries.len());
+    for entry in entries {
+        if !is_sealed(&entry.video_id) {
+            if plaintext_allowed("interaction", &entry.user).await {
+                out.push(entry);
+            }
+            continue;
+        }
+        let Some(key) = key_for(&entry.user) else {
+            continue;
+        };
+        match open_interaction(&key, entry).await {
+            Ok(opened) => out.push(opened),
+            Err(e) => leptos::logging::error!("VAULT: Could not open interaction: {:?}", e),
+        }
+    }
+    out
+}
+
+pub async fn seal_search(entry: SearchEntry) -> Result<SearchEntry, GlassBoxError> {
+    let key = require_key(&entry.user)?;
+    seal_search_with(&key, entry).await
+}
+
+async fn seal_search_with(key: &CryptoKey, entry: SearchEntry) -> Result<SearchEntry, GlassBoxError> {
+    Ok(SearchEntry {
+        query: encrypt(key, &entry.query).await?,
+        ..entry
+    })
+}
+
+async fn open_search(key: &CryptoKey, entry: SearchEntry) -> Result<SearchEntry, GlassBoxError> {
+    Ok(SearchEntry {
+        query: decrypt(key, &entry.query).await?,
+        ..entry
+    })
+}
+
+pub async fn open_searches(entries: Vec<SearchEntry>) -> Vec<SearchEntry> {
+    let mut out = Vec::with_capacity(entries.len());
+    for entry in entries {
+        if !is_sealed(&entry.query) {
+            if plaintext_allowed("search", &entry.user).await {
+                out.push(entry);
+            }
+            continue;
+        }
+        let Some(key) = key_for(&entry.user) else {
+            continue;
+        };
+        match open_search(&key, entry).await {
+            Ok(opened) => out.push(opened),
+            Err(e) => leptos::logging::error!("VAULT: Could not open search: {:?}", e),
+        }
+    }
+    out
+}
+
+pub async fn seal_rating(rating: Rating) -> Result<Rating, GlassBoxError> {
+    let key = require_key(&rating.user)?;
+    seal_rating_with(&key, rating).await
+}
+
+async fn seal_rating_with(key: &CryptoKey, rating: Rating) -> Result<Rating, GlassBoxError> {
+    Ok(Rating {
+        video_id: encrypt(key, &rating.video_id).await?,
+        video_title: encrypt(key, &rating.video_title).await?,
+        ..rating
+    })
+}
+
+async fn open_rating(key: &CryptoKey, rating: Rating) -> Result<Rating, GlassBoxError> {
+    Ok(Rating {
+        video_id: decrypt(key, &rating.video_id).await?,
+        video_title: decrypt(key, &rating.video_title).await?,
+        ..rating
+    })
+}
+
+pub async fn open_ratings(ratings: Vec<Rating>) -> Vec<Rating> {
+    let mut out = Vec::with_capacity(ratings.len());
+    for rating in ratings {
+        if !is_sealed(&rating.video_id) {
+            if plaintext_allowed("rating", &rating.user).await {
+                out.push(rating);
+            }
+            continue;
+        }
+        let Some(key) = key_for(&rating.user) else {
+            continue;
+        };
+        match open_rating(&key, rating).await {
+            Ok(opened) => out.push(opened),
+            Err(e) => leptos::logging::error!("VAULT: Could not open rating: {:?}", e),
+        }
+    }
+    out
+}
+
+pub async fn seal_watch(entry: WatchEntry) -> Result<WatchEntry, GlassBoxError> {
+    let key = require_key(&entry.user)?;
+    seal_watch_with(&key, entry).await
+}
+
+async fn seal_watch_with(key: &CryptoKey, entry: WatchEntry) -> Result<WatchEntry, GlassBoxError> {
+    Ok(WatchEntry {
+        video_id: encrypt(key, &entry.video_id).await?,
+        video_title: encrypt(key, &entry.video_title).await?,
+        ..entry
+    })
+}
+
+async fn open_watch(key: &CryptoKey, entry: WatchEntry) -> Result<WatchEntry, GlassBoxError> {
+    Ok(WatchEntry {
+        video_id: decrypt(key, &entry.video_id).await?,
+        video_title: decrypt(key, &entry.video_title).await?,
+        ..entry
+    })
+}
+
+pub async fn open_watchlist(entries: Vec<WatchEntry>) -> Vec<WatchEntry> {
+    let mut out = Vec::with_capacity(entries.len());
+    for entry in entries {
+        if !is_sealed(&entry.video_id) {
+            if plaintext_allowed("watch entry", &entry.user).await {
+                out.push(entry);
+            }
+            continue;
+        }
+        let Some(key) = key_for(&entry.user) else {
+            continue;
+        };
+        match open_watch(&key, entry).await {
+            Ok(opened) => out.push(opened),
+            Err(e) => leptos::logging::error!("VAULT: Could not open watch entry: {:?}", e),
+        }
+    }
+    out
+}
+
+pub async fn seal_progress(progress: EpisodeProgress) -> Result<EpisodeProgress, GlassBoxError> {
+    let key = require_key(&progress.user)?;
+    seal_progress_with(&key, progress).await
+}
+
+// The episode IDs alone would reveal the show, so they are sealed as one value
+async fn seal_progress_with(key: &CryptoKey, progress: EpisodeProgress) -> Result<EpisodeProgress, GlassBoxError> {
+    Ok(EpisodeProgress {
+        video_id: encrypt(key, &progress.video_id).await?,
+        video_title: encrypt(key, &progress.video_title).await?,
+        watched: vec![encrypt(key, &progress.watched.join(",")).await?],
+        ..progress
+    })
+}
+
+async fn open_one_progress(key: &CryptoKey, progress: EpisodeProgress) -> Result<EpisodeProgress, GlassBoxError> {
+    let watched = match progress.watched.as_slice() {
+        [sealed] => decrypt(key, sealed)
+            .await?
+            .split(',')
+            .filter(|id| !id.is_empty())
+            .map(|id| id.to_string())
+            .collect(),
+        _ => return Err(GlassBoxError::Parse("episode IDs are not sealed as one value".to_string())),
+    };
+    Ok(EpisodeProgress {
+        video_id: decrypt(key, &progress.video_id).await?,
+        video_title: decrypt(key, &progress.video_title).await?,
+        watched,
+        ..progress
+    })
+}
+
+pub async fn open_progress(rows: Vec<EpisodeProgress>) -> Vec<EpisodeProgress> {
+    let mut out = Vec::with_capacity(rows.len());
+    for progress in rows {
+        if !is_sealed(&progress.video_id) {
+            if plaintext_allowed("episode progress", &progress.user).await {
+                out.push(progress);
+            }
+            continue;
+        }
+        let Some(key) = key_for(&progress.user) else {
+            continue;
+        };
+        match open_one_progress(&key, progress).await {
+            Ok(opened) => out.push(opened),
+            Err(e) => leptos::logging::error!("VAULT: Could not open episode progress: {:?}", e),
+        }
+    }
+    out
+}
+
+pub async fn seal_list(list: UserList) -> Result<UserList, GlassBoxError> {
+    let key = require_key(&list.user)?;
+    seal_list_with(&key, list).await
+}
+
+async fn seal_list_with(key: &CryptoKey, list: UserList) -> Result<UserList, GlassBoxError> {
+    let mut items = Vec::with_capacity(list.items.len());
+    for item in list.items {
+        items.push(ListItem {
+            video_id: encrypt(key, &item.video_id).await?,
+            video_title: encrypt(key, &item.video_title).await?,
+            note: encrypt(key, &item.note).await?,
+        });
+    }
+    Ok(UserList {
+        name: encrypt(key, &list.name).await?,
+        description: encrypt(key, &list.description).await?,
+        items,
+        ..list
+    })
+}
+
+async fn open_list(key: &CryptoKey, list: UserList) -> Result<UserList, GlassBoxError> {
+    let mut items = Vec::with_capacity(list.items.len());
+    for item in list.items {
+        items.push(ListItem {
+            video_id: decrypt(key, &item.video_id).await?,
+            video_title: decrypt(key, &item.video_title).await?,
+            note: decrypt(key, &item.note).await?,
+        });
+    }
+    Ok(UserList {
+        name: decrypt(key, &list.name).await?,
+        description: decrypt(key, &list.description).await?,
+        items,
+        ..list
+    })
+}
+
+pub async fn open_lists(lists: Vec<UserList>) -> Vec<UserList> {
+    let mut out = Vec::with_capacity(lists.len());
+    for list in lists {
+        if !is_sealed(&list.name) {
+            if plaintext_allowed("list", &list.user).await {
+                out.push(list);
+            }
+            continue;
+        }
+        let Some(key) = key_for(&list.user) else {
+            continue;
+        };
+        match open_list(&key, list).await {
+            Ok(opened) => out.push(opened),
+            Err(e) => leptos::logging::error!("VAULT: Could not open list: {:?}", e),
+        }
+    }
+    out
+}
+
+pub async fn seal_note(note: TitleNote) -> Result<TitleNote, GlassBoxError> {
+    let key = require_key(&note.user)?;
+    seal_note_with(&key, note).await
+}
+
+// Tags are sealed together like episode IDs, so their count doesn't show either
+async fn seal_note_with(key: &CryptoKey, note: TitleNote) -> Result<TitleNote, GlassBoxError> {
+    Ok(TitleNote {
+        video_id: encrypt(key, &note.video_id).await?,
+        video_title: encrypt(key, &note.video_title).await?,
+        text: encrypt(key, &note.text).await?,
+        tags: vec![encrypt(key, &note.tags.join(",")).await?],
+        ..note
+    })
+}
+
+async fn open_note(key: &CryptoKey, note: TitleNote) -> Result<TitleNote, GlassBoxError> {
+    let tags = match note.tags.as_slice() {
+        [sealed] => decrypt(key, sealed)
+            .await?
+            .split(',')
+            .filter(|t| !t.is_empty())
+            .map(|t| t.to_string())
+            .collect(),
+        _ => return Err(GlassBoxError::Parse("tags are not sealed as one value".to_string())),
+    };
+    Ok(TitleNote {
+        video_id: decrypt(key, &note.video_id).await?,
+        video_title: decrypt(key, &note.video_title).await?,
+        text: decrypt(key, &note.text).await?,
+        tags,
+        ..note
+    })
+}
+
+pub async fn open_notes(rows: Vec<TitleNote>) -> Vec<TitleNote> {
+    let mut out = Vec::with_capacity(rows.len());
+    for note in rows {
+        if !is_sealed(&note.video_id) {
+            if plaintext_allowed("note", &note.user).await {
+                out.push(note);
+            }
+            continue;
+        }
+        let Some(key) = key_for(&note.user) else {
+            continue;
+        };
+        match open_note(&key, note).await {
+            Ok(opened) => out.push(opened),
+            Err(e) => leptos::logging::error!("VAULT: Could not open note: {:?}", e),
+        }
+    }
+    out
+}
+
+pub async fn seal_library_item(item: LibraryItem) -> Result<LibraryItem, GlassBoxError> {
+    let key = require_key(&item.user)?;
+    seal_library_item_with(&key, item).await
+}
+
+// The show's metadata is public, but which shows an account saved (and which
+// ones it watched together, in related_ids) is not
+async fn seal_library_item_with(key: &CryptoKey, item: LibraryItem) -> Result<LibraryItem, GlassBoxError> {
+    Ok(LibraryItem {
+        video: encrypt(key, &item.video).await?,
+        ..item
+    })
+}
+
+async fn open_library_item(key: &CryptoKey, item: LibraryItem) -> Result<LibraryItem, GlassBoxError> {
+    Ok(LibraryItem {
+        video: decrypt(key, &item.video).await?,
+        ..item
+    })
+}
+
+// library_item only exists since v11, so every row of it is sealed
+pub async fn open_library(items: Vec<LibraryItem>) -> Vec<LibraryItem> {
+    let mut out = Vec::with_capacity(items.len());
+    for item in items {
+        let Some(key) = key_for(&item.user) else {
+            continue;
+        };
+        match open_library_item(&key, item).await {
+            Ok(opened) => out.push(opened),
+            Err(e) => leptos::logging::error!("VAULT: Could not open library item: {:?}", e),
+        }
+    }
+    out
+}
@@ -1,6 +1,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use surrealdb::sql::Thing;
+use crate::model::error::GlassBoxError;
+use crate::model::store::{Store, SurrealStore};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)] // Added PartialEq for signals
 pub struct Video {
@@ -23,43 +25,54 @@ pub struct Video {
     pub saved_at: DateTime<Utc>,
 }
 
-// --- DATABASE SAVE FUNCTION ---
-pub async fn save_video(video: Video) -> Result<Video, String> {
-    leptos::logging::log!("SAVE_VIDEO: Attempting to save video: {}", video.title);
-
-    let db = crate::model::db::DB.get().ok_or_else(|| {
-        leptos::logging::error!("SAVE_VIDEO: Database not loaded!");
-        "Database not loaded".to_string()
-    })?;
+// One show in one user's library, as stored. The vault seals the whole Video
+// (JSON) into `video`, so the row only says which account saved something and when.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LibraryItem {
+    pub id: Option<Thing>,
+    pub user: Thing,
+    pub video: String,
+    pub saved_at: String,
+}
 
-    leptos::logging::log!(
-        "SAVE_VIDEO: DB acquired, saving with id: {}",
-        video.video_id
-    );
+impl LibraryItem {
+    pub fn new(id: Thing, user: Thing, video: &Video) -> Result<Self, GlassBoxError> {
+        Ok(LibraryItem {
+            id: Some(id),
+            user,
+            video: serde_json::to_string(&Video { id: None, ..video.clone() })?,
+            saved_at: video.saved_at.to_rfc3339(),
+        })
+    }
 
-    // Create a new record or update existing
-    // We use the video_id as the key. Table is "video".
-    // 2.0 syntax: ("video", id)
-    let id_str = video.video_id.clone();
+    pub fn to_video(&self) -> Result<Video, GlassBoxError> {
+        Ok(serde_json::from_str(&self.video)?)
+    }
+}
 
-    // We need to clone video because .content() consumes it
-    let content = video.clone();
+// --- DATABASE SAVE FUNCTION ---
+// Adds the show to this user's library, or refreshes the copy already there
+pub async fn save_video(user_id: Thing, video: Video) -> Result<Video, GlassBoxError> {
+    save_video_with(&SurrealStore, user_id, video).await
+}
 
-    // Use .upsert to create OR update (update only works on existing records)
-    let result: Result<Option<Video>, _> = db.upsert(("video", id_str)).content(content).await;
+pub async fn save_video_with<S: Store>(store: &S, user_id: Thing, video: Video) -> Result<Video, GlassBoxError> {
+    leptos::logging::log!("SAVE_VIDEO: Attempting to save video: {}", video.title);
 
-    match result {
-        Ok(Some(v)) => {
+    match store.save_video(&user_id, video).await {
+        Ok(v) => {
             leptos::logging::log!("SAVE_VIDEO: Successfully saved: {}", v.title);
             Ok(v)
         }
-        Ok(None) => {
-            leptos::logging::error!("SAVE_VIDEO: Save returned None");
-            Err("Save failed".to_string())
-        }
         Err(e) => {
-            leptos::logging::error!("SAVE_VIDEO: Error: {}", e);
-            Err(e.to_string())
+            leptos::logging::error!("SAVE_VIDEO: Error: {:?}", e);
+            Err(e)
         }
     }
 }
+
+// Every show this user has saved, including ones only kept for a list or a
+// note. The feed's "library" is the narrower get_user_library (history.rs).
+pub async fn get_library(user_id: Thing) -> Result<Vec<Video>, GlassBoxError> {
+    SurrealStore.library(&user_id).await
+}
@@ -0,0 +1,220 @@
+// <------- Explicit watch states ------->
+// A click only says "this looked interesting". The watch state is what the
+// user says about a show, and each state has its own weight in the taste
+// vector (see RecommenderConfig::*_weight).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use surrealdb::sql::Thing;
+use crate::model::config::RecommenderConfig;
+use crate::model::error::GlassBoxError;
+use crate::model::store::{Store, SurrealStore};
+use crate::model::video::Video;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchState {
+    WantToWatch,
+    Watching,
+    Watched,
+    Dropped,
+}
+
+impl WatchState {
+    pub const ALL: [WatchState; 4] = [WatchState::WantToWatch, WatchState::Watching, WatchState::Watched, WatchState::Dropped];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            WatchState::WantToWatch => "Want to Watch",
+            WatchState::Watching => "Watching",
+            WatchState::Watched => "Watched",
+            WatchState::Dropped => "Dropped",
+        }
+    }
+
+    // Stable name for <select> values and URLs
+    pub fn key(&self) -> &'static str {
+        match self {
+            WatchState::WantToWatch => "want_to_watch",
+            WatchState::Watching => "watching",
+            WatchState::Watched => "watched",
+            WatchState::Dropped => "dropped",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.key() == key)
+    }
+
+    pub fn weight(&self, config: &RecommenderConfig) -> f64 {
+        match self {
+            WatchState::WantToWatch => config.want_weight,
+            WatchState::Watching => config.watching_weight,
+            WatchState::Watched => config.watched_weight,
+            WatchState::Dropped => config.dropped_weight,
+        }
+    }
+}
+
+// One entry per (user, show); changing the state overwrites it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WatchEntry {
+    pub id: Option<Thing>,
+    pub user: Thing,
+    pub video_id: String,
+    pub video_title: String,
+    pub state: WatchState,
+    pub added_at: String,  // first time the show got any state
+    pub timestamp: String, // when it entered the current state
+}
+
+pub async fn set_watch_state(user_id: Thing, video: Video, state: WatchState) -> Result<WatchEntry, GlassBoxError> {
+    set_watch_state_with(&SurrealStore, user_id, video, state).await
+}
+
+pub async fn set_watch_state_with<S: Store>(store: &S, user_id: Thing, video: Video, state: WatchState) -> Result<WatchEntry, GlassBoxError> {
+    // The library page and the feed read show metadata from the user's library
+    if !store.library(&user_id).await?.iter().any(|v| v.video_id == video.video_id) {
+        store.save_video(&user_id, video.clone()).await?;
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let added_at = store
+        .watchlist(&user_id)
+        .await?
+        .into_iter()
+        .find(|e| e.video_id == video.video_id)
+        .map(|e| e.added_at)
+        .unwrap_or_else(|| now.clone());
+
+    let entry = WatchEntry {
+        id: None,
+        user: user_id,
+        video_id: video.video_id,
+        video_title: video.title,
+        state,
+        added_at,
+        timestamp: now,
+    };
+    store.save_watch(entry).await
+}
+
+pub async fn clear_watch_state(user_id: Thing, video_id: String) -> Result<(), GlassBoxError> {
+    clear_watch_state_with(&SurrealStore, user_id, video_id).await
+}
+
+pub async fn clear_watch_state_with<S: Store>(store: &S, user_id: Thing, video_id: String) -> Result<(), GlassBoxError> {
+    let existing = store.watchlist(&user_id).await?.into_iter().find(|e| e.video_id == video_id);
+    match existing.and_then(|e| e.id) {
+        Some(id) => store.delete_watch(&id).await,
+        None => Ok(()),
+    }
+}
+
+// Newest state change first
+pub async fn get_watchlist(user_id: Thing) -> Result<Vec<WatchEntry>, GlassBoxError> {
+    SurrealStore.watchlist(&user_id).await
+}
+
+// Moving a show to a new state counts as fresh activity for the recency boost
+pub fn with_watch_times(library: Vec<Video>, entries: &[WatchEntry]) -> Vec<Video> {
+    library
+        .into_iter()
+        .map(|v| {
+            let changed = entries
+                .iter()
+                .find(|e| e.video_id == v.video_id)
+                .and_then(|e| DateTime::parse_from_rfc3339(&e.timestamp).ok())
+                .map(|t| t.with_timezone(&Utc));
+            match changed {
+                Some(saved_at) => Video { saved_at, ..v },
+                None => v,
+            }
+        })
+        .collect()
+}
+
+// video_id -> state multiplier; shows without a state fall back to click_weight
+pub fn state_weights(entries: &[WatchEntry], config: &RecommenderConfig) -> HashMap<String, f64> {
+    entries.iter().map(|e| (e.video_id.clone(), e.state.weight(config))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::store::MemoryStore;
+    use futures::executor::block_on;
+
+    fn show(id: &str) -> Video {
+        Video {
+            id: None,
+            video_id: id.to_string(),
+            title: format!("Show {}", id),
+            description: String::new(),
+            thumbnail_url: String::new(),
+            rating: 7.0,
+            genres: Vec::new(),
+            channel_name: String::new(),
+            related_ids: Vec::new(),
+            saved_at: DateTime::parse_from_rfc3339("2025-01-01T00:00:00+00:00").unwrap().with_timezone(&Utc),
+        }
+    }
+
+    fn entry(id: &str, state: WatchState, timestamp: &str) -> WatchEntry {
+        WatchEntry {
+            id: None,
+            user: Thing::from(("user", "ann")),
+            video_id: id.to_string(),
+            video_title: format!("Show {}", id),
+            state,
+            added_at: timestamp.to_string(),
+            timestamp: timestamp.to_string(),
+        }
+    }
+
+    #[test]
+    fn each_state_maps_to_its_config_weight() {
+        let config = RecommenderConfig {
+            want_weight: 0.1,
+            watching_weight: 0.2,
+            watched_weight: 0.3,
+            dropped_weight: 0.4,
+            ..RecommenderConfig::default()
+        };
+        let entries: Vec<WatchEntry> = WatchState::ALL.iter().enumerate().map(|(i, s)| entry(&i.to_string(), *s, "")).collect();
+        let weights = state_weights(&entries, &config);
+        assert_eq!(weights.len(), 4);
+        assert_eq!((weights["0"], weights["1"], weights["2"], weights["3"]), (0.1, 0.2, 0.3, 0.4));
+        assert!(!weights.contains_key("9"));
+        assert!(WatchState::ALL.iter().all(|s| WatchState::from_key(s.key()) == Some(*s)));
+    }
+
+    #[test]
+    fn a_state_change_dates_the_show() {
+        let library = vec![show("1"), show("2"), show("3")];
+        let entries = vec![entry("1", WatchState::Watched, "2025-03-01T10:00:00+00:00"), entry("2", WatchState::Watching, "yesterday")];
+        let dated = with_watch_times(library, &entries);
+        assert_eq!(dated[0].saved_at.to_rfc3339(), "2025-03-01T10:00:00+00:00");
+        // An unreadable timestamp or no state keeps the library date
+        assert_eq!(dated[1].saved_at, show("2").saved_at);
+        assert_eq!(dated[2].saved_at, show("3").saved_at);
+    }
+
+    #[test]
+    fn changing_the_state_keeps_when_it_was_first_added() {
+        let store = MemoryStore::new();
+        let user = Thing::from(("user", "ann"));
+        let first = block_on(set_watch_state_with(&store, user.clone(), show("1"), WatchState::WantToWatch)).unwrap();
+        let second = block_on(set_watch_state_with(&store, user.clone(), show("1"), WatchState::Watching)).unwrap();
+        assert_eq!(second.added_at, first.added_at);
+
+        let saved = block_on(store.watchlist(&user)).unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].state, WatchState::Watching);
+        assert_eq!(block_on(store.library(&user)).unwrap().len(), 1);
+
+        block_on(clear_watch_state_with(&store, user.clone(), "1".to_string())).unwrap();
+        assert!(block_on(store.watchlist(&user)).unwrap().is_empty());
+    }
+}
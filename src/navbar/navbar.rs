@@ -40,7 +40,33 @@ pub fn Navbar() -> impl IntoView {
                     </li>
                     {move || match get_session.get().username {
                         Some(_name) => view! {
-
+                            // Session survived a reload but the key didn't: ask for the password again
+                            {get_session.get().is_locked().then(|| view! {
+                                <li class="nav-item" on:click=move |_| set_is_open.set(false)>
+                                    <A href="/login" attr:class="nav-link">"Unlock"</A>
+                                </li>
+                            })}
+                            <li class="nav-item" on:click=move |_| set_is_open.set(false)>
+                                <A href="/library" attr:class="nav-link">"Library"</A>
+                            </li>
+                            <li class="nav-item" on:click=move |_| set_is_open.set(false)>
+                                <A href="/lists" attr:class="nav-link">"Lists"</A>
+                            </li>
+                            <li class="nav-item" on:click=move |_| set_is_open.set(false)>
+                                <A href="/history" attr:class="nav-link">"History"</A>
+                            </li>
+                            <li class="nav-item" on:click=move |_| set_is_open.set(false)>
+                                <A href="/profile" attr:class="nav-link">"Profile"</A>
+                            </li>
+                            <li class="nav-item" on:click=move |_| set_is_open.set(false)>
+                                <A href="/import" attr:class="nav-link">"Import"</A>
+                            </li>
+                            <li class="nav-item" on:click=move |_| set_is_open.set(false)>
+                                <A href="/backup" attr:class="nav-link">"Backup"</A>
+                            </li>
+                            <li class="nav-item" on:click=move |_| set_is_open.set(false)>
+                                <A href="/settings" attr:class="nav-link">"Settings"</A>
+                            </li>
                             <li class="nav-item" on:click=move |_| session.logout()>
                                 <span class="nav-link" style="cursor: pointer">"Logout"</span>
                             </li>
@@ -48,6 +74,7 @@ pub fn Navbar() -> impl IntoView {
                         None => view! {
                             <li class="nav-item"><A href="/login" attr:class="nav-link">"Login"</A></li>
                             <li class="nav-item"><A href="/signup" attr:class="nav-link">"Sign Up"</A></li>
+                            <li class="nav-item"><A href="/backup" attr:class="nav-link">"Restore"</A></li>
                         }.into_any()
                     }}
                 </ul>
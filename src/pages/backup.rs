@@ -0,0 +1,190 @@
+use crate::cards::main_card::MainCard;
+use crate::model::backup::{download_backup, open_backup, plan_restore, restore_backup, BackupManifest, RestorePlan};
+use crate::model::session::SessionState;
+use crate::model::upload::read_selected_file;
+use leptos::prelude::*;
+
+// Works signed out too: after the browser wiped IndexedDB there is no account to sign in to
+#[component]
+pub fn Backup() -> impl IntoView {
+    let session = use_context::<SessionState>().expect("SessionState not found");
+
+    let (backup_pass, set_backup_pass) = signal(String::new());
+    let (restore_pass, set_restore_pass) = signal(String::new());
+    let (backup_file, set_backup_file) = signal(Option::<(String, String)>::None);
+    // Filled by the dry run; the Restore button only appears after it
+    let plan = RwSignal::new(Option::<(BackupManifest, RestorePlan)>::None);
+    let (busy, set_busy) = signal(false);
+    let (status_msg, set_status) = signal(Option::<String>::None);
+
+    let passphrase = |raw: String| Some(raw).filter(|p| !p.trim().is_empty());
+
+    let on_backup = move |_| {
+        let pass = passphrase(backup_pass.get());
+        set_busy.set(true);
+        leptos::task::spawn_local(async move {
+            match download_backup(pass.as_deref()).await {
+                Ok(backup) => {
+                    let rows: usize = backup.manifest.tables.iter().map(|t| t.rows).sum();
+                    set_status.set(Some(format!(
+                        "Saved {} accounts ({} rows{}) to {}.",
+                        backup.manifest.accounts,
+                        rows,
+                        if backup.manifest.encrypted { ", passphrase protected" } else { "" },
+                        backup.filename()
+                    )));
+                }
+                Err(e) => {
+                    leptos::logging::error!("BACKUP: {:?}", e);
+                    set_status.set(Some(format!("ERROR: {}", e)));
+                }
+            }
+            set_busy.set(false);
+        });
+    };
+
+    let on_pick = move |ev: web_sys::Event| {
+        plan.set(None);
+        leptos::task::spawn_local(async move {
+            match read_selected_file(&ev).await {
+                Ok(picked) => set_backup_file.set(picked),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+        });
+    };
+
+    // Verifies the file and diffs it against what is here; writes nothing
+    let on_check = move |_| {
+        let Some((_, text)) = backup_file.get() else {
+            set_status.set(Some("ERROR: Choose a backup file first".to_string()));
+            return;
+        };
+        let pass = passphrase(restore_pass.get());
+        plan.set(None);
+        set_busy.set(true);
+        leptos::task::spawn_local(async move {
+            let checked = match open_backup(&text, pass.as_deref()).await {
+                Ok(backup) => plan_restore(&backup).await.map(|p| (backup.manifest, p)),
+                Err(e) => Err(e),
+            };
+            match checked {
+                Ok(checked) => {
+                    set_status.set(Some("Backup verified. Review the changes below before restoring.".to_string()));
+                    plan.set(Some(checked));
+                }
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+            set_busy.set(false);
+        });
+    };
+
+    let on_restore = move |_| {
+        let Some((_, text)) = backup_file.get() else {
+            return;
+        };
+        let confirmed = web_sys::window()
+            .and_then(|w| w.confirm_with_message("Replace every account on this browser with this backup?").ok())
+            .unwrap_or(false);
+        if !confirmed {
+            return;
+        }
+        let pass = passphrase(restore_pass.get());
+        set_busy.set(true);
+        leptos::task::spawn_local(async move {
+            // Verified again: the file may have been picked before it was swapped on disk
+            let result = match open_backup(&text, pass.as_deref()).await {
+                Ok(backup) => {
+                    let accounts = backup.manifest.accounts;
+                    restore_backup(backup).await.map(|_| accounts)
+                }
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(accounts) => {
+                    session.clear();
+                    plan.set(None);
+                    set_status.set(Some(format!("Restored {} accounts. Log in to continue.", accounts)));
+                }
+                Err(e) => {
+                    leptos::logging::error!("RESTORE: {:?}", e);
+                    set_status.set(Some(format!("ERROR: {}", e)));
+                }
+            }
+            set_busy.set(false);
+        });
+    };
+
+    let input_style = "background: rgba(0,0,0,0.2); border: 1px solid rgba(255,255,255,0.1); border-radius: 8px; padding: 8px; color: white;";
+    let button_style = "background: #646cff; border: none; border-radius: 8px; padding: 10px 16px; color: white; cursor: pointer;";
+    let row_style = "display: flex; align-items: center; gap: 10px; padding: 6px 0; border-bottom: 1px solid rgba(255,255,255,0.05);";
+
+    view! {
+        <MainCard>
+            <div style="max-width: 700px; margin: 0 auto; display: flex; flex-direction: column; gap: 15px;">
+                <h1>"Backup & restore"</h1>
+                <p style="opacity: 0.8;">"Browsers can clear their storage without asking. A backup file holds every account on this browser, exactly as stored, so it can be put back later or somewhere else."</p>
+
+                {move || status_msg.get().map(|msg| view! {
+                    <p style="color: #aaa; padding: 10px; background: rgba(0,0,0,0.3); border-radius: 8px;">{msg}</p>
+                })}
+
+                <h3>"Back up"</h3>
+                <p style="opacity: 0.6; font-size: 0.9rem;">"Private history stays encrypted with each account's password. A passphrase also hides usernames, settings and the show cache. If you set one, the backup cannot be restored without it."</p>
+                <div style="display: flex; gap: 10px; align-items: center; flex-wrap: wrap;">
+                    <input
+                        type="password"
+                        style=input_style
+                        placeholder="Passphrase (optional)"
+                        prop:value=backup_pass
+                        on:input=move |ev| set_backup_pass.set(event_target_value(&ev))
+                    />
+                    <button type="button" style=button_style on:click=on_backup disabled=move || busy.get()>"Download backup"</button>
+                </div>
+
+                <h3>"Restore"</h3>
+                <p style="opacity: 0.6; font-size: 0.9rem;">"The file is checked first and nothing changes until you confirm. Restoring replaces every account on this browser."</p>
+                <div style="display: flex; gap: 10px; align-items: center; flex-wrap: wrap;">
+                    <input type="file" accept=".json,application/json" on:change=on_pick />
+                    <input
+                        type="password"
+                        style=input_style
+                        placeholder="Passphrase, if it has one"
+                        prop:value=restore_pass
+                        on:input=move |ev| set_restore_pass.set(event_target_value(&ev))
+                    />
+                    <button type="button" style=button_style on:click=on_check disabled=move || busy.get()>"Check backup"</button>
+                </div>
+
+                {move || plan.get().map(|(manifest, plan)| view! {
+                    <div style="display: flex; flex-direction: column; gap: 5px;">
+                        <p>{format!(
+                            "Made {} (schema v{}) with {} accounts: {}.",
+                            manifest.created_at.get(..16).unwrap_or(&manifest.created_at).replace('T', " "),
+                            manifest.schema_version,
+                            manifest.accounts,
+                            plan.accounts.join(", ")
+                        )}</p>
+                        {plan.tables.iter().map(|t| view! {
+                            <div style=row_style>
+                                <span style="flex: 1;">{t.name.clone()}</span>
+                                <span style="color: #8f8;">{format!("+{}", t.added)}</span>
+                                <span style="color: #fc6;">{format!("~{}", t.changed)}</span>
+                                <span style="color: #ff6b6b;">{format!("-{}", t.removed)}</span>
+                                <span style="color: #777; font-size: 0.85rem;">{format!("{} unchanged", t.unchanged)}</span>
+                            </div>
+                        }).collect::<Vec<_>>()}
+                        {if plan.is_noop() {
+                            view! { <p style="opacity: 0.6;">"This browser already matches the backup."</p> }.into_any()
+                        } else {
+                            view! {
+                                <button type="button" style="background: #c0392b; border: none; border-radius: 8px; padding: 10px 16px; color: white; cursor: pointer;" on:click=on_restore disabled=move || busy.get()>
+                                    "Restore this backup"
+                                </button>
+                            }.into_any()
+                        }}
+                    </div>
+                })}
+            </div>
+        </MainCard>
+    }
+}
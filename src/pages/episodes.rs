@@ -0,0 +1,162 @@
+use crate::api::search::fetch_show;
+use crate::cards::main_card::MainCard;
+use crate::components::vault_gate::VaultGate;
+use crate::model::config::{load_config, RecommenderConfig};
+use crate::model::episodes::{episodes_for, get_progress, next_episode, set_episodes_watched, show_signal, Episode, EpisodeProgress};
+use crate::model::session::SessionState;
+use crate::model::video::{get_library, Video};
+use chrono::Utc;
+use leptos::prelude::*;
+use leptos_router::hooks::use_params_map;
+use std::collections::BTreeMap;
+
+#[component]
+pub fn Episodes() -> impl IntoView {
+    let session = use_context::<SessionState>().expect("SessionState not found");
+    let get_session = session.1;
+    let params = use_params_map();
+
+    let video = RwSignal::new(Option::<Video>::None);
+    let episodes = RwSignal::new(Vec::<Episode>::new());
+    let progress = RwSignal::new(Option::<EpisodeProgress>::None);
+    let config = RwSignal::new(RecommenderConfig::default());
+    let loading = RwSignal::new(true);
+    let (status_msg, set_status) = signal(Option::<String>::None);
+
+    let show_id = move || params.read().get("id").unwrap_or_default();
+    let current_user = move || get_session.get().user_thing();
+
+    Effect::new(move |_| {
+        let id = show_id();
+        let user = current_user();
+        loading.set(true);
+        leptos::task::spawn_local(async move {
+            // Library first (works offline), TVMaze for shows we never saved
+            let cached = match &user {
+                Some(user) => get_library(user.clone()).await.ok().and_then(|l| l.into_iter().find(|v| v.video_id == id)),
+                None => None,
+            };
+            let show = match cached {
+                Some(v) => Ok(v),
+                None => fetch_show(&id).await,
+            };
+            match show {
+                Ok(v) => video.set(Some(v)),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+            match episodes_for(&id).await {
+                Ok(list) => episodes.set(list),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+            if let Some(user) = user {
+                config.set(load_config(user.clone()).await.unwrap_or_default());
+                match get_progress(user).await {
+                    Ok(all) => progress.set(all.into_iter().find(|p| p.video_id == id)),
+                    Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+                }
+            }
+            loading.set(false);
+        });
+    });
+
+    let toggle = move |episode_ids: Vec<String>, watched: bool| {
+        let (Some(user), Some(v)) = (current_user(), video.get_untracked()) else {
+            set_status.set(Some("ERROR: Please login first".to_string()));
+            return;
+        };
+        leptos::task::spawn_local(async move {
+            match set_episodes_watched(user, v, episode_ids, watched).await {
+                Ok(p) => progress.set(Some(p)),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+        });
+    };
+
+    let is_watched = move |episode_id: &str| progress.get().map(|p| p.has_watched(episode_id)).unwrap_or(false);
+
+    let small_button = "background: transparent; border: 1px solid rgba(255,255,255,0.2); border-radius: 6px; padding: 4px 10px; color: #ccc; cursor: pointer;";
+    let row_style = "display: flex; align-items: center; gap: 10px; padding: 6px 0; border-bottom: 1px solid rgba(255,255,255,0.05);";
+
+    view! {
+        <MainCard>
+            <div style="max-width: 700px; margin: 0 auto; display: flex; flex-direction: column; gap: 15px;">
+                <h1>{move || video.get().map(|v| v.title).unwrap_or_else(|| "Episodes".to_string())}</h1>
+                <VaultGate>
+
+                    {move || status_msg.get().map(|msg| view! {
+                        <p style="color: #aaa; padding: 10px; background: rgba(0,0,0,0.3); border-radius: 8px;">{msg}</p>
+                    })}
+
+                    {move || match progress.get() {
+                        Some(p) => {
+                            let list = episodes.get();
+                            let now = Utc::now();
+                            let signal = show_signal(&p, &list, &config.get(), now);
+                            let next = next_episode(&p, &list, now.date_naive())
+                                .map(|e| format!(" · Next: {} {}", e.code(), e.name))
+                                .unwrap_or_default();
+                            view! {
+                                <p style="opacity: 0.8;">{format!("{} of {} episodes watched · {}{}", p.watched.len(), list.len(), signal.label(), next)}</p>
+                            }.into_any()
+                        }
+                        None if current_user().is_none() => view! {
+                            <p style="opacity: 0.8;">"Log in to keep track of the episodes you've seen."</p>
+                        }.into_any(),
+                        None => view! {
+                            <p style="opacity: 0.8;">"Tick off episodes as you watch them."</p>
+                        }.into_any(),
+                    }}
+
+                    {move || {
+                        if loading.get() {
+                            return view! { <p style="color: #888;">"Loading episodes..."</p> }.into_any();
+                        }
+                        let today = Utc::now().date_naive();
+                        let mut seasons: BTreeMap<u32, Vec<Episode>> = BTreeMap::new();
+                        for e in episodes.get() {
+                            seasons.entry(e.season).or_default().push(e);
+                        }
+                        seasons.into_iter().map(|(season, list)| {
+                            // Future episodes can't be ticked, so a season is "done" when every aired one is
+                            let aired: Vec<String> = list.iter().filter(|e| e.has_aired(today)).map(|e| e.episode_id.clone()).collect();
+                            let none_aired = aired.is_empty();
+                            let season_done = !none_aired && aired.iter().all(|id| is_watched(id));
+                            view! {
+                                <div style="display: flex; align-items: center; justify-content: space-between; margin-top: 10px;">
+                                    <h3>{format!("Season {}", season)}</h3>
+                                    <button
+                                        type="button"
+                                        style=small_button
+                                        disabled=none_aired
+                                        on:click=move |_| toggle(aired.clone(), !season_done)
+                                    >
+                                        {if season_done { "Unmark season" } else { "Mark season watched" }}
+                                    </button>
+                                </div>
+                                {list.into_iter().map(|e| {
+                                    let id = e.episode_id.clone();
+                                    let watched = is_watched(&id);
+                                    let aired = e.has_aired(today);
+                                    let when = e.airdate.map(|d| d.to_string()).unwrap_or_else(|| "TBA".to_string());
+                                    view! {
+                                        <div style=row_style>
+                                            <input
+                                                type="checkbox"
+                                                prop:checked=watched
+                                                disabled=!aired
+                                                on:change=move |_| toggle(vec![id.clone()], !watched)
+                                            />
+                                            <span style="color: #777; font-size: 0.85rem;">{e.code()}</span>
+                                            <span style="flex: 1;">{e.name.clone()}</span>
+                                            <span style="color: #777; font-size: 0.85rem;">{when}</span>
+                                        </div>
+                                    }
+                                }).collect::<Vec<_>>()}
+                            }
+                        }).collect::<Vec<_>>().into_any()
+                    }}
+                </VaultGate>
+            </div>
+        </MainCard>
+    }
+}
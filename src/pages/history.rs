@@ -0,0 +1,293 @@
+use crate::cards::main_card::MainCard;
+use crate::components::vault_gate::VaultGate;
+use crate::model::config::{load_retention, save_retention, RetentionPolicy, SUGGESTED_SEARCH_DAYS};
+use crate::model::error::GlassBoxError;
+use crate::model::history::{
+    apply_retention, compact_interactions, delete_interaction, delete_range, delete_search, get_interaction_stats, get_searches,
+    get_user_interactions, InteractionEntry, InteractionStat, SearchEntry,
+};
+use crate::model::session::SessionState;
+use chrono::{NaiveDate, Utc};
+use leptos::prelude::*;
+use std::collections::HashSet;
+use surrealdb::sql::Thing;
+
+#[component]
+pub fn History() -> impl IntoView {
+    let session = use_context::<SessionState>().expect("SessionState not found");
+    let get_session = session.1;
+
+    let searches = RwSignal::new(Vec::<SearchEntry>::new());
+    let interactions = RwSignal::new(Vec::<InteractionEntry>::new());
+    let stats = RwSignal::new(Vec::<InteractionStat>::new());
+    // Record ids (Thing::to_string) ticked for bulk delete
+    let selected = RwSignal::new(HashSet::<String>::new());
+    let refresh = RwSignal::new(0usize);
+    let (status_msg, set_status) = signal(Option::<String>::None);
+
+    // Form fields as text; blank = keep forever
+    let (search_days, set_search_days) = signal(String::new());
+    let (interaction_days, set_interaction_days) = signal(String::new());
+    let (range_from, set_range_from) = signal(String::new());
+    let (range_to, set_range_to) = signal(String::new());
+
+    let current_user = move || get_session.get().user_thing();
+
+    // (Re)load both lists; retention runs first so the page never shows expired rows
+    Effect::new(move |_| {
+        refresh.track();
+        let Some(user) = current_user() else {
+            return;
+        };
+        leptos::task::spawn_local(async move {
+            if let Err(e) = apply_retention(user.clone()).await {
+                leptos::logging::error!("HISTORY: Retention error: {:?}", e);
+            }
+            match load_retention(user.clone()).await {
+                Ok(policy) => {
+                    set_search_days.set(policy.search_days.map(|d| d.to_string()).unwrap_or_default());
+                    set_interaction_days.set(policy.interaction_days.map(|d| d.to_string()).unwrap_or_default());
+                }
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+            match get_searches(user.clone()).await {
+                Ok(list) => searches.set(list),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+            match get_user_interactions(user.clone()).await {
+                Ok(list) => interactions.set(list),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+            match get_interaction_stats(user).await {
+                Ok(list) => stats.set(list),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+            selected.set(HashSet::new());
+        });
+    });
+
+    let reload = move || refresh.update(|n| *n += 1);
+
+    let forget_search = move |id: Thing| {
+        leptos::task::spawn_local(async move {
+            match delete_search(id).await {
+                Ok(()) => reload(),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+        });
+    };
+
+    let forget_interaction = move |id: Thing| {
+        leptos::task::spawn_local(async move {
+            match delete_interaction(id).await {
+                Ok(()) => reload(),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+        });
+    };
+
+    let on_delete_selected = move |_| {
+        let ids = selected.get();
+        let search_ids: HashSet<String> = searches.get().iter().filter_map(|s| s.id.as_ref().map(|t| t.to_string())).collect();
+        leptos::task::spawn_local(async move {
+            let mut deleted = 0;
+            for raw in ids {
+                let Ok(id) = surrealdb::sql::thing(&raw) else {
+                    continue;
+                };
+                let result = if search_ids.contains(&raw) {
+                    delete_search(id).await
+                } else {
+                    delete_interaction(id).await
+                };
+                match result {
+                    Ok(()) => deleted += 1,
+                    Err(e) => leptos::logging::error!("HISTORY: Delete error: {:?}", e),
+                }
+            }
+            set_status.set(Some(format!("Deleted {} entries.", deleted)));
+            reload();
+        });
+    };
+
+    let on_delete_range = move |_| {
+        let Some(user) = current_user() else {
+            return;
+        };
+        let parse = |raw: String, what: &str| {
+            NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d")
+                .map_err(|_| GlassBoxError::Validation(format!("Pick a {} date", what)))
+        };
+        let (from, to) = match (parse(range_from.get(), "start"), parse(range_to.get(), "end")) {
+            (Ok(f), Ok(t)) if f <= t => (f, t),
+            (Err(e), _) | (_, Err(e)) => {
+                set_status.set(Some(format!("ERROR: {}", e)));
+                return;
+            }
+            _ => {
+                set_status.set(Some("ERROR: The start date must be before the end date".to_string()));
+                return;
+            }
+        };
+        // Whole days, end date included
+        let from = from.and_hms_opt(0, 0, 0).map(|d| d.and_utc()).unwrap_or_else(Utc::now);
+        let to = to.succ_opt().and_then(|d| d.and_hms_opt(0, 0, 0)).map(|d| d.and_utc()).unwrap_or_else(Utc::now);
+
+        leptos::task::spawn_local(async move {
+            match delete_range(user, from, to).await {
+                Ok((s, i)) => set_status.set(Some(format!("Deleted {} searches and {} interactions.", s, i))),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+            reload();
+        });
+    };
+
+    let on_save_retention = move |_| {
+        let Some(user) = current_user() else {
+            return;
+        };
+        let parse = |raw: String| -> Result<Option<u32>, GlassBoxError> {
+            let raw = raw.trim().to_string();
+            if raw.is_empty() {
+                return Ok(None);
+            }
+            raw.parse()
+                .map(Some)
+                .map_err(|_| GlassBoxError::Validation(format!("'{}' is not a number of days", raw)))
+        };
+        let policy = match (parse(search_days.get()), parse(interaction_days.get())) {
+            (Ok(search_days), Ok(interaction_days)) => RetentionPolicy { search_days, interaction_days },
+            (Err(e), _) | (_, Err(e)) => {
+                set_status.set(Some(format!("ERROR: {}", e)));
+                return;
+            }
+        };
+        leptos::task::spawn_local(async move {
+            match save_retention(user, policy).await {
+                Ok(_) => {
+                    set_status.set(Some("Retention policy saved.".to_string()));
+                    reload();
+                }
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+        });
+    };
+
+    // Merges repeated clicks left over from before runs were counted
+    let on_compact = move |_| {
+        let Some(user) = current_user() else {
+            return;
+        };
+        leptos::task::spawn_local(async move {
+            match compact_interactions(user).await {
+                Ok(r) => set_status.set(Some(format!("Compacted {} interaction rows into {}.", r.rows_before, r.rows_after))),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+            reload();
+        });
+    };
+
+    let toggle = move |id: String| {
+        selected.update(|s| {
+            if !s.remove(&id) {
+                s.insert(id);
+            }
+        });
+    };
+
+    let input_style = "background: rgba(0,0,0,0.2); border: 1px solid rgba(255,255,255,0.1); border-radius: 8px; padding: 8px; color: white;";
+    let button_style = "background: #646cff; border: none; border-radius: 8px; padding: 10px 16px; color: white; cursor: pointer;";
+    let small_button = "background: transparent; border: 1px solid rgba(255,255,255,0.2); border-radius: 6px; padding: 4px 10px; color: #ccc; cursor: pointer;";
+    let row_style = "display: flex; align-items: center; gap: 10px; padding: 6px 0; border-bottom: 1px solid rgba(255,255,255,0.05);";
+
+    let when = |ts: &str| ts.get(..16).unwrap_or(ts).replace('T', " ");
+
+    view! {
+        <MainCard>
+            <div style="max-width: 700px; margin: 0 auto; display: flex; flex-direction: column; gap: 15px;">
+                <h1>"Your history"</h1>
+                <VaultGate>
+                    <p style="opacity: 0.8;">"Everything GlassBox remembers about you. Forgotten entries also leave your recommendations."</p>
+
+                    {move || status_msg.get().map(|msg| view! {
+                        <p style="color: #aaa; padding: 10px; background: rgba(0,0,0,0.3); border-radius: 8px;">{msg}</p>
+                    })}
+
+                    <h3>"Keep history for"</h3>
+                    <div style="display: flex; gap: 10px; align-items: center; flex-wrap: wrap;">
+                        <label>"Searches (days)"</label>
+                        <input type="text" style=input_style placeholder="forever" prop:value=search_days on:input=move |ev| set_search_days.set(event_target_value(&ev)) />
+                        <label>"Clicks (days)"</label>
+                        <input type="text" style=input_style placeholder="forever" prop:value=interaction_days on:input=move |ev| set_interaction_days.set(event_target_value(&ev)) />
+                        <button type="button" style=button_style on:click=on_save_retention>"Save"</button>
+                    </div>
+                    // Nothing is purged unless asked for; the usual choice is one click away
+                    {move || search_days.get().trim().is_empty().then(|| view! {
+                        <div style="display: flex; gap: 10px; align-items: center; flex-wrap: wrap; opacity: 0.8;">
+                            <span>"Searches are kept forever."</span>
+                            <button type="button" style=button_style on:click=move |_| set_search_days.set(SUGGESTED_SEARCH_DAYS.to_string())>
+                                {format!("Keep {} days instead", SUGGESTED_SEARCH_DAYS)}
+                            </button>
+                            <span>"(then Save)"</span>
+                        </div>
+                    })}
+
+                    <h3>"Delete a date range"</h3>
+                    <div style="display: flex; gap: 10px; align-items: center; flex-wrap: wrap;">
+                        <input type="date" style=input_style prop:value=range_from on:input=move |ev| set_range_from.set(event_target_value(&ev)) />
+                        <span>"to"</span>
+                        <input type="date" style=input_style prop:value=range_to on:input=move |ev| set_range_to.set(event_target_value(&ev)) />
+                        <button type="button" style=button_style on:click=on_delete_range>"Delete range"</button>
+                    </div>
+
+                    <button type="button" style=button_style on:click=on_delete_selected disabled=move || selected.get().is_empty()>
+                        {move || format!("Delete selected ({})", selected.get().len())}
+                    </button>
+
+                    <h3>{move || format!("Searches ({})", searches.get().len())}</h3>
+                    {move || searches.get().into_iter().filter_map(|s| {
+                        let id = s.id.clone()?;
+                        let key = id.to_string();
+                        let checked_key = key.clone();
+                        Some(view! {
+                            <div style=row_style>
+                                <input type="checkbox" prop:checked=move || selected.get().contains(&checked_key) on:change=move |_| toggle(key.clone()) />
+                                <span style="flex: 1;">{s.query.clone()}</span>
+                                <span style="color: #777; font-size: 0.85rem;">{when(&s.timestamp)}</span>
+                                <button type="button" style=small_button on:click=move |_| forget_search(id.clone())>"Forget"</button>
+                            </div>
+                        })
+                    }).collect::<Vec<_>>()}
+
+                    <h3>"Most visited"</h3>
+                    {move || stats.get().into_iter().take(5).map(|s| view! {
+                        <div style=row_style>
+                            <span style="flex: 1;">{format!("{} ({})", s.video_title, s.interaction_type)}</span>
+                            <span style="color: #aaa;">{format!("×{}", s.count)}</span>
+                            <span style="color: #777; font-size: 0.85rem;">{format!("{} – {}", when(&s.first_at), when(&s.last_at))}</span>
+                        </div>
+                    }).collect::<Vec<_>>()}
+
+                    <div style="display: flex; align-items: center; gap: 10px;">
+                        <h3 style="flex: 1;">{move || format!("Clicks & imports ({})", interactions.get().len())}</h3>
+                        <button type="button" style=small_button on:click=on_compact>"Compact history"</button>
+                    </div>
+                    {move || interactions.get().into_iter().filter_map(|i| {
+                        let id = i.id.clone()?;
+                        let key = id.to_string();
+                        let checked_key = key.clone();
+                        Some(view! {
+                            <div style=row_style>
+                                <input type="checkbox" prop:checked=move || selected.get().contains(&checked_key) on:change=move |_| toggle(key.clone()) />
+                                <span style="flex: 1;">{format!("{} ({})", i.video_title, i.interaction_type)}</span>
+                                {(i.count > 1).then(|| view! { <span style="color: #aaa;">{format!("×{}", i.count)}</span> })}
+                                <span style="color: #777; font-size: 0.85rem;">{when(i.last_seen())}</span>
+                                <button type="button" style=small_button on:click=move |_| forget_interaction(id.clone())>"Forget"</button>
+                            </div>
+                        })
+                    }).collect::<Vec<_>>()}
+                </VaultGate>
+            </div>
+        </MainCard>
+    }
+}
@@ -1,6 +1,7 @@
 use crate::cards::main_card::MainCard;
 use crate::components::feed::Feed;
 use crate::components::search::Search;
+use crate::components::storage_notice::StorageNotice;
 use crate::model::feed_control::FeedTrigger;
 use crate::model::session::SessionState;
 use leptos::prelude::*;
@@ -29,6 +30,8 @@ pub fn Home() -> impl IntoView {
                             <p style="opacity: 0.8;">"What would you like to watch today?"</p>
                         </div>
 
+                        <StorageNotice />
+
                         // This Component now handles Input AND Results
                         <Search />
 
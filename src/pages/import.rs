@@ -0,0 +1,231 @@
+use crate::cards::main_card::MainCard;
+use crate::model::feed_control::FeedTrigger;
+use crate::model::importers::{commit_import, match_row, parse_export, ImportRow, ImportSource, MatchOutcome, PendingImport};
+use crate::model::session::SessionState;
+use crate::model::upload::read_selected_file;
+use crate::model::video::Video;
+use leptos::prelude::*;
+use std::collections::HashMap;
+
+#[component]
+pub fn ImportHistory() -> impl IntoView {
+    let session = use_context::<SessionState>().expect("SessionState not found");
+    let get_session = session.1;
+    let feed_trigger = use_context::<FeedTrigger>();
+
+    let (status_msg, set_status) = signal(Option::<String>::None);
+    let source = RwSignal::new(Option::<ImportSource>::None);
+    let pending = RwSignal::new(Vec::<PendingImport>::new());
+    // Row index -> chosen video_id for ambiguous rows ("" = skip)
+    let choices = RwSignal::new(HashMap::<usize, String>::new());
+    let progress = RwSignal::new((0usize, 0usize));
+    let busy = RwSignal::new(false);
+
+    let on_pick = move |ev: web_sys::Event| {
+        leptos::task::spawn_local(async move {
+            let (name, text) = match read_selected_file(&ev).await {
+                Ok(Some(file)) => file,
+                Ok(None) => return,
+                Err(e) => {
+                    set_status.set(Some(format!("ERROR: {}", e)));
+                    return;
+                }
+            };
+            let (kind, rows) = match parse_export(&name, &text) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    set_status.set(Some(format!("ERROR: {}", e)));
+                    return;
+                }
+            };
+
+            busy.set(true);
+            source.set(Some(kind));
+            pending.set(Vec::new());
+            choices.set(HashMap::new());
+            progress.set((0, rows.len()));
+            set_status.set(Some(format!("Matching {} titles from {} against TVMaze...", rows.len(), kind.label())));
+
+            // One at a time: TVMaze rate-limits bursts
+            for (i, row) in rows.into_iter().enumerate() {
+                let outcome = match_row(&row).await;
+                pending.update(|p| p.push(PendingImport { row, outcome }));
+                progress.set((i + 1, progress.get_untracked().1));
+            }
+
+            busy.set(false);
+            set_status.set(Some("Review the matches below, then import.".to_string()));
+        });
+    };
+
+    let picks = move || -> Vec<(ImportRow, Video)> {
+        let chosen = choices.get();
+        pending
+            .get()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, p)| match p.outcome {
+                MatchOutcome::Matched(video) => Some((p.row, video)),
+                MatchOutcome::Ambiguous(candidates) => {
+                    let id = chosen.get(&i)?;
+                    let video = candidates.into_iter().find(|c| &c.video_id == id)?;
+                    Some((p.row, video))
+                }
+                MatchOutcome::Unmatched => None,
+            })
+            .collect()
+    };
+
+    let on_commit = move |_| {
+        let Some(uid_str) = get_session.get().user_id else {
+            set_status.set(Some("ERROR: Please login first".to_string()));
+            return;
+        };
+        let Some(kind) = source.get() else {
+            return;
+        };
+        let chosen = picks();
+        busy.set(true);
+
+        leptos::task::spawn_local(async move {
+            let Ok(thing) = surrealdb::sql::thing(&uid_str) else {
+                busy.set(false);
+                return;
+            };
+            match commit_import(thing, kind, chosen).await {
+                Ok(summary) => {
+                    set_status.set(Some(format!(
+                        "Imported {} titles and {} ratings from {} ({} already imported, skipped).",
+                        summary.titles,
+                        summary.ratings,
+                        kind.label(),
+                        summary.skipped
+                    )));
+                    pending.set(Vec::new());
+                    if let Some(t) = feed_trigger {
+                        t.0.update(|n| *n += 1);
+                    }
+                }
+                Err(e) => {
+                    leptos::logging::error!("IMPORT: {:?}", e);
+                    set_status.set(Some(format!("ERROR: {}", e)));
+                }
+            }
+            busy.set(false);
+        });
+    };
+
+    let input_style = "background: rgba(0,0,0,0.2); border: 1px solid rgba(255,255,255,0.1); border-radius: 8px; padding: 8px; color: white;";
+    let button_style = "background: #646cff; border: none; border-radius: 8px; padding: 10px 16px; color: white; cursor: pointer;";
+    let row_style = "display: flex; justify-content: space-between; align-items: center; gap: 10px; padding: 6px 0; border-bottom: 1px solid rgba(255,255,255,0.05);";
+
+    let describe = |row: &ImportRow| {
+        let mut label = row.title.clone();
+        if let Some(year) = row.year {
+            label.push_str(&format!(" ({})", year));
+        }
+        if let Some(rating) = row.rating {
+            label.push_str(&format!(" - rated {}/10", rating));
+        }
+        label
+    };
+
+    view! {
+        <MainCard>
+            <div style="max-width: 700px; margin: 0 auto; display: flex; flex-direction: column; gap: 15px;">
+                <h1>"Import your history"</h1>
+                <p style="opacity: 0.8;">
+                    "Letterboxd diary.csv / ratings.csv, IMDb ratings.csv or a Trakt .json export. "
+                    "Files are read in your browser; only titles are looked up on TVMaze."
+                </p>
+                <p style="opacity: 0.8;">
+                    "Letterboxd only logs films and TVMaze only knows TV shows, so most Letterboxd rows won't find a match. "
+                    "Only the series in it (miniseries, specials listed as films) come through."
+                </p>
+
+                <input type="file" accept=".csv,.json,text/csv,application/json" on:change=on_pick disabled=move || busy.get() />
+
+                {move || status_msg.get().map(|msg| view! {
+                    <p style="color: #aaa; padding: 10px; background: rgba(0,0,0,0.3); border-radius: 8px;">{msg}</p>
+                })}
+
+                {move || {
+                    let (done, total) = progress.get();
+                    (busy.get() && total > 0).then(|| view! {
+                        <p style="opacity: 0.6;">{format!("{} / {}", done, total)}</p>
+                    })
+                }}
+
+                {move || {
+                    let rows = pending.get();
+                    let matched: Vec<String> = rows
+                        .iter()
+                        .filter_map(|p| match &p.outcome {
+                            MatchOutcome::Matched(v) => Some(format!("{} -> {}", describe(&p.row), v.title)),
+                            _ => None,
+                        })
+                        .collect();
+                    (!matched.is_empty()).then(|| view! {
+                        <h3>{format!("Matched ({})", matched.len())}</h3>
+                        <div style="max-height: 250px; overflow-y: auto; font-size: 0.9rem; color: #aaa;">
+                            {matched.into_iter().map(|m| view! { <div style=row_style>{m}</div> }).collect::<Vec<_>>()}
+                        </div>
+                    })
+                }}
+
+                {move || {
+                    let rows: Vec<(usize, ImportRow, Vec<Video>)> = pending
+                        .get()
+                        .into_iter()
+                        .enumerate()
+                        .filter_map(|(i, p)| match p.outcome {
+                            MatchOutcome::Ambiguous(c) => Some((i, p.row, c)),
+                            _ => None,
+                        })
+                        .collect();
+                    (!rows.is_empty()).then(|| view! {
+                        <h3>{format!("Needs review ({})", rows.len())}</h3>
+                        {rows.into_iter().map(|(i, row, candidates)| view! {
+                            <div style=row_style>
+                                <span>{describe(&row)}</span>
+                                <select
+                                    style=input_style
+                                    on:change=move |ev| {
+                                        let id = event_target_value(&ev);
+                                        choices.update(|c| { c.insert(i, id); });
+                                    }
+                                >
+                                    <option value="">"Skip"</option>
+                                    {candidates.into_iter().map(|c| view! {
+                                        <option value=c.video_id.clone()>{format!("{} ({})", c.title, c.channel_name)}</option>
+                                    }).collect::<Vec<_>>()}
+                                </select>
+                            </div>
+                        }).collect::<Vec<_>>()}
+                    })
+                }}
+
+                {move || {
+                    let missing: Vec<String> = pending
+                        .get()
+                        .iter()
+                        .filter(|p| p.outcome == MatchOutcome::Unmatched)
+                        .map(|p| describe(&p.row))
+                        .collect();
+                    (!missing.is_empty()).then(|| view! {
+                        <h3>{format!("Not on TVMaze ({})", missing.len())}</h3>
+                        <div style="max-height: 150px; overflow-y: auto; font-size: 0.85rem; color: #777;">
+                            {missing.into_iter().map(|m| view! { <div>{m}</div> }).collect::<Vec<_>>()}
+                        </div>
+                    })
+                }}
+
+                {move || (!pending.get().is_empty() && !busy.get()).then(|| view! {
+                    <button type="button" style=button_style on:click=on_commit>
+                        {move || format!("Import {} titles", picks().len())}
+                    </button>
+                })}
+            </div>
+        </MainCard>
+    }
+}
@@ -0,0 +1,157 @@
+use crate::cards::main_card::MainCard;
+use crate::components::vault_gate::VaultGate;
+use crate::model::session::SessionState;
+use crate::model::video::{get_library, Video};
+use crate::model::watchlist::{clear_watch_state, get_watchlist, set_watch_state, WatchEntry, WatchState};
+use leptos::prelude::*;
+
+#[component]
+pub fn Library() -> impl IntoView {
+    let session = use_context::<SessionState>().expect("SessionState not found");
+    let get_session = session.1;
+
+    // Each entry with its cached show (None if the video row is gone)
+    let entries = RwSignal::new(Vec::<(WatchEntry, Option<Video>)>::new());
+    let (filter, set_filter) = signal(Option::<WatchState>::None);
+    let refresh = RwSignal::new(0usize);
+    let (status_msg, set_status) = signal(Option::<String>::None);
+
+    let current_user = move || get_session.get().user_thing();
+
+    Effect::new(move |_| {
+        refresh.track();
+        let Some(user) = current_user() else {
+            return;
+        };
+        leptos::task::spawn_local(async move {
+            let watchlist = match get_watchlist(user.clone()).await {
+                Ok(list) => list,
+                Err(e) => {
+                    set_status.set(Some(format!("ERROR: {}", e)));
+                    return;
+                }
+            };
+            let library = get_library(user).await.unwrap_or_else(|e| {
+                leptos::logging::error!("LIBRARY: Video lookup error: {:?}", e);
+                Vec::new()
+            });
+            entries.set(
+                watchlist
+                    .into_iter()
+                    .map(|e| {
+                        let video = library.iter().find(|v| v.video_id == e.video_id).cloned();
+                        (e, video)
+                    })
+                    .collect(),
+            );
+        });
+    });
+
+    let reload = move || refresh.update(|n| *n += 1);
+
+    let on_change = move |video: Video, key: String| {
+        let Some(user) = current_user() else {
+            return;
+        };
+        leptos::task::spawn_local(async move {
+            let result = match WatchState::from_key(&key) {
+                Some(state) => set_watch_state(user, video, state).await.map(|_| ()),
+                None => clear_watch_state(user, video.video_id).await,
+            };
+            match result {
+                Ok(()) => reload(),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+        });
+    };
+
+    let on_remove = move |video_id: String| {
+        let Some(user) = current_user() else {
+            return;
+        };
+        leptos::task::spawn_local(async move {
+            match clear_watch_state(user, video_id).await {
+                Ok(()) => reload(),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+        });
+    };
+
+    let input_style = "background: rgba(0,0,0,0.2); border: 1px solid rgba(255,255,255,0.1); border-radius: 8px; padding: 8px; color: white;";
+    let small_button = "background: transparent; border: 1px solid rgba(255,255,255,0.2); border-radius: 6px; padding: 4px 10px; color: #ccc; cursor: pointer;";
+    let row_style = "display: flex; align-items: center; gap: 10px; padding: 6px 0; border-bottom: 1px solid rgba(255,255,255,0.05);";
+
+    let when = |ts: &str| ts.get(..10).unwrap_or(ts).to_string();
+    let count = move |state: WatchState| entries.get().iter().filter(|(e, _)| e.state == state).count();
+
+    view! {
+        <MainCard>
+            <div style="max-width: 700px; margin: 0 auto; display: flex; flex-direction: column; gap: 15px;">
+                <h1>"Your library"</h1>
+                <VaultGate>
+                    <p style="opacity: 0.8;">"Shows you gave a watch state. Each state has its own weight in your recommendations (see Settings)."</p>
+
+                    {move || status_msg.get().map(|msg| view! {
+                        <p style="color: #aaa; padding: 10px; background: rgba(0,0,0,0.3); border-radius: 8px;">{msg}</p>
+                    })}
+
+                    <div style="display: flex; gap: 10px; align-items: center;">
+                        <label>"Show"</label>
+                        <select
+                            style=input_style
+                            on:change=move |ev| set_filter.set(WatchState::from_key(&event_target_value(&ev)))
+                        >
+                            <option value="all" selected=move || filter.get().is_none()>
+                                {move || format!("All ({})", entries.get().len())}
+                            </option>
+                            {WatchState::ALL.into_iter().map(|state| view! {
+                                <option value=state.key() selected=move || filter.get() == Some(state)>
+                                    {move || format!("{} ({})", state.label(), count(state))}
+                                </option>
+                            }).collect::<Vec<_>>()}
+                        </select>
+                    </div>
+
+                    {move || {
+                        let shown: Vec<(WatchEntry, Option<Video>)> = entries
+                            .get()
+                            .into_iter()
+                            .filter(|(e, _)| filter.get().is_none_or(|f| e.state == f))
+                            .collect();
+                        if shown.is_empty() {
+                            return view! {
+                                <p style="opacity: 0.6;">"Nothing here yet. Open a show and pick Want to Watch, Watching, Watched or Dropped."</p>
+                            }.into_any();
+                        }
+                        shown.into_iter().map(|(entry, video)| {
+                            let current = entry.state;
+                            let video_id = entry.video_id.clone();
+                            let thumbnail = video.as_ref().map(|v| v.thumbnail_url.clone()).unwrap_or_default();
+                            view! {
+                                <div style=row_style>
+                                    <img src=thumbnail style="width: 40px; height: 56px; object-fit: cover; border-radius: 4px;" />
+                                    <span style="flex: 1;">{entry.video_title.clone()}</span>
+                                    <span style="color: #777; font-size: 0.85rem;">{format!("since {}", when(&entry.timestamp))}</span>
+                                    <select
+                                        style=input_style
+                                        disabled=video.is_none()
+                                        on:change=move |ev| {
+                                            if let Some(v) = video.clone() {
+                                                on_change(v, event_target_value(&ev));
+                                            }
+                                        }
+                                    >
+                                        {WatchState::ALL.into_iter().map(|state| view! {
+                                            <option value=state.key() selected=state == current>{state.label()}</option>
+                                        }).collect::<Vec<_>>()}
+                                    </select>
+                                    <button type="button" style=small_button on:click=move |_| on_remove(video_id.clone())>"Remove"</button>
+                                </div>
+                            }
+                        }).collect::<Vec<_>>().into_any()
+                    }}
+                </VaultGate>
+            </div>
+        </MainCard>
+    }
+}
@@ -0,0 +1,349 @@
+use crate::cards::main_card::MainCard;
+use crate::components::vault_gate::VaultGate;
+use crate::model::config::load_config;
+use crate::model::download::download_text;
+use crate::model::lists::{add_to_list, create_list, delete_list, get_lists, import_list, more_like_list, move_item, save_list, SharedList, UserList};
+use crate::model::session::SessionState;
+use crate::model::upload::read_selected_file;
+use crate::model::video::{get_library, Video};
+use leptos::prelude::*;
+
+#[component]
+pub fn Lists() -> impl IntoView {
+    let session = use_context::<SessionState>().expect("SessionState not found");
+    let get_session = session.1;
+
+    let lists = RwSignal::new(Vec::<UserList>::new());
+    // Record id (Thing::to_string) of the open list
+    let selected = RwSignal::new(Option::<String>::None);
+    let library = RwSignal::new(Vec::<Video>::new());
+    let suggestions = RwSignal::new(Vec::<Video>::new());
+    let (suggesting, set_suggesting) = signal(false);
+    let (share_code, set_share_code) = signal(String::new());
+    let (status_msg, set_status) = signal(Option::<String>::None);
+
+    // Form fields
+    let (new_name, set_new_name) = signal(String::new());
+    let (new_description, set_new_description) = signal(String::new());
+    let (import_text, set_import_text) = signal(String::new());
+
+    let current_user = move || get_session.get().user_thing();
+
+    Effect::new(move |_| {
+        let Some(user) = current_user() else {
+            return;
+        };
+        leptos::task::spawn_local(async move {
+            match get_lists(user.clone()).await {
+                Ok(all) => {
+                    if selected.get_untracked().is_none() {
+                        selected.set(all.first().and_then(|l| l.id.as_ref().map(|id| id.to_string())));
+                    }
+                    lists.set(all);
+                }
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+            library.set(get_library(user).await.unwrap_or_default());
+        });
+    });
+
+    let open_list = move || {
+        let key = selected.get()?;
+        lists.get().into_iter().find(|l| l.id.as_ref().map(|id| id.to_string()).as_deref() == Some(key.as_str()))
+    };
+
+    // Puts a saved list back in place (or appends a new one) and opens it
+    let store_local = move |list: UserList| {
+        let key = list.id.as_ref().map(|id| id.to_string());
+        lists.update(|all| match all.iter_mut().find(|l| l.id == list.id) {
+            Some(existing) => *existing = list,
+            None => all.push(list),
+        });
+        selected.set(key);
+    };
+
+    let persist = move |list: UserList| {
+        leptos::task::spawn_local(async move {
+            match save_list(list).await {
+                Ok(saved) => store_local(saved),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+        });
+    };
+
+    let on_create = move |_| {
+        let Some(user) = current_user() else {
+            set_status.set(Some("ERROR: Please login first".to_string()));
+            return;
+        };
+        let (name, description) = (new_name.get(), new_description.get());
+        leptos::task::spawn_local(async move {
+            match create_list(user, name, description).await {
+                Ok(list) => {
+                    set_new_name.set(String::new());
+                    set_new_description.set(String::new());
+                    set_status.set(Some(format!("Created '{}'.", list.name)));
+                    store_local(list);
+                }
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+        });
+    };
+
+    let run_import = move |text: String| {
+        let Some(user) = current_user() else {
+            set_status.set(Some("ERROR: Please login first".to_string()));
+            return;
+        };
+        let shared = match SharedList::parse(&text) {
+            Ok(s) => s,
+            Err(e) => {
+                set_status.set(Some(format!("ERROR: {}", e)));
+                return;
+            }
+        };
+        leptos::task::spawn_local(async move {
+            match import_list(user.clone(), shared).await {
+                Ok(list) => {
+                    set_import_text.set(String::new());
+                    set_status.set(Some(format!("Imported '{}' ({} shows).", list.name, list.items.len())));
+                    library.set(get_library(user).await.unwrap_or_default());
+                    store_local(list);
+                }
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+        });
+    };
+
+    let on_import_file = move |ev: web_sys::Event| {
+        leptos::task::spawn_local(async move {
+            match read_selected_file(&ev).await {
+                Ok(Some((_, text))) => run_import(text),
+                Ok(None) => {}
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+        });
+    };
+
+    let on_download = move |_| {
+        let Some(list) = open_list() else {
+            return;
+        };
+        let shared = SharedList::from_list(&list);
+        let result = shared.to_json().and_then(|json| download_text(&shared.filename(), "application/json", &json));
+        if let Err(e) = result {
+            set_status.set(Some(format!("ERROR: {}", e)));
+        }
+    };
+
+    let on_share = move |_| {
+        let Some(list) = open_list() else {
+            return;
+        };
+        match SharedList::from_list(&list).to_code() {
+            Ok(code) => set_share_code.set(code),
+            Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+        }
+    };
+
+    let on_delete = move |_| {
+        let Some(id) = open_list().and_then(|l| l.id) else {
+            return;
+        };
+        leptos::task::spawn_local(async move {
+            match delete_list(id.clone()).await {
+                Ok(()) => {
+                    lists.update(|all| all.retain(|l| l.id.as_ref() != Some(&id)));
+                    selected.set(lists.get_untracked().first().and_then(|l| l.id.as_ref().map(|id| id.to_string())));
+                    set_status.set(Some("List deleted.".to_string()));
+                }
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+        });
+    };
+
+    let on_suggest = move |_| {
+        let (Some(list), Some(user)) = (open_list(), current_user()) else {
+            return;
+        };
+        set_suggesting.set(true);
+        leptos::task::spawn_local(async move {
+            let config = load_config(user).await.unwrap_or_default();
+            match more_like_list(&list, &library.get_untracked(), &config).await {
+                Ok(found) => suggestions.set(found),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+            set_suggesting.set(false);
+        });
+    };
+
+    let on_add_suggestion = move |video: Video| {
+        let Some(list) = open_list() else {
+            return;
+        };
+        leptos::task::spawn_local(async move {
+            match add_to_list(list, video.clone()).await {
+                Ok(saved) => {
+                    suggestions.update(|s| s.retain(|v| v.video_id != video.video_id));
+                    library.update(|l| {
+                        if !l.iter().any(|v| v.video_id == video.video_id) {
+                            l.push(video);
+                        }
+                    });
+                    store_local(saved);
+                }
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+        });
+    };
+
+    let poster = move |video_id: &str| {
+        library
+            .get()
+            .iter()
+            .find(|v| v.video_id == video_id)
+            .map(|v| v.thumbnail_url.clone())
+            .unwrap_or_default()
+    };
+
+    let input_style = "background: rgba(0,0,0,0.2); border: 1px solid rgba(255,255,255,0.1); border-radius: 8px; padding: 8px; color: white;";
+    let button_style = "background: #646cff; border: none; border-radius: 8px; padding: 10px 16px; color: white; cursor: pointer;";
+    let small_button = "background: transparent; border: 1px solid rgba(255,255,255,0.2); border-radius: 6px; padding: 4px 10px; color: #ccc; cursor: pointer;";
+    let row_style = "display: flex; align-items: center; gap: 10px; padding: 6px 0; border-bottom: 1px solid rgba(255,255,255,0.05);";
+
+    view! {
+        <MainCard>
+            <div style="max-width: 700px; margin: 0 auto; display: flex; flex-direction: column; gap: 15px;">
+                <h1>"Your lists"</h1>
+                <VaultGate>
+
+                    {move || status_msg.get().map(|msg| view! {
+                        <p style="color: #aaa; padding: 10px; background: rgba(0,0,0,0.3); border-radius: 8px;">{msg}</p>
+                    })}
+
+                    <h3>"New list"</h3>
+                    <input type="text" style=input_style placeholder="Name, e.g. Comfort shows" prop:value=new_name on:input=move |ev| set_new_name.set(event_target_value(&ev)) />
+                    <input type="text" style=input_style placeholder="Description (optional)" prop:value=new_description on:input=move |ev| set_new_description.set(event_target_value(&ev)) />
+                    <button type="button" style=button_style on:click=on_create>"Create list"</button>
+
+                    <h3>"Import a shared list"</h3>
+                    <textarea rows="3" style=input_style placeholder="Paste a list code (gblist1:...) or list JSON" prop:value=import_text on:input=move |ev| set_import_text.set(event_target_value(&ev))></textarea>
+                    <div style="display: flex; gap: 10px; align-items: center;">
+                        <button type="button" style=button_style on:click=move |_| run_import(import_text.get())>"Import"</button>
+                        <input type="file" accept=".json,application/json" on:change=on_import_file />
+                    </div>
+
+                    {move || {
+                        let all = lists.get();
+                        if all.is_empty() {
+                            return view! { <p style="opacity: 0.6;">"No lists yet."</p> }.into_any();
+                        }
+                        view! {
+                            <div style="display: flex; gap: 8px; flex-wrap: wrap;">
+                                {all.into_iter().map(|l| {
+                                    let key = l.id.as_ref().map(|id| id.to_string());
+                                    let active_key = key.clone();
+                                    view! {
+                                        <button
+                                            type="button"
+                                            style=move || if selected.get() == active_key { button_style } else { small_button }
+                                            on:click=move |_| {
+                                                selected.set(key.clone());
+                                                suggestions.set(Vec::new());
+                                                set_share_code.set(String::new());
+                                            }
+                                        >
+                                            {format!("{} ({})", l.name, l.items.len())}
+                                        </button>
+                                    }
+                                }).collect::<Vec<_>>()}
+                            </div>
+                        }.into_any()
+                    }}
+
+                    {move || open_list().map(|list| {
+                        let count = list.items.len();
+                        view! {
+                            <h2>{list.name.clone()}</h2>
+                            {(!list.description.is_empty()).then(|| view! { <p style="opacity: 0.8;">{list.description.clone()}</p> })}
+
+                            {list.items.iter().cloned().enumerate().map(|(idx, item)| {
+                                let (up, down, remove, note) = (list.clone(), list.clone(), list.clone(), list.clone());
+                                view! {
+                                    <div style=row_style>
+                                        <img src=poster(&item.video_id) style="width: 40px; height: 56px; object-fit: cover; border-radius: 4px;" />
+                                        <div style="flex: 1; display: flex; flex-direction: column; gap: 4px;">
+                                            <span>{format!("{}. {}", idx + 1, item.video_title)}</span>
+                                            <input
+                                                type="text"
+                                                style=input_style
+                                                placeholder="Note"
+                                                prop:value=item.note.clone()
+                                                on:change=move |ev| {
+                                                    let mut l = note.clone();
+                                                    l.items[idx].note = event_target_value(&ev);
+                                                    persist(l);
+                                                }
+                                            />
+                                        </div>
+                                        <button type="button" style=small_button disabled={idx == 0} on:click=move |_| {
+                                            let mut l = up.clone();
+                                            move_item(&mut l, idx, -1);
+                                            persist(l);
+                                        }>"↑"</button>
+                                        <button type="button" style=small_button disabled={idx + 1 == count} on:click=move |_| {
+                                            let mut l = down.clone();
+                                            move_item(&mut l, idx, 1);
+                                            persist(l);
+                                        }>"↓"</button>
+                                        <button type="button" style=small_button on:click=move |_| {
+                                            let mut l = remove.clone();
+                                            l.items.remove(idx);
+                                            persist(l);
+                                        }>"Remove"</button>
+                                    </div>
+                                }
+                            }).collect::<Vec<_>>()}
+
+                            {(count == 0).then(|| view! {
+                                <p style="opacity: 0.6;">"Empty. Add shows from a show's details, or from the suggestions below once the list has a few."</p>
+                            })}
+
+                            <div style="display: flex; gap: 10px; flex-wrap: wrap;">
+                                <button type="button" style=button_style disabled=move || suggesting.get() || count == 0 on:click=on_suggest>
+                                    {move || if suggesting.get() { "Looking..." } else { "More like this list" }}
+                                </button>
+                                <button type="button" style=button_style on:click=on_download>"Download JSON"</button>
+                                <button type="button" style=button_style on:click=on_share>"Share code"</button>
+                                <button type="button" style=small_button on:click=on_delete>"Delete list"</button>
+                            </div>
+                        }
+                    })}
+
+                    {move || (!share_code.get().is_empty()).then(|| view! {
+                        <p style="opacity: 0.6; font-size: 0.9rem;">"Anyone can paste this into their own GlassBox to get a copy of the list."</p>
+                        <textarea rows="3" style=input_style readonly=true prop:value=share_code></textarea>
+                    })}
+
+                    {move || {
+                        let found = suggestions.get();
+                        (!found.is_empty()).then(|| view! {
+                            <h3>"More like this list"</h3>
+                            {found.into_iter().map(|v| {
+                                let to_add = v.clone();
+                                view! {
+                                    <div style=row_style>
+                                        <img src=v.thumbnail_url.clone() style="width: 40px; height: 56px; object-fit: cover; border-radius: 4px;" />
+                                        <span style="flex: 1;">{v.title.clone()}</span>
+                                        <span style="color: #777; font-size: 0.85rem;">{v.genres.join(", ")}</span>
+                                        <button type="button" style=small_button on:click=move |_| on_add_suggestion(to_add.clone())>"Add"</button>
+                                    </div>
+                                }
+                            }).collect::<Vec<_>>()}
+                        })
+                    }}
+                </VaultGate>
+            </div>
+        </MainCard>
+    }
+}
@@ -1,5 +1,7 @@
+use crate::model::history::{apply_retention, compact_interactions};
 use crate::model::session::SessionState;
 use crate::model::users::login_user;
+use crate::model::vault::unlock;
 use leptos::prelude::*;
 use leptos_meta::{Script, Stylesheet};
 use leptos_router::hooks::use_navigate;
@@ -29,14 +31,31 @@ pub fn Login() -> impl IntoView {
         let u_for_login = u_val.clone();
 
         // Clone environment for async block
-        let sess = session.clone();
+        let sess = session;
         let nav = navigate.clone();
-        let setter = set_error.clone();
-        let loader = set_is_loading.clone();
+        let setter = set_error;
+        let loader = set_is_loading;
 
         leptos::task::spawn_local(async move {
-            match login_user(u_for_login, p_val).await {
+            match login_user(u_for_login, p_val.clone()).await {
                 Ok(user) => {
+                    // 0. Derive the vault key while we still have the password
+                    if let Err(e) = unlock(&user, &p_val).await {
+                        leptos::logging::log!("LOGIN PAGE: Vault error: {:?}", e);
+                        setter.set(Some(e.to_string()));
+                        loader.set(false);
+                        return;
+                    }
+                    // 0b. Retention policy runs on every login
+                    if let Some(id) = user.id.clone() {
+                        if let Err(e) = apply_retention(id.clone()).await {
+                            leptos::logging::error!("LOGIN PAGE: Retention error: {:?}", e);
+                        }
+                        // ...and so does compaction of repeated clicks
+                        if let Err(e) = compact_interactions(id).await {
+                            leptos::logging::error!("LOGIN PAGE: Compaction error: {:?}", e);
+                        }
+                    }
                     leptos::logging::log!("LOGIN PAGE: Success, redirecting...");
                     // 1. Update Session with full User object
                     sess.login(user);
@@ -44,8 +63,8 @@ pub fn Login() -> impl IntoView {
                     nav("/", Default::default());
                 }
                 Err(e) => {
-                    leptos::logging::log!("LOGIN PAGE: Error: {}", e);
-                    setter.set(Some(e));
+                    leptos::logging::log!("LOGIN PAGE: Error: {:?}", e);
+                    setter.set(Some(e.to_string()));
                 }
             }
             loader.set(false);
@@ -1,3 +1,11 @@
+pub mod backup;
+pub mod episodes;
+pub mod history;
 pub mod home;
+pub mod import;
+pub mod library;
+pub mod lists;
 pub mod login;
+pub mod profile;
+pub mod settings;
 pub mod signup;
\ No newline at end of file
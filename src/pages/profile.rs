@@ -0,0 +1,80 @@
+use crate::cards::main_card::MainCard;
+use crate::components::vault_gate::VaultGate;
+use crate::model::history::{get_searches, SearchEntry};
+use crate::model::queries::{frequent_queries, top_terms};
+use crate::model::session::SessionState;
+use leptos::prelude::*;
+use leptos_router::components::A;
+
+const FREQUENT_LIMIT: usize = 15;
+const TERM_LIMIT: usize = 12;
+
+#[component]
+pub fn Profile() -> impl IntoView {
+    let session = use_context::<SessionState>().expect("SessionState not found");
+    let get_session = session.1;
+
+    let searches = RwSignal::new(Vec::<SearchEntry>::new());
+    let (status_msg, set_status) = signal(Option::<String>::None);
+
+    Effect::new(move |_| {
+        let Some(user) = get_session.get().user_thing() else {
+            return;
+        };
+        leptos::task::spawn_local(async move {
+            match get_searches(user).await {
+                Ok(list) => searches.set(list),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+        });
+    });
+
+    let small_button = "background: transparent; border: 1px solid rgba(255,255,255,0.2); border-radius: 6px; padding: 4px 10px; color: #ccc; cursor: pointer; text-decoration: none;";
+    let row_style = "display: flex; align-items: center; gap: 10px; padding: 6px 0; border-bottom: 1px solid rgba(255,255,255,0.05);";
+    let chip_style = "border: 1px solid rgba(255,255,255,0.2); border-radius: 20px; padding: 4px 12px; color: #ccc;";
+
+    let when = |ts: &str| ts.get(..16).unwrap_or(ts).replace('T', " ");
+
+    view! {
+        <MainCard>
+            <div style="max-width: 700px; margin: 0 auto; display: flex; flex-direction: column; gap: 15px;">
+                <h1>{move || format!("{}'s profile", get_session.get().username.unwrap_or_default())}</h1>
+                <VaultGate>
+
+                    {move || status_msg.get().map(|msg| view! {
+                        <p style="color: #aaa; padding: 10px; background: rgba(0,0,0,0.3); border-radius: 8px;">{msg}</p>
+                    })}
+
+                    {move || {
+                        let list = searches.get();
+                        let distinct = frequent_queries(&list).len();
+                        view! {
+                            <p style="opacity: 0.8;">{format!("{} searches, {} different queries.", list.len(), distinct)}</p>
+                        }
+                    }}
+
+                    <h3>"Frequent searches"</h3>
+                    {move || frequent_queries(&searches.get()).into_iter().take(FREQUENT_LIMIT).map(|stat| {
+                        let href = format!("/?q={}", String::from(js_sys::encode_uri_component(&stat.query)));
+                        view! {
+                            <div style=row_style>
+                                <span style="flex: 1;">{stat.query.clone()}</span>
+                                <span style="color: #aaa;">{format!("×{}", stat.count)}</span>
+                                <span style="color: #777; font-size: 0.85rem;">{when(&stat.last_at)}</span>
+                                <A href=href attr:style=small_button>"Search again"</A>
+                            </div>
+                        }
+                    }).collect::<Vec<_>>()}
+
+                    <h3>"Words you search for"</h3>
+                    <p style="opacity: 0.6; font-size: 0.9rem;">"Shows matching these get a small boost in your feed (see the search weight in Settings)."</p>
+                    <div style="display: flex; flex-wrap: wrap; gap: 8px;">
+                        {move || top_terms(&searches.get()).into_iter().take(TERM_LIMIT).map(|(term, n)| view! {
+                            <span style=chip_style>{format!("{} ({})", term, n)}</span>
+                        }).collect::<Vec<_>>()}
+                    </div>
+                </VaultGate>
+            </div>
+        </MainCard>
+    }
+}
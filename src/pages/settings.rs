@@ -0,0 +1,824 @@
+use crate::api::search::search_videos;
+use crate::cards::main_card::MainCard;
+use crate::model::archive::{download_archive, import_archive, Archive, ImportMode};
+use crate::model::config::{load_config, save_config, RecommenderConfig};
+use crate::model::error::GlassBoxError;
+use crate::model::history::{get_searches, get_user_library};
+use crate::model::notes::get_notes;
+use crate::model::session::SessionState;
+use crate::model::storage::{format_bytes, request_persistence, storage_status, StorageStatus};
+use crate::model::svd::SVD;
+use crate::model::trending::{get_trending, is_trending_query};
+use crate::model::tuning::{describe, evaluate_popularity, grid_search, random_search, temporal_split, Metric, SearchSpace, Signals, TrialResult};
+use crate::model::upload::read_selected_file;
+use crate::model::users::{change_password, delete_account};
+use crate::model::vault::is_unlocked;
+use crate::model::video::Video;
+use crate::model::watchlist::get_watchlist;
+use leptos::prelude::*;
+use leptos_router::components::A;
+
+#[component]
+pub fn Settings() -> impl IntoView {
+    let session = use_context::<SessionState>().expect("SessionState not found");
+    let get_session = session.1;
+
+    // Form fields are kept as text so half-typed numbers don't get rejected
+    let (multipliers, set_multipliers) = signal(String::new());
+    let (default_weight, set_default_weight) = signal(String::new());
+    let (fallback_query, set_fallback_query) = signal(String::new());
+    let (candidate_genres, set_candidate_genres) = signal(String::new());
+    let (session_blend, set_session_blend) = signal(String::new());
+    let (session_gap, set_session_gap) = signal(String::new());
+    let (people_weight, set_people_weight) = signal(String::new());
+    let (graph_seeds, set_graph_seeds) = signal(String::new());
+    let (graph_restart, set_graph_restart) = signal(String::new());
+    let (graph_candidates, set_graph_candidates) = signal(String::new());
+    let (click_weight, set_click_weight) = signal(String::new());
+    let (want_weight, set_want_weight) = signal(String::new());
+    let (watching_weight, set_watching_weight) = signal(String::new());
+    let (watched_weight, set_watched_weight) = signal(String::new());
+    let (dropped_weight, set_dropped_weight) = signal(String::new());
+    let (abandon_days, set_abandon_days) = signal(String::new());
+    let (tag_weight, set_tag_weight) = signal(String::new());
+    let (query_weight, set_query_weight) = signal(String::new());
+    let (json_text, set_json_text) = signal(String::new());
+    let (status_msg, set_status) = signal(Option::<String>::None);
+
+    // Auto-tune state
+    let (metric, set_metric) = signal(Metric::default());
+    let (use_random, set_use_random) = signal(false);
+    let (tuning, set_tuning) = signal(false);
+    let (trials, set_trials) = signal(Vec::<TrialResult>::new());
+
+    // Archive import state
+    let (archive_file, set_archive_file) = signal(Option::<(String, String)>::None);
+    let (import_mode, set_import_mode) = signal(ImportMode::Merge);
+    let (importing, set_importing) = signal(false);
+
+    // Password change state
+    let (old_password, set_old_password) = signal(String::new());
+    let (new_password, set_new_password) = signal(String::new());
+    let (rekeying, set_rekeying) = signal(false);
+
+    // Account deletion
+    let (delete_password, set_delete_password) = signal(String::new());
+
+    // Browser storage (see storage.rs)
+    let storage = RwSignal::new(Option::<StorageStatus>::None);
+    let load_storage = move || {
+        leptos::task::spawn_local(async move {
+            match storage_status().await {
+                Ok(s) => storage.set(Some(s)),
+                Err(e) => leptos::logging::error!("STORAGE: Estimate error: {:?}", e),
+            }
+        });
+    };
+    load_storage();
+
+    let fill_form = move |config: &RecommenderConfig| {
+        set_multipliers.set(
+            config
+                .recency_multipliers
+                .iter()
+                .map(|m| m.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+        set_default_weight.set(config.default_weight.to_string());
+        set_fallback_query.set(config.fallback_query.clone());
+        set_candidate_genres.set(config.candidate_genres.to_string());
+        set_session_blend.set(config.session_blend.to_string());
+        set_session_gap.set(config.session_gap_minutes.to_string());
+        set_people_weight.set(config.people_weight.to_string());
+        set_graph_seeds.set(config.graph_seeds.to_string());
+        set_graph_restart.set(config.graph_restart.to_string());
+        set_graph_candidates.set(config.graph_candidates.to_string());
+        set_click_weight.set(config.click_weight.to_string());
+        set_want_weight.set(config.want_weight.to_string());
+        set_watching_weight.set(config.watching_weight.to_string());
+        set_watched_weight.set(config.watched_weight.to_string());
+        set_dropped_weight.set(config.dropped_weight.to_string());
+        set_abandon_days.set(config.abandon_days.to_string());
+        set_tag_weight.set(config.tag_weight.to_string());
+        set_query_weight.set(config.query_weight.to_string());
+        set_json_text.set(config.to_json().unwrap_or_default());
+    };
+
+    // Load the saved config once the user is known
+    Effect::new(move |_| {
+        let Some(uid_str) = get_session.get().user_id else {
+            return;
+        };
+        leptos::task::spawn_local(async move {
+            let Ok(thing) = surrealdb::sql::thing(&uid_str) else {
+                return;
+            };
+            match load_config(thing).await {
+                Ok(config) => fill_form(&config),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+        });
+    });
+
+    let read_form = move || -> Result<RecommenderConfig, GlassBoxError> {
+        let recency_multipliers = multipliers
+            .get()
+            .split(',')
+            .map(|m| m.trim())
+            .filter(|m| !m.is_empty())
+            .map(|m| m.parse::<f64>().map_err(|_| GlassBoxError::Validation(format!("'{}' is not a number", m))))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let config = RecommenderConfig {
+            recency_multipliers,
+            default_weight: default_weight
+                .get()
+                .trim()
+                .parse()
+                .map_err(|_| GlassBoxError::Validation("Default weight must be a number".to_string()))?,
+            fallback_query: fallback_query.get().trim().to_string(),
+            candidate_genres: candidate_genres
+                .get()
+                .trim()
+                .parse()
+                .map_err(|_| GlassBoxError::Validation("Candidate genres must be a whole number".to_string()))?,
+            session_blend: session_blend
+                .get()
+                .trim()
+                .parse()
+                .map_err(|_| GlassBoxError::Validation("Session blend must be a number".to_string()))?,
+            session_gap_minutes: session_gap
+                .get()
+                .trim()
+                .parse()
+                .map_err(|_| GlassBoxError::Validation("Session gap must be a whole number of minutes".to_string()))?,
+            people_weight: people_weight
+                .get()
+                .trim()
+                .parse()
+                .map_err(|_| GlassBoxError::Validation("People weight must be a number".to_string()))?,
+            graph_seeds: graph_seeds
+                .get()
+                .trim()
+                .parse()
+                .map_err(|_| GlassBoxError::Validation("Graph seeds must be a whole number".to_string()))?,
+            graph_restart: graph_restart
+                .get()
+                .trim()
+                .parse()
+                .map_err(|_| GlassBoxError::Validation("Graph restart must be a number".to_string()))?,
+            graph_candidates: graph_candidates
+                .get()
+                .trim()
+                .parse()
+                .map_err(|_| GlassBoxError::Validation("Graph candidates must be a whole number".to_string()))?,
+            click_weight: click_weight
+                .get()
+                .trim()
+                .parse()
+                .map_err(|_| GlassBoxError::Validation("Click weight must be a number".to_string()))?,
+            want_weight: want_weight
+                .get()
+                .trim()
+                .parse()
+                .map_err(|_| GlassBoxError::Validation("Want to Watch weight must be a number".to_string()))?,
+            watching_weight: watching_weight
+                .get()
+                .trim()
+                .parse()
+                .map_err(|_| GlassBoxError::Validation("Watching weight must be a number".to_string()))?,
+            watched_weight: watched_weight
+                .get()
+                .trim()
+                .parse()
+                .map_err(|_| GlassBoxError::Validation("Watched weight must be a number".to_string()))?,
+            dropped_weight: dropped_weight
+                .get()
+                .trim()
+                .parse()
+                .map_err(|_| GlassBoxError::Validation("Dropped weight must be a number".to_string()))?,
+            abandon_days: abandon_days
+                .get()
+                .trim()
+                .parse()
+                .map_err(|_| GlassBoxError::Validation("Abandon days must be a whole number".to_string()))?,
+            tag_weight: tag_weight
+                .get()
+                .trim()
+                .parse()
+                .map_err(|_| GlassBoxError::Validation("Tag weight must be a number".to_string()))?,
+            query_weight: query_weight
+                .get()
+                .trim()
+                .parse()
+                .map_err(|_| GlassBoxError::Validation("Search weight must be a number".to_string()))?,
+        };
+        config.validate()?;
+        Ok(config)
+    };
+
+    let persist = move |config: RecommenderConfig| {
+        let Some(uid_str) = get_session.get().user_id else {
+            set_status.set(Some("ERROR: Please login first".to_string()));
+            return;
+        };
+        leptos::task::spawn_local(async move {
+            let Ok(thing) = surrealdb::sql::thing(&uid_str) else {
+                return;
+            };
+            match save_config(thing, config).await {
+                Ok(saved) => {
+                    fill_form(&saved);
+                    set_status.set(Some("Settings saved.".to_string()));
+                }
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+        });
+    };
+
+    let on_save = move |_| match read_form() {
+        Ok(config) => persist(config),
+        Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+    };
+
+    let on_import_json = move |_| match RecommenderConfig::from_json(&json_text.get()) {
+        Ok(config) => persist(config),
+        Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+    };
+
+    let on_reset = move |_| persist(RecommenderConfig::default());
+
+    let on_export = move |_| {
+        let Some(uid_str) = get_session.get().user_id else {
+            set_status.set(Some("ERROR: Please login first".to_string()));
+            return;
+        };
+        leptos::task::spawn_local(async move {
+            let Ok(thing) = surrealdb::sql::thing(&uid_str) else {
+                return;
+            };
+            match download_archive(thing).await {
+                Ok(archive) => set_status.set(Some(format!(
+                    "Exported {} titles, {} interactions, {} searches and {} ratings to {}.",
+                    archive.library.len(),
+                    archive.interactions.len(),
+                    archive.searches.len(),
+                    archive.ratings.len(),
+                    archive.filename()
+                ))),
+                Err(e) => {
+                    leptos::logging::error!("EXPORT: {:?}", e);
+                    set_status.set(Some(format!("ERROR: {}", e)));
+                }
+            }
+        });
+    };
+
+    let on_persist = move |_| {
+        leptos::task::spawn_local(async move {
+            match request_persistence().await {
+                Ok(true) => set_status.set(Some("This browser will now keep your data.".to_string())),
+                Ok(false) => set_status.set(Some(
+                    "The browser declined. It may still clear your data when space runs low, so keep a backup.".to_string(),
+                )),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+            load_storage();
+        });
+    };
+
+    let on_pick_archive = move |ev: web_sys::Event| {
+        leptos::task::spawn_local(async move {
+            match read_selected_file(&ev).await {
+                Ok(picked) => set_archive_file.set(picked),
+                Err(e) => set_status.set(Some(format!("ERROR: {}", e))),
+            }
+        });
+    };
+
+    let on_import_archive = move |_| {
+        let Some(uid_str) = get_session.get().user_id else {
+            set_status.set(Some("ERROR: Please login first".to_string()));
+            return;
+        };
+        // Imported rows are sealed on the way in; a reload leaves the key behind
+        if !is_unlocked(&uid_str) {
+            set_status.set(Some(format!("ERROR: {}", GlassBoxError::VaultLocked)));
+            return;
+        }
+        let Some((name, text)) = archive_file.get() else {
+            set_status.set(Some("ERROR: Choose an archive file first".to_string()));
+            return;
+        };
+        let archive = match Archive::from_json(&text) {
+            Ok(a) => a,
+            Err(e) => {
+                set_status.set(Some(format!("ERROR: {}", e)));
+                return;
+            }
+        };
+
+        let mode = import_mode.get();
+        if mode == ImportMode::Replace {
+            let confirmed = web_sys::window()
+                .and_then(|w| w.confirm_with_message("Replace your history, searches, ratings and settings with this archive?").ok())
+                .unwrap_or(false);
+            if !confirmed {
+                return;
+            }
+        }
+
+        set_importing.set(true);
+        leptos::task::spawn_local(async move {
+            let Ok(thing) = surrealdb::sql::thing(&uid_str) else {
+                set_importing.set(false);
+                return;
+            };
+            match import_archive(thing.clone(), &archive, mode).await {
+                Ok(report) => {
+                    set_status.set(Some(format!(
+                        "Imported {} from {}'s archive: {} titles, {} interactions, {} searches, {} ratings, {} watch states, {} shows' episode progress, {} lists, {} notes{} ({} skipped).",
+                        name,
+                        archive.profile.username,
+                        report.library,
+                        report.interactions,
+                        report.searches,
+                        report.ratings,
+                        report.watchlist,
+                        report.progress,
+                        report.lists,
+                        report.notes,
+                        if report.settings { " and settings" } else { "" },
+                        report.skipped
+                    )));
+                    if report.settings {
+                        if let Ok(config) = load_config(thing).await {
+                            fill_form(&config);
+                        }
+                    }
+                }
+                Err(e) => {
+                    leptos::logging::error!("IMPORT: {:?}", e);
+                    set_status.set(Some(format!("ERROR: {}", e)));
+                }
+            }
+            set_importing.set(false);
+        });
+    };
+
+    let on_change_password = move |_| {
+        let Some(uid_str) = get_session.get().user_id else {
+            set_status.set(Some("ERROR: Please login first".to_string()));
+            return;
+        };
+        let (old, new) = (old_password.get(), new_password.get());
+        set_rekeying.set(true);
+
+        leptos::task::spawn_local(async move {
+            let Ok(thing) = surrealdb::sql::thing(&uid_str) else {
+                set_rekeying.set(false);
+                return;
+            };
+            match change_password(thing, old, new).await {
+                Ok(()) => {
+                    set_old_password.set(String::new());
+                    set_new_password.set(String::new());
+                    set_status.set(Some("Password changed. Your history is now encrypted with the new one.".to_string()));
+                }
+                Err(e) => {
+                    leptos::logging::error!("PASSWORD: {:?}", e);
+                    set_status.set(Some(format!("ERROR: {}", e)));
+                }
+            }
+            set_rekeying.set(false);
+        });
+    };
+
+    let on_delete_account = move |_| {
+        let Some(uid_str) = get_session.get().user_id else {
+            set_status.set(Some("ERROR: Please login first".to_string()));
+            return;
+        };
+        let confirmed = web_sys::window()
+            .and_then(|w| w.confirm_with_message("Delete your account and everything GlassBox stored for it? This cannot be undone.").ok())
+            .unwrap_or(false);
+        if !confirmed {
+            return;
+        }
+        let password = delete_password.get();
+
+        leptos::task::spawn_local(async move {
+            let Ok(thing) = surrealdb::sql::thing(&uid_str) else {
+                return;
+            };
+            match delete_account(thing, password).await {
+                Ok(report) => {
+                    session.clear();
+                    set_delete_password.set(String::new());
+                    set_status.set(Some(format!(
+                        "Account '{}' deleted: {} library titles, {} interactions, {} searches, {} ratings, {} watch states, {} shows' episode progress, {} lists, {} notes{}. You have been signed out.",
+                        report.username,
+                        report.library,
+                        report.interactions,
+                        report.searches,
+                        report.ratings,
+                        report.watchlist,
+                        report.progress,
+                        report.lists,
+                        report.notes,
+                        if report.settings { " and your settings" } else { "" }
+                    )));
+                }
+                Err(e) => {
+                    leptos::logging::error!("ACCOUNT: {:?}", e);
+                    set_status.set(Some(format!("ERROR: {}", e)));
+                }
+            }
+        });
+    };
+
+    // Replays the library on a temporal split and ranks configs by the chosen metric
+    let on_tune = move |_| {
+        let base = match read_form() {
+            Ok(config) => config,
+            Err(e) => {
+                set_status.set(Some(format!("ERROR: {}", e)));
+                return;
+            }
+        };
+        let chosen_metric = metric.get();
+        let random = use_random.get();
+        let Some(user) = get_session.get().user_thing() else {
+            set_status.set(Some("ERROR: Please login first".to_string()));
+            return;
+        };
+        set_tuning.set(true);
+        set_status.set(None);
+
+        leptos::task::spawn_local(async move {
+            let library: Vec<Video> = match get_user_library(user.clone()).await {
+                Ok(l) => l,
+                Err(e) => {
+                    set_status.set(Some(format!("ERROR: {}", e)));
+                    set_tuning.set(false);
+                    return;
+                }
+            };
+
+            let signals = match (get_watchlist(user.clone()).await, get_notes(user.clone()).await, get_searches(user).await) {
+                (Ok(watchlist), Ok(notes), Ok(searches)) => Signals { watchlist, notes, searches },
+                (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+                    set_status.set(Some(format!("ERROR: {}", e)));
+                    set_tuning.set(false);
+                    return;
+                }
+            };
+
+            let Some(split) = temporal_split(&library, 0.2).map(|split| split.with_signals(signals)) else {
+                set_status.set(Some("ERROR: Save at least two titles before tuning.".to_string()));
+                set_tuning.set(false);
+                return;
+            };
+
+            // Pool = held-out titles + distractors for the training profile's genres
+            let mut pool = split.test.clone();
+            let profile = SVD::user_vector(&split.train, &base);
+            let wide = RecommenderConfig { candidate_genres: 3, ..base.clone() };
+            for genre in SVD::get_top_genres(&profile, &wide) {
+                let found = if is_trending_query(&genre) {
+                    get_trending(40).await
+                } else {
+                    search_videos(&genre).await
+                };
+                if let Ok(found) = found {
+                    for v in found {
+                        if !pool.iter().any(|p| p.video_id == v.video_id) {
+                            pool.push(v);
+                        }
+                    }
+                }
+            }
+
+            let space = SearchSpace::default();
+            let results = if random {
+                let seed = chrono::Utc::now().timestamp_millis() as u64;
+                random_search(&base, &space, &split, &pool, chosen_metric, 10, 30, seed)
+            } else {
+                grid_search(&base, &space, &split, &pool, chosen_metric, 10)
+            };
+
+            let baseline = evaluate_popularity(&split, &pool, chosen_metric, 10);
+
+            if let Some(best) = results.first() {
+                set_json_text.set(best.config.to_json().unwrap_or_default());
+                set_status.set(Some(format!(
+                    "Best {} = {:.3} (popularity baseline {:.3}). Its JSON is below, press \"Import JSON\" to make it your default.",
+                    chosen_metric.label(),
+                    best.score,
+                    baseline
+                )));
+            }
+            set_trials.set(results.into_iter().take(5).collect());
+            set_tuning.set(false);
+        });
+    };
+
+    let input_style = "background: rgba(0,0,0,0.2); border: 1px solid rgba(255,255,255,0.1); border-radius: 8px; padding: 8px; color: white;";
+    let button_style = "background: #646cff; border: none; border-radius: 8px; padding: 10px 16px; color: white; cursor: pointer;";
+
+    view! {
+        <MainCard>
+            <div style="max-width: 700px; margin: 0 auto; display: flex; flex-direction: column; gap: 15px;">
+                <h1>"Recommender Settings"</h1>
+                <p style="opacity: 0.8;">"Every knob the engine uses to build your feed. Nothing here leaves your browser."</p>
+
+                {move || status_msg.get().map(|msg| view! {
+                    <p style="color: #aaa; padding: 10px; background: rgba(0,0,0,0.3); border-radius: 8px;">{msg}</p>
+                })}
+
+                <label>"Recency multipliers (newest first)"</label>
+                <input
+                    type="text"
+                    style=input_style
+                    prop:value=multipliers
+                    on:input=move |ev| set_multipliers.set(event_target_value(&ev))
+                />
+
+                <label>"Default weight for unrated titles"</label>
+                <input
+                    type="text"
+                    style=input_style
+                    prop:value=default_weight
+                    on:input=move |ev| set_default_weight.set(event_target_value(&ev))
+                />
+
+                <label>"Fallback query (used when no genre stands out)"</label>
+                <input
+                    type="text"
+                    style=input_style
+                    prop:value=fallback_query
+                    on:input=move |ev| set_fallback_query.set(event_target_value(&ev))
+                />
+
+                <label>"Number of top genres searched for candidates"</label>
+                <input
+                    type="text"
+                    style=input_style
+                    prop:value=candidate_genres
+                    on:input=move |ev| set_candidate_genres.set(event_target_value(&ev))
+                />
+
+                <label>"Session blend (0 = only long-term taste, 1 = only recent clicks)"</label>
+                <input
+                    type="text"
+                    style=input_style
+                    prop:value=session_blend
+                    on:input=move |ev| set_session_blend.set(event_target_value(&ev))
+                />
+
+                <label>"Minutes of inactivity that end a session"</label>
+                <input
+                    type="text"
+                    style=input_style
+                    prop:value=session_gap
+                    on:input=move |ev| set_session_gap.set(event_target_value(&ev))
+                />
+
+                <label>"Weight of shared cast & crew"</label>
+                <input
+                    type="text"
+                    style=input_style
+                    prop:value=people_weight
+                    on:input=move |ev| set_people_weight.set(event_target_value(&ev))
+                />
+
+                <label>"Library titles the related-shows walk starts from"</label>
+                <input
+                    type="text"
+                    style=input_style
+                    prop:value=graph_seeds
+                    on:input=move |ev| set_graph_seeds.set(event_target_value(&ev))
+                />
+
+                <label>"Restart probability of the related-shows walk"</label>
+                <input
+                    type="text"
+                    style=input_style
+                    prop:value=graph_restart
+                    on:input=move |ev| set_graph_restart.set(event_target_value(&ev))
+                />
+
+                <label>"Related shows added to the feed (0 = off)"</label>
+                <input
+                    type="text"
+                    style=input_style
+                    prop:value=graph_candidates
+                    on:input=move |ev| set_graph_candidates.set(event_target_value(&ev))
+                />
+
+                <label>"Weight of shows you only clicked or rated"</label>
+                <input
+                    type="text"
+                    style=input_style
+                    prop:value=click_weight
+                    on:input=move |ev| set_click_weight.set(event_target_value(&ev))
+                />
+
+                <label>"Weight of \"Want to Watch\""</label>
+                <input
+                    type="text"
+                    style=input_style
+                    prop:value=want_weight
+                    on:input=move |ev| set_want_weight.set(event_target_value(&ev))
+                />
+
+                <label>"Weight of \"Watching\""</label>
+                <input
+                    type="text"
+                    style=input_style
+                    prop:value=watching_weight
+                    on:input=move |ev| set_watching_weight.set(event_target_value(&ev))
+                />
+
+                <label>"Weight of \"Watched\""</label>
+                <input
+                    type="text"
+                    style=input_style
+                    prop:value=watched_weight
+                    on:input=move |ev| set_watched_weight.set(event_target_value(&ev))
+                />
+
+                <label>"Weight of \"Dropped\" (0 = ignore)"</label>
+                <input
+                    type="text"
+                    style=input_style
+                    prop:value=dropped_weight
+                    on:input=move |ev| set_dropped_weight.set(event_target_value(&ev))
+                />
+
+                <label>"Days without a ticked episode before a show counts as abandoned"</label>
+                <input
+                    type="text"
+                    style=input_style
+                    prop:value=abandon_days
+                    on:input=move |ev| set_abandon_days.set(event_target_value(&ev))
+                />
+
+                <label>"Extra score for resembling shows you tagged (0 = ignore tags)"</label>
+                <input
+                    type="text"
+                    style=input_style
+                    prop:value=tag_weight
+                    on:input=move |ev| set_tag_weight.set(event_target_value(&ev))
+                />
+
+                <label>"Extra score for matching words you search for (0 = ignore searches)"</label>
+                <input
+                    type="text"
+                    style=input_style
+                    prop:value=query_weight
+                    on:input=move |ev| set_query_weight.set(event_target_value(&ev))
+                />
+
+                <div style="display: flex; gap: 10px;">
+                    <button type="button" style=button_style on:click=on_save>"Save"</button>
+                    <button type="button" style=button_style on:click=on_reset>"Reset to defaults"</button>
+                </div>
+
+                <h3 style="margin-top: 20px;">"Config as JSON"</h3>
+                <p style="opacity: 0.6; font-size: 0.9rem;">"Copy this into a file for offline evaluation, or paste a tuned config and import it."</p>
+                <textarea
+                    rows="10"
+                    style=input_style
+                    prop:value=json_text
+                    on:input=move |ev| set_json_text.set(event_target_value(&ev))
+                ></textarea>
+                <button type="button" style=button_style on:click=on_import_json>"Import JSON"</button>
+
+                <h3 style="margin-top: 20px;">"Auto-tune on my history"</h3>
+                <p style="opacity: 0.6; font-size: 0.9rem;">"Older titles build the profile, your newest 20% are held out and the configs are ranked by how high they put them."</p>
+                <div style="display: flex; gap: 10px; align-items: center;">
+                    <select
+                        style=input_style
+                        on:change=move |ev| {
+                            set_metric.set(match event_target_value(&ev).as_str() {
+                                "hit" => Metric::HitRate,
+                                "mrr" => Metric::Mrr,
+                                _ => Metric::Ndcg,
+                            })
+                        }
+                    >
+                        <option value="ndcg">"NDCG@10"</option>
+                        <option value="hit">"HitRate@10"</option>
+                        <option value="mrr">"MRR"</option>
+                    </select>
+                    <select
+                        style=input_style
+                        on:change=move |ev| set_use_random.set(event_target_value(&ev) == "random")
+                    >
+                        <option value="grid">"Grid search"</option>
+                        <option value="random">"Random search"</option>
+                    </select>
+                    <button type="button" style=button_style on:click=on_tune disabled=move || tuning.get()>
+                        {move || if tuning.get() { "Tuning..." } else { "Run" }}
+                    </button>
+                </div>
+
+                {move || {
+                    trials.get().into_iter().map(|t| view! {
+                        <div style="font-size: 0.85rem; color: #aaa; font-family: monospace;">
+                            {format!("{:.3}  {}", t.score, describe(&t.config))}
+                        </div>
+                    }).collect::<Vec<_>>()
+                }}
+
+                <h3 style="margin-top: 20px;">"Password"</h3>
+                <p style="opacity: 0.6; font-size: 0.9rem;">"Your history is encrypted with a key made from your password, so changing it re-encrypts everything."</p>
+                <input
+                    type="password"
+                    placeholder="Current password"
+                    style=input_style
+                    prop:value=old_password
+                    on:input=move |ev| set_old_password.set(event_target_value(&ev))
+                />
+                <input
+                    type="password"
+                    placeholder="New password"
+                    style=input_style
+                    prop:value=new_password
+                    on:input=move |ev| set_new_password.set(event_target_value(&ev))
+                />
+                <button type="button" style=button_style on:click=on_change_password disabled=move || rekeying.get()>
+                    {move || if rekeying.get() { "Re-encrypting..." } else { "Change password" }}
+                </button>
+
+                <h3 style="margin-top: 20px;">"Storage"</h3>
+                {move || storage.get().map(|s| view! {
+                    <p style="opacity: 0.8;">{format!(
+                        "Using {} of {} ({:.1}%).",
+                        format_bytes(s.usage),
+                        format_bytes(s.quota),
+                        s.fraction() * 100.0
+                    )}</p>
+                    <div style="height: 8px; border-radius: 4px; background: rgba(255,255,255,0.1); overflow: hidden;">
+                        <div style={format!(
+                            "height: 100%; width: {:.1}%; background: {};",
+                            s.fraction() * 100.0,
+                            if s.near_quota() { "#ff6b6b" } else { "#646cff" }
+                        )}></div>
+                    </div>
+                    {s.near_quota().then(|| view! {
+                        <p style="color: #ff6b6b;">"Storage is nearly full. Export your data or download a backup now: the browser may start clearing it."</p>
+                        <div style="display: flex; gap: 10px; align-items: center;">
+                            <button type="button" style=button_style on:click=on_export>"Export my data"</button>
+                            <A href="/backup" attr:style="color: #646cff;">"Back up this browser"</A>
+                        </div>
+                    })}
+                    {if s.persisted {
+                        view! { <p style="opacity: 0.6; font-size: 0.9rem;">"Persistent: the browser will not clear this data on its own."</p> }.into_any()
+                    } else {
+                        view! {
+                            <p style="color: #ff6b6b; font-size: 0.9rem;">"Not persistent: the browser may clear everything here when space runs low."</p>
+                            <button type="button" style=button_style on:click=on_persist>"Ask to keep my data"</button>
+                        }.into_any()
+                    }}
+                })}
+
+                <h3 style="margin-top: 20px;">"Your data"</h3>
+                <p style="opacity: 0.6; font-size: 0.9rem;">"Download your profile, library, history, ratings and settings as one JSON file. Your password is never included."</p>
+                <button type="button" style=button_style on:click=on_export>"Export my data"</button>
+
+                <p style="opacity: 0.6; font-size: 0.9rem;">"Moving from another browser? Import an archive: merge keeps what is already here, replace starts over from the file."</p>
+                <div style="display: flex; gap: 10px; align-items: center; flex-wrap: wrap;">
+                    <input type="file" accept=".json,application/json" on:change=on_pick_archive />
+                    <select
+                        style=input_style
+                        on:change=move |ev| {
+                            set_import_mode.set(if event_target_value(&ev) == "replace" { ImportMode::Replace } else { ImportMode::Merge })
+                        }
+                    >
+                        <option value="merge">"Merge"</option>
+                        <option value="replace">"Replace"</option>
+                    </select>
+                    <button type="button" style=button_style on:click=on_import_archive disabled=move || importing.get()>
+                        {move || if importing.get() { "Importing..." } else { "Import archive" }}
+                    </button>
+                </div>
+
+                <h3 style="margin-top: 20px; color: #ff6b6b;">"Delete account"</h3>
+                <p style="opacity: 0.6; font-size: 0.9rem;">"Removes your account, history, searches, ratings and settings from this browser. Export your data first if you want to keep it."</p>
+                <input
+                    type="password"
+                    placeholder="Confirm with your password"
+                    style=input_style
+                    prop:value=delete_password
+                    on:input=move |ev| set_delete_password.set(event_target_value(&ev))
+                />
+                <button type="button" style="background: #c0392b; border: none; border-radius: 8px; padding: 10px 16px; color: white; cursor: pointer;" on:click=on_delete_account>
+                    "Delete my account"
+                </button>
+            </div>
+        </MainCard>
+    }
+}
@@ -36,7 +36,7 @@ pub fn Signup() -> impl IntoView {
                     set_password.set("".to_string());
 
                     // Redirect to login after a short delay to let user see success message
-                    let _ = leptos::task::spawn_local(async move {
+                    leptos::task::spawn_local(async move {
                         gloo_timers::future::sleep(std::time::Duration::from_millis(1500)).await;
                         nav("/login", Default::default());
                     });